@@ -0,0 +1,395 @@
+use crate::{Cli, OutputFormat, OutputShape, ARCH_VALUES, OS_VALUES};
+use clap::parser::ValueSource;
+use clap::ArgMatches;
+use loaderspot_core::Platform;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const CONFIG_FILE_NAME: &str = "loaderspot.toml";
+
+/// Settings read from `loaderspot.toml`. Keys mirror the long flag names with
+/// `_` instead of `-`; `os` is the `--platform` flag and `headers` the
+/// repeatable `--header`. The `[templates]` table
+/// overrides installer paths by platform code, e.g. `win64 = "win32-x86_64/
+/// spotify_installer-{version}-{number}.exe"`, and has no flag.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FileConfig {
+    pub range: Option<String>,
+    pub arch: Option<Vec<String>>,
+    pub os: Option<Vec<String>>,
+    pub connections: Option<usize>,
+    pub rps: Option<u32>,
+    pub jitter_ms: Option<u64>,
+    pub ramp: Option<bool>,
+    pub max_requests: Option<u64>,
+    pub budget: Option<u64>,
+    pub timeout: Option<u64>,
+    pub ladder_search: Option<bool>,
+    pub auto_range: Option<bool>,
+    pub smart_window: Option<i32>,
+    pub smart_increment: Option<i32>,
+    pub smart_iterations: Option<u32>,
+    pub version_concurrency: Option<usize>,
+    pub max_gap: Option<u32>,
+    pub step: Option<u32>,
+    pub min_size: Option<u64>,
+    pub require_content_type: Option<bool>,
+    pub try_dmg: Option<bool>,
+    pub verify: Option<bool>,
+    pub only_new: Option<bool>,
+    pub error_threshold: Option<u8>,
+    pub error_window: Option<usize>,
+    pub no_error_pause: Option<bool>,
+    pub output_format: Option<OutputFormat>,
+    pub output_shape: Option<OutputShape>,
+    pub strict_version: Option<bool>,
+    pub proxy: Option<String>,
+    pub webhook: Option<String>,
+    pub notify: Option<bool>,
+    pub local_address: Option<String>,
+    pub ipv4_only: Option<bool>,
+    pub ipv6_only: Option<bool>,
+    pub user_agent: Option<String>,
+    pub headers: Option<Vec<String>>,
+    pub no_follow: Option<bool>,
+    pub http2_prior_knowledge: Option<bool>,
+    pub no_compression: Option<bool>,
+    pub pool_max_idle_per_host: Option<usize>,
+    pub pool_idle_timeout: Option<u64>,
+    pub quiet: Option<bool>,
+    pub templates: HashMap<String, String>,
+}
+
+/// `--config` if given, otherwise the first `loaderspot.toml` found in the
+/// current directory or `~/.config/loaderspot/`.
+fn config_path(explicit: Option<&Path>) -> Option<PathBuf> {
+    if let Some(path) = explicit {
+        return Some(path.to_path_buf());
+    }
+
+    let local = PathBuf::from(CONFIG_FILE_NAME);
+    if local.is_file() {
+        return Some(local);
+    }
+
+    let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"))?;
+    let user = PathBuf::from(home)
+        .join(".config")
+        .join("loaderspot")
+        .join(CONFIG_FILE_NAME);
+    user.is_file().then_some(user)
+}
+
+fn load(path: &Path) -> Result<FileConfig, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| format!("Cannot read config {}: {}", path.display(), e))?;
+    let config: FileConfig =
+        toml::from_str(&text).map_err(|e| format!("Invalid config {}: {}", path.display(), e))?;
+
+    for (key, values, allowed) in [
+        ("arch", &config.arch, &ARCH_VALUES[..]),
+        ("os", &config.os, &OS_VALUES[..]),
+    ] {
+        for value in values.iter().flatten() {
+            if !allowed.contains(&value.as_str()) {
+                return Err(format!(
+                    "Invalid config {}: {} '{}' is not one of {}",
+                    path.display(),
+                    key,
+                    value,
+                    allowed.join(", ")
+                ));
+            }
+        }
+    }
+
+    Ok(config)
+}
+
+/// A file value only replaces an argument that was neither passed on the
+/// command line nor set through its environment variable.
+fn from_file(matches: &ArgMatches, id: &str) -> bool {
+    !matches!(
+        matches.value_source(id),
+        Some(ValueSource::CommandLine | ValueSource::EnvVariable)
+    )
+}
+
+/// Fills in `cli` from the config file: command line > environment > file > default.
+pub fn apply(cli: &mut Cli, matches: &ArgMatches) -> Result<(), String> {
+    let Some(path) = config_path(cli.config.as_deref()) else {
+        return Ok(());
+    };
+    let file = load(&path)?;
+
+    if let Some(range) = file.range.filter(|_| from_file(matches, "range")) {
+        cli.range = range;
+    }
+    if let Some(arch) = file.arch.filter(|_| from_file(matches, "arch")) {
+        cli.arch = arch;
+    }
+    if let Some(os) = file.os.filter(|_| from_file(matches, "os")) {
+        cli.platform = os;
+    }
+    if let Some(connections) = file
+        .connections
+        .filter(|_| from_file(matches, "connections"))
+    {
+        cli.connections = connections;
+    }
+    if let Some(rps) = file.rps.filter(|_| from_file(matches, "rps")) {
+        if rps == 0 {
+            return Err(format!(
+                "Invalid config {}: rps must be at least 1",
+                path.display()
+            ));
+        }
+        cli.rps = Some(rps);
+    }
+    if let Some(jitter_ms) = file.jitter_ms.filter(|_| from_file(matches, "jitter_ms")) {
+        cli.jitter_ms = jitter_ms;
+    }
+    if let Some(ramp) = file.ramp.filter(|_| from_file(matches, "ramp")) {
+        cli.ramp = ramp;
+    }
+    if let Some(max) = file
+        .max_requests
+        .filter(|_| from_file(matches, "max_requests"))
+    {
+        if max == 0 {
+            return Err(format!(
+                "Invalid config {}: max_requests must be at least 1",
+                path.display()
+            ));
+        }
+        cli.max_requests = max;
+    }
+    if let Some(budget) = file.budget.filter(|_| from_file(matches, "budget")) {
+        if budget == 0 {
+            return Err(format!(
+                "Invalid config {}: budget must be at least 1",
+                path.display()
+            ));
+        }
+        cli.budget = Some(budget);
+    }
+    if let Some(timeout) = file.timeout.filter(|_| from_file(matches, "timeout")) {
+        cli.timeout = timeout;
+    }
+    if let Some(ladder) = file
+        .ladder_search
+        .filter(|_| from_file(matches, "ladder_search"))
+    {
+        cli.ladder_search = ladder;
+    }
+    if let Some(auto_range) = file.auto_range.filter(|_| from_file(matches, "auto_range")) {
+        cli.auto_range = auto_range;
+    }
+    if cli.ladder_search && cli.auto_range {
+        return Err(format!(
+            "Invalid config {}: ladder_search and auto_range exclude each other",
+            path.display()
+        ));
+    }
+    if let Some(window) = file
+        .smart_window
+        .filter(|_| from_file(matches, "smart_window"))
+    {
+        if window < 1 {
+            return Err(format!(
+                "Invalid config {}: smart_window must be at least 1",
+                path.display()
+            ));
+        }
+        cli.smart_window = window;
+    }
+    if let Some(increment) = file
+        .smart_increment
+        .filter(|_| from_file(matches, "smart_increment"))
+    {
+        if increment < 1 {
+            return Err(format!(
+                "Invalid config {}: smart_increment must be at least 1",
+                path.display()
+            ));
+        }
+        cli.smart_increment = increment;
+    }
+    if let Some(iterations) = file
+        .smart_iterations
+        .filter(|_| from_file(matches, "smart_iterations"))
+    {
+        cli.smart_iterations = iterations;
+    }
+    if let Some(n) = file
+        .version_concurrency
+        .filter(|_| from_file(matches, "version_concurrency"))
+    {
+        cli.version_concurrency = n;
+    }
+    if let Some(gap) = file.max_gap.filter(|_| from_file(matches, "max_gap")) {
+        if gap == 0 {
+            return Err(format!(
+                "Invalid config {}: max_gap must be at least 1",
+                path.display()
+            ));
+        }
+        cli.max_gap = Some(gap);
+    }
+    if let Some(step) = file.step.filter(|_| from_file(matches, "step")) {
+        if step == 0 {
+            return Err(format!(
+                "Invalid config {}: step must be at least 1",
+                path.display()
+            ));
+        }
+        cli.step = step;
+    }
+    if let Some(min_size) = file.min_size.filter(|_| from_file(matches, "min_size")) {
+        cli.min_size = Some(min_size);
+    }
+    if let Some(require) = file
+        .require_content_type
+        .filter(|_| from_file(matches, "require_content_type"))
+    {
+        cli.require_content_type = require;
+    }
+    if let Some(try_dmg) = file.try_dmg.filter(|_| from_file(matches, "try_dmg")) {
+        cli.try_dmg = try_dmg;
+    }
+    if let Some(verify) = file.verify.filter(|_| from_file(matches, "verify")) {
+        cli.verify = verify;
+    }
+    if let Some(only_new) = file.only_new.filter(|_| from_file(matches, "only_new")) {
+        cli.only_new = only_new;
+    }
+    if let Some(percent) = file
+        .error_threshold
+        .filter(|_| from_file(matches, "error_threshold"))
+    {
+        if !(1..=100).contains(&percent) {
+            return Err(format!(
+                "Invalid config {}: error_threshold must be between 1 and 100",
+                path.display()
+            ));
+        }
+        cli.error_threshold = percent;
+    }
+    if let Some(window) = file
+        .error_window
+        .filter(|_| from_file(matches, "error_window"))
+    {
+        if window < 1 {
+            return Err(format!(
+                "Invalid config {}: error_window must be at least 1",
+                path.display()
+            ));
+        }
+        cli.error_window = window;
+    }
+    if let Some(no_pause) = file
+        .no_error_pause
+        .filter(|_| from_file(matches, "no_error_pause"))
+    {
+        cli.no_error_pause = no_pause;
+    }
+    if let Some(format) = file
+        .output_format
+        .filter(|_| from_file(matches, "output_format"))
+    {
+        cli.output_format = Some(format);
+    }
+    if let Some(shape) = file
+        .output_shape
+        .filter(|_| from_file(matches, "output_shape"))
+    {
+        cli.output_shape = shape;
+    }
+    if let Some(strict) = file
+        .strict_version
+        .filter(|_| from_file(matches, "strict_version"))
+    {
+        cli.strict_version = strict;
+    }
+    if let Some(proxy) = file.proxy.filter(|_| from_file(matches, "proxy")) {
+        cli.proxy = Some(proxy);
+    }
+    if let Some(webhook) = file.webhook.filter(|_| from_file(matches, "webhook")) {
+        cli.webhook = Some(webhook);
+    }
+    if let Some(notify) = file.notify.filter(|_| from_file(matches, "notify")) {
+        cli.notify = notify;
+    }
+    if let Some(address) = file
+        .local_address
+        .filter(|_| from_file(matches, "local_address"))
+    {
+        cli.local_address = Some(address);
+    }
+    // The two flags are one setting: either one given outside the file wins.
+    if from_file(matches, "ipv4_only") && from_file(matches, "ipv6_only") {
+        if file.ipv4_only == Some(true) && file.ipv6_only == Some(true) {
+            return Err(format!(
+                "Invalid config {}: ipv4_only and ipv6_only cannot both be set",
+                path.display()
+            ));
+        }
+        cli.ipv4_only = file.ipv4_only.unwrap_or(cli.ipv4_only);
+        cli.ipv6_only = file.ipv6_only.unwrap_or(cli.ipv6_only);
+    }
+    if let Some(agent) = file.user_agent.filter(|_| from_file(matches, "user_agent")) {
+        cli.user_agent = Some(agent);
+    }
+    if let Some(headers) = file.headers.filter(|_| from_file(matches, "headers")) {
+        cli.headers = headers;
+    }
+    if let Some(no_follow) = file.no_follow.filter(|_| from_file(matches, "no_follow")) {
+        cli.no_follow = no_follow;
+    }
+    if let Some(h2) = file
+        .http2_prior_knowledge
+        .filter(|_| from_file(matches, "http2_prior_knowledge"))
+    {
+        cli.http2_prior_knowledge = h2;
+    }
+    if let Some(no_compression) = file
+        .no_compression
+        .filter(|_| from_file(matches, "no_compression"))
+    {
+        cli.no_compression = no_compression;
+    }
+    if let Some(max_idle) = file
+        .pool_max_idle_per_host
+        .filter(|_| from_file(matches, "pool_max_idle_per_host"))
+    {
+        cli.pool_max_idle_per_host = Some(max_idle);
+    }
+    if let Some(timeout) = file
+        .pool_idle_timeout
+        .filter(|_| from_file(matches, "pool_idle_timeout"))
+    {
+        cli.pool_idle_timeout = timeout;
+    }
+    if let Some(quiet) = file.quiet.filter(|_| from_file(matches, "quiet")) {
+        cli.quiet = quiet;
+    }
+    for (code, template) in &file.templates {
+        let Some(platform) = Platform::from_code(code) else {
+            let codes: Vec<&str> = Platform::all().iter().map(|p| p.code()).collect();
+            return Err(format!(
+                "Invalid config {}: unknown platform '{}' in [templates], expected one of {}",
+                path.display(),
+                code,
+                codes.join(", ")
+            ));
+        };
+        cli.templates
+            .set(platform, template)
+            .map_err(|e| format!("Invalid config {}: {}", path.display(), e))?;
+    }
+
+    Ok(())
+}
@@ -1,172 +1,618 @@
-use clap::Parser;
-use indicatif::{ProgressBar, ProgressStyle};
+mod config;
+
+use clap::{CommandFactory, FromArgMatches, Parser, ValueEnum};
+use clap_complete::Shell;
+use indicatif::{HumanDuration, ProgressBar, ProgressStyle};
+use loaderspot_core::{
+    count_builds, download_command, download_installer, estimate_search_time, expand_version_range,
+    fetch_versions_json, file_name_from_url, format_age, format_iso8601, generate_urls,
+    is_suspiciously_small, parse_build_ranges, should_use_win_x86, validate_version,
+    validate_version_strict, version_list_lines, CachedInstaller, CheckedBuilds, Checkpoint,
+    ClientOptions, ContentTypeCounts, Downloader, ErrorStorm, FailedChecks, FoundEvent,
+    FoundInstaller, IpFamily, Jitter, KnownVersions, LadderOptions, Manifest, PathTemplates,
+    Platform, Probe, Ramp, RateLimiter, Reachability, RequestBudget, ResultsCache, RunReport,
+    RunStats, SearchEngine, SearchParams, SearchedVersion, Verification, AUTO_RANGE_GAP, BASE_URL,
+    CONFIRM_DELAY, DEFAULT_MAX_REQUESTS, DEFAULT_POOL_IDLE_TIMEOUT, SUSPICIOUS_SIZE,
+};
+use regex::Regex;
 use reqwest::Client;
-use serde::Serialize;
-use std::collections::HashMap;
-use std::sync::Arc;
-use std::time::Duration;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::sync::Semaphore;
-use regex::Regex;
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
-enum PlatformArch {
-    WinX86,
-    WinX64,
-    WinArm64,
-    MacOsIntel,
-    MacOsArm64,
-}
 
-impl PlatformArch {
-    fn path_template(&self) -> &str {
-        match self {
-            PlatformArch::WinX86 => "win32-x86/spotify_installer-{version}-{number}.exe",
-            PlatformArch::WinX64 => "win32-x86_64/spotify_installer-{version}-{number}.exe",
-            PlatformArch::WinArm64 => "win32-arm64/spotify_installer-{version}-{number}.exe",
-            PlatformArch::MacOsIntel => "osx-x86_64/spotify-autoupdate-{version}-{number}.tbz",
-            PlatformArch::MacOsArm64 => "osx-arm64/spotify-autoupdate-{version}-{number}.tbz",
-        }
-    }
+/// Set by --quiet, before anything is printed.
+static QUIET: AtomicBool = AtomicBool::new(false);
 
-    fn to_string(&self) -> &str {
-        match self {
-            PlatformArch::WinX86 => "WIN32",
-            PlatformArch::WinX64 => "WIN64",
-            PlatformArch::WinArm64 => "WIN-ARM64",
-            PlatformArch::MacOsIntel => "OSX",
-            PlatformArch::MacOsArm64 => "OSX-ARM64",
+/// `eprintln!` for warnings and progress notes, which --quiet silences.
+/// Errors go through `error!`, which --quiet leaves alone.
+macro_rules! status {
+    ($($arg:tt)*) => {
+        if !QUIET.load(Ordering::Relaxed) {
+            eprintln!($($arg)*);
         }
-    }
+    };
 }
 
-struct UrlGenerator;
-
-impl UrlGenerator {
-    const BASE_URL: &'static str = "https://upgrade.scdn.co/upgrade/client/";
+/// Set by --json-errors, before anything is printed.
+static JSON_ERRORS: AtomicBool = AtomicBool::new(false);
 
-    fn generate_url(platform: PlatformArch, version: &str, number: i32) -> String {
-        let path = platform
-            .path_template()
-            .replace("{version}", version)
-            .replace("{number}", &number.to_string());
-        format!("{}{}", Self::BASE_URL, path)
+/// An error or warning on stderr: `Error: <message>`, or with --json-errors
+/// `{"level": "error", "code": "bad_range", "message": "..."}` on one line.
+/// Codes are stable, so scripts can match on them rather than the message.
+fn report_problem(level: &str, code: &str, message: &str) {
+    if JSON_ERRORS.load(Ordering::Relaxed) {
+        eprintln!(
+            "{}",
+            json!({ "level": level, "code": code, "message": message })
+        );
+    } else if level == "error" {
+        eprintln!("Error: {}", message);
+    } else {
+        eprintln!("Warning: {}", message);
     }
-
 }
 
-fn extract_base_version(version: &str) -> String {
-    let parts: Vec<&str> = version.split('.').collect();
-    if parts.len() >= 3 {
-        format!("{}.{}.{}", parts[0], parts[1], parts[2])
-    } else {
-        version.to_string()
-    }
+/// An error under a stable `code`; see [`report_problem`].
+macro_rules! error {
+    ($code:literal, $($arg:tt)*) => {
+        report_problem("error", $code, &format!($($arg)*))
+    };
 }
 
-fn should_use_win_x86(version: &str) -> bool {
-    let base_version = extract_base_version(version);
-    let parts: Vec<&str> = base_version.split('.').collect();
+/// Warnings so far, repeated in the `warnings` of JSON output.
+static WARNINGS: Mutex<Vec<String>> = Mutex::new(Vec::new());
 
-    if parts.len() >= 3 {
-        if let (Ok(major), Ok(minor), Ok(patch)) = (
-            parts[0].parse::<u32>(),
-            parts[1].parse::<u32>(),
-            parts[2].parse::<u32>(),
-        ) {
-            return (major, minor, patch) <= (1, 2, 53);
+/// A warning under a stable `code` that JSON output also carries; see
+/// [`report_problem`].
+macro_rules! warning {
+    ($code:literal, $($arg:tt)*) => {{
+        let message = format!($($arg)*);
+        if !QUIET.load(Ordering::Relaxed) {
+            report_problem("warning", $code, &message);
         }
-    }
-    true
+        WARNINGS.lock().unwrap().push(message);
+    }};
 }
 
-async fn check_url(client: &Client, url: String, platform: PlatformArch) -> Option<(String, PlatformArch)> {
-    match client.head(&url).send().await {
-        Ok(response) if response.status().is_success() => Some((url, platform)),
-        _ => None,
-    }
-}
+/// `schema_version` of the JSON envelope. Bump it on any change that can
+/// break a parser: a removed or renamed key, or a value of another type.
+const SCHEMA_VERSION: u32 = 2;
 
-async fn search_installers(
-    client: &Client,
-    version: &str,
-    start: i32,
-    end: i32,
-    platform: PlatformArch,
-    max_connections: usize,
-) -> Vec<(String, PlatformArch)> {
-    let semaphore = Arc::new(Semaphore::new(max_connections));
-    let mut tasks = Vec::new();
-    let found_urls = Arc::new(tokio::sync::Mutex::new(Vec::new()));
-
-    for number in start..=end {
-        let url = UrlGenerator::generate_url(platform, version, number);
-        let client = client.clone();
-        let permit = semaphore.clone().acquire_owned().await.unwrap();
-        let found_urls_clone = found_urls.clone();
-
-        let task = tokio::spawn(async move {
-            let result = check_url(&client, url, platform).await;
-            drop(permit);
-
-            if let Some(found_data) = result {
-                let mut guard = found_urls_clone.lock().await;
-                guard.push(found_data);
-            }
-        });
+const SOFT_MAX_CONNECTIONS: usize = 300;
+/// Installers are large; the 30s scan timeout would cut downloads short.
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+/// How long a search stays paused after the network seemed to go down.
+const NETWORK_DOWN_PAUSE: Duration = Duration::from_secs(30);
+/// How often --checkpoint saves the progress.
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(5);
+/// One line per installer in the webhook message. Plain markdown renders in
+/// both Discord and Slack.
+const WEBHOOK_LINE_TEMPLATE: &str = "`{platform}` {version} build {build}: {url}";
 
-        tasks.push(task);
-    }
+/// Exit code when the search ran to completion but found no installer.
+const EXIT_NOTHING_FOUND: i32 = 2;
+/// Exit code after Ctrl-C, as shells report for SIGINT.
+const EXIT_INTERRUPTED: i32 = 130;
+const AFTER_HELP: &str = "Settings are taken from, in order of precedence: command-line flags,
+LOADERSPOT_* environment variables, loaderspot.toml (--config, else the current
+directory, else ~/.config/loaderspot/), built-in defaults.
 
-    for task in tasks {
-        let _ = task.await;
-    }
+Exit codes:
+  0  at least one installer was found
+  1  error: bad arguments, network unavailable, failed download verification
+  2  the search completed but found nothing
+  130  interrupted with Ctrl-C; whatever was found so far is still printed,
+       a second Ctrl-C quits at once
 
-    let guard = found_urls.lock().await;
-    guard.clone()
+On Unix, Ctrl-Z (SIGTSTP) pauses a running search and resumes it again;
+SIGCONT resumes it too.
+
+Shell completion: --generate-completions bash|zsh|fish|powershell prints a script,
+e.g. for bash:
+  loaderspot_cli --generate-completions bash > ~/.local/share/bash-completion/completions/loaderspot_cli
+for zsh, save it as _loaderspot_cli in a directory on $fpath; for fish, as
+~/.config/fish/completions/loaderspot_cli.fish; for PowerShell, add it to $PROFILE.
+
+--json-errors codes:
+  errors    bad_arguments, bad_cache, bad_checkpoint, bad_config, bad_connections,
+            bad_ladder, bad_manifest, bad_network_settings, bad_range, bad_urls_file,
+            bad_versions, cache_not_saved, checkpoint_not_removed, checkpoint_not_saved,
+            download_failed, network_unavailable, no_platforms, no_urls, no_versions,
+            not_enough_versions, report_not_saved, too_many_requests, unreachable,
+            unsupported_format, verification_failed, versions_json_unavailable
+  warnings  auto_range_empty, budget_exhausted, cannot_pause, insecure, invalid_url,
+            invalid_version, many_connections, network_down, small_installer,
+            unconfirmed_hit, unexpected_content_type, webhook_failed, x86_unsupported";
+
+const ARCH_VALUES: [&str; 5] = ["x86", "x64", "arm64", "intel", "all"];
+const OS_VALUES: [&str; 3] = ["win", "mac", "all"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum OutputFormat {
+    /// `json`, indented
+    Pretty,
+    /// One object: {"schema_version", "versions", "results", "searched", "stats", "warnings"}
+    Json,
+    Ndjson,
+    /// Aligned columns for reading in a terminal
+    Table,
+    /// One `curl` command per installer, saving into --download if given
+    Curl,
+    /// One `wget` command per installer, saving into --download if given
+    Wget,
+    /// Every URL, one per line, in `table` order, for batch downloaders. Combine with
+    /// --download to also fetch them
+    Urls,
+    /// Only the newest URL of each platform, one per line, e.g. for `xargs curl -O`
+    #[serde(rename = "latest-urls")]
+    LatestUrls,
+    /// A single line for logs: UTC time, versions, installers found, platforms with a hit
+    /// and the time taken
+    Oneline,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum OutputShape {
+    /// One object per version with the latest URL of each platform; size and
+    /// Last-Modified sit under `details`
+    Flat,
+    /// version -> platform -> every build found, newest first
+    Grouped,
 }
 
 #[derive(Parser, Debug)]
-#[clap(author, version, about, long_about = None, disable_version_flag = true)]
+#[clap(author, version, about, long_about = None, disable_version_flag = true, after_help = AFTER_HELP)]
 struct Cli {
-    /// Spotify version(s) to search for
-    #[clap(long, required = true, use_value_delimiter = true, value_delimiter = ',')]
+    /// Spotify version(s) to search for; `-` reads one version per line from stdin
+    #[clap(long, required_unless_present_any = ["from_cache", "versions_file", "version_range", "urls_file", "rescan_failures", "generate_completions"], use_value_delimiter = true, value_delimiter = ',')]
     version: Vec<String>,
 
-    /// Range of build numbers to check (e.g., 0-5000)
-    #[clap(long, default_value = "0-5000")]
+    /// File with one version per line; lines starting with # are comments
+    #[clap(long, value_name = "PATH")]
+    versions_file: Option<PathBuf>,
+
+    /// Every version from FROM to TO, e.g. 1.2.60.500..1.2.60.600. Only the fourth component
+    /// is enumerated, so both ends share major.minor.patch, and the versions have no .gHASH
+    /// suffix, which can't be enumerated. Repeatable
+    #[clap(long, value_name = "FROM..TO", conflicts_with = "strict_version")]
+    version_range: Vec<String>,
+
+    /// Check the URLs listed in PATH, one per line, instead of searching; `-` reads stdin.
+    /// Every URL is reported with its status, and the exit code is 2 if none is live
+    #[clap(long, value_name = "PATH", conflicts_with_all = ["version", "versions_file", "version_range", "from_cache", "checkpoint", "resume"])]
+    urls_file: Option<PathBuf>,
+
+    /// Only accept versions with an 8 character commit hash, e.g. 1.2.60.564.gcc6305cb
+    #[clap(long, env = "LOADERSPOT_STRICT_VERSION")]
+    strict_version: bool,
+
+    /// Range(s) of build numbers to check, e.g. 0-5000, a single build like 1234, or
+    /// several bands like 0-100,3000-3100. Overlapping ranges are merged
+    #[clap(long, env = "LOADERSPOT_RANGE", default_value = "0-5000")]
     range: String,
 
     /// Architecture(s)
-    #[clap(long, use_value_delimiter = true, value_delimiter = ',', value_parser = ["x86", "x64", "arm64", "intel", "all"], default_value = "all")]
+    #[clap(long, env = "LOADERSPOT_ARCH", use_value_delimiter = true, value_delimiter = ',', value_parser = ARCH_VALUES, default_value = "all")]
     arch: Vec<String>,
 
     /// Platform(s)
-    #[clap(long, name = "os", use_value_delimiter = true, value_delimiter = ',', value_parser = ["win", "mac", "all"], default_value = "all")]
+    #[clap(long, name = "os", env = "LOADERSPOT_OS", use_value_delimiter = true, value_delimiter = ',', value_parser = OS_VALUES, default_value = "all")]
     platform: Vec<String>,
 
-    /// Number of concurrent connections
-    #[clap(long, default_value_t = 100)]
+    /// Number of concurrent connections. Values above 300 are allowed but
+    /// make rate limiting by the CDN more likely
+    #[clap(long, env = "LOADERSPOT_CONNECTIONS", default_value_t = 100)]
     connections: usize,
 
-    /// Use ladder search algorithm
-    #[clap(long)]
+    /// Send at most this many requests per second, spread evenly. Applies on top
+    /// of --connections, for scanning politely from shared networks
+    #[clap(long, value_name = "N", env = "LOADERSPOT_RPS", value_parser = clap::value_parser!(u32).range(1..))]
+    rps: Option<u32>,
+
+    /// Wait a random 0 to MS milliseconds before each request, so they don't
+    /// arrive in lockstep. A waiting request still holds its connection
+    #[clap(
+        long,
+        value_name = "MS",
+        env = "LOADERSPOT_JITTER_MS",
+        default_value_t = 0
+    )]
+    jitter_ms: u64,
+
+    /// Start with a few connections and double them every 500ms up to --connections,
+    /// so a cold connection pool isn't hit with every request at once
+    #[clap(long, env = "LOADERSPOT_RAMP")]
+    ramp: bool,
+
+    /// Refuse searches that would send more requests than this, unless --yes is given.
+    /// A smart search counts its largest possible size
+    #[clap(long, value_name = "N", env = "LOADERSPOT_MAX_REQUESTS", default_value_t = DEFAULT_MAX_REQUESTS,
+        value_parser = clap::value_parser!(u64).range(1..))]
+    max_requests: u64,
+
+    /// Stop once N requests were sent and report what was found so far, e.g. to time-box a
+    /// monitoring run. Retries after a 429 don't count; a --checkpoint is kept for --resume
+    #[clap(long, value_name = "N", env = "LOADERSPOT_BUDGET", value_parser = clap::value_parser!(u64).range(1..))]
+    budget: Option<u64>,
+
+    /// Run a search even when it exceeds --max-requests
+    #[clap(short, long)]
+    yes: bool,
+
+    /// Seconds before a single request is given up on
+    #[clap(long, env = "LOADERSPOT_TIMEOUT", default_value_t = 30)]
+    timeout: u64,
+
+    /// Find the newest builds with a staircase search (0-1000, then 1000 more
+    /// at a time for platforms without a hit) instead of scanning --range
+    #[clap(long, visible_alias = "smart", env = "LOADERSPOT_LADDER_SEARCH")]
     ladder_search: bool,
+
+    /// Builds in the first step of --ladder-search, which scans 0-N
+    #[clap(long, value_name = "N", env = "LOADERSPOT_SMART_WINDOW",
+        default_value_t = LadderOptions::default().initial_window,
+        value_parser = clap::value_parser!(i32).range(1..))]
+    smart_window: i32,
+
+    /// Builds added by every further step of --ladder-search
+    #[clap(long, value_name = "N", env = "LOADERSPOT_SMART_INCREMENT",
+        default_value_t = LadderOptions::default().increment,
+        value_parser = clap::value_parser!(i32).range(1..))]
+    smart_increment: i32,
+
+    /// Most further steps of --ladder-search for platforms without a hit
+    #[clap(long, value_name = "N", env = "LOADERSPOT_SMART_ITERATIONS",
+        default_value_t = LadderOptions::default().max_iterations)]
+    smart_iterations: u32,
+
+    /// Find the builds within --range without scanning all of it: probe builds 0, 1, 2, 4, ...
+    /// and then every --max-gap (default 50) until a hit, then scan back to the previous probe and
+    /// onwards until a whole gap misses. Prints the builds each platform turned out to span.
+    /// Assumes those form one run at least a gap long; a shorter run may be missed
+    #[clap(long, env = "LOADERSPOT_AUTO_RANGE", conflicts_with_all = ["ladder_search", "checkpoint", "resume"])]
+    auto_range: bool,
+
+    /// Stop scanning a platform after this many misses in a row past its last hit.
+    /// Never stops before the first hit; off by default for an exhaustive scan
+    #[clap(long, value_name = "N", env = "LOADERSPOT_MAX_GAP", value_parser = clap::value_parser!(u32).range(1..))]
+    max_gap: Option<u32>,
+
+    /// Check only every Nth build number, a quick sweep of a wide range; scan a narrower
+    /// --range around the hits afterwards to find the builds in between
+    #[clap(long, value_name = "N", env = "LOADERSPOT_STEP", default_value_t = 1,
+        value_parser = clap::value_parser!(u32).range(1..))]
+    step: u32,
+
+    /// Treat installers smaller than this as missing, e.g. 1048576 to skip CDN placeholders.
+    /// Found files under 1 MiB are flagged either way
+    #[clap(long, value_name = "BYTES", env = "LOADERSPOT_MIN_SIZE")]
+    min_size: Option<u64>,
+
+    /// Treat files served with a Content-Type installers don't have, such as the text/html of
+    /// a CDN error page, as missing. Hits with such a type are flagged either way
+    #[clap(long, env = "LOADERSPOT_REQUIRE_CONTENT_TYPE")]
+    require_content_type: bool,
+
+    /// Also look for macOS builds as .dmg when the .tbz is missing.
+    /// Doubles the requests for macOS, so it is off by default
+    #[clap(long, env = "LOADERSPOT_TRY_DMG")]
+    try_dmg: bool,
+
+    /// Check every hit once more before reporting it, after a 2s pause and with a GET instead
+    /// of a HEAD, and drop the ones that don't answer again. Costs one request per hit
+    #[clap(long, env = "LOADERSPOT_VERIFY")]
+    verify: bool,
+
+    /// Skip versions already listed in the repository's versions.json, so only new finds are
+    /// reported. Fails when versions.json can't be fetched
+    #[clap(long, env = "LOADERSPOT_ONLY_NEW")]
+    only_new: bool,
+
+    /// Pause for 30s when more than this share of the last --error-window checks got no
+    /// response at all, instead of counting the rest of the range as misses
+    #[clap(long, value_name = "PERCENT", env = "LOADERSPOT_ERROR_THRESHOLD",
+        default_value_t = ErrorStorm::DEFAULT_THRESHOLD_PERCENT,
+        value_parser = clap::value_parser!(u8).range(1..=100))]
+    error_threshold: u8,
+
+    /// Number of recent checks --error-threshold looks at
+    #[clap(long, value_name = "N", env = "LOADERSPOT_ERROR_WINDOW",
+        default_value_t = ErrorStorm::DEFAULT_WINDOW,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    error_window: usize,
+
+    /// Never pause when the network seems down
+    #[clap(long, env = "LOADERSPOT_NO_ERROR_PAUSE")]
+    no_error_pause: bool,
+
+    /// Number of versions searched at the same time; all of them share the --connections limit
+    #[clap(long, env = "LOADERSPOT_VERSION_CONCURRENCY", default_value_t = 1)]
+    version_concurrency: usize,
+
+    /// Output format; ndjson prints one JSON object per installer as soon as it is found.
+    /// Defaults to table when stdout is a terminal and pretty otherwise
+    #[clap(long, value_enum, env = "LOADERSPOT_OUTPUT_FORMAT")]
+    output_format: Option<OutputFormat>,
+
+    /// Compare the builds found for two or more versions instead of listing them: per platform,
+    /// which versions each build exists for, marking the ones missing from some. Table or JSON only
+    #[clap(long)]
+    compare: bool,
+
+    /// Print only how many builds were found per version and platform, and in total,
+    /// instead of the URLs. Table or JSON only
+    #[clap(long, conflicts_with = "compare")]
+    count_only: bool,
+
+    /// Print, per platform, the distinct build numbers found across all versions instead of
+    /// the URLs. Table or JSON only
+    #[clap(long, conflicts_with_all = ["compare", "count_only"])]
+    unique_builds: bool,
+
+    /// Layout of pretty/json output; ignored with ndjson and table
+    #[clap(long, value_enum, env = "LOADERSPOT_OUTPUT_SHAPE", default_value_t = OutputShape::Flat)]
+    output_shape: OutputShape,
+
+    /// Download the latest installer of each platform found into this directory.
+    /// Interrupted downloads resume from their .part file
+    #[clap(long, value_name = "DIR")]
+    download: Option<PathBuf>,
+
+    /// JSON manifest of known installers ({"<file name>": {"sha256": "...", "size": N}}).
+    /// Downloaded files are checked against it and the run fails on any mismatch
+    #[clap(long, value_name = "PATH", requires = "download")]
+    manifest: Option<PathBuf>,
+
+    /// Save the results of the search to this JSON file
+    #[clap(long, value_name = "PATH")]
+    cache_file: Option<PathBuf>,
+
+    /// Write a summary of the run: settings, request counts, elapsed time and the
+    /// installers found. Markdown for a .md path, JSON otherwise
+    #[clap(long, value_name = "PATH")]
+    report: Option<PathBuf>,
+
+    /// Save the progress of the search to this file every few seconds, so --resume can pick it
+    /// up after a crash or Ctrl-C. Removed once the search completes
+    #[clap(long, value_name = "PATH", conflicts_with = "resume")]
+    checkpoint: Option<PathBuf>,
+
+    /// Continue the search saved by --checkpoint. The versions, platforms, ranges and search
+    /// options must be the same as when it was saved; progress keeps being saved to PATH
+    #[clap(long, value_name = "PATH")]
+    resume: Option<PathBuf>,
+
+    /// Check again only the builds that got no response (or stayed rate-limited) in the search
+    /// saved by --checkpoint, and add what turns up to it. A completed search keeps its
+    /// checkpoint while any are left. Prints the installers found this time
+    #[clap(long, value_name = "PATH", conflicts_with_all = ["version", "versions_file", "version_range", "from_cache", "urls_file", "checkpoint", "resume"])]
+    rescan_failures: Option<PathBuf>,
+
+    /// Print the results saved in --cache-file instead of searching; no network access
+    #[clap(long, requires = "cache_file")]
+    from_cache: bool,
+
+    /// Discord/Slack incoming webhook to notify when installers are found
+    #[clap(long, value_name = "URL", env = "LOADERSPOT_WEBHOOK")]
+    webhook: Option<String>,
+
+    /// Ring the terminal bell and show a desktop notification once the search completes
+    #[clap(long, env = "LOADERSPOT_NOTIFY")]
+    notify: bool,
+
+    /// Print the URLs that would be checked and their count, without sending any request
+    #[clap(long)]
+    dry_run: bool,
+
+    /// Log every checked URL and its status (-v), or every request sent (-vv).
+    /// RUST_LOG overrides this
+    #[clap(short, long, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// Print nothing but the results and errors: no progress bars or warnings
+    #[clap(short, long, env = "LOADERSPOT_QUIET", conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// Print errors and warnings on stderr as one JSON object per line,
+    /// {"level", "code", "message"}, with a stable code for each kind of problem
+    #[clap(long, env = "LOADERSPOT_JSON_ERRORS")]
+    json_errors: bool,
+
+    /// Print a completion script for SHELL to stdout and exit
+    #[clap(long, value_name = "SHELL", hide = true)]
+    generate_completions: Option<Shell>,
+
+    /// Proxy URL for all requests (http://, https:// or socks5:// with the `socks` feature).
+    /// Defaults to the HTTP_PROXY/HTTPS_PROXY environment variables
+    #[clap(long, env = "LOADERSPOT_PROXY")]
+    proxy: Option<String>,
+
+    /// Source IP address to send requests from, for machines with several interfaces
+    #[clap(long, value_name = "IP", env = "LOADERSPOT_LOCAL_ADDRESS")]
+    local_address: Option<String>,
+
+    /// Only connect over IPv4
+    #[clap(long, env = "LOADERSPOT_IPV4_ONLY", conflicts_with = "ipv6_only")]
+    ipv4_only: bool,
+
+    /// Only connect over IPv6
+    #[clap(long, env = "LOADERSPOT_IPV6_ONLY")]
+    ipv6_only: bool,
+
+    /// User-Agent to send instead of reqwest's default
+    #[clap(long, value_name = "STRING", env = "LOADERSPOT_USER_AGENT")]
+    user_agent: Option<String>,
+
+    /// Extra request header, e.g. --header "Accept-Language: en". Repeatable
+    #[clap(long = "header", value_name = "NAME: VALUE")]
+    headers: Vec<String>,
+
+    /// Don't follow redirects; a redirect then counts as a hit on the original URL.
+    /// By default up to 10 are followed and the final URL is reported
+    #[clap(long, env = "LOADERSPOT_NO_FOLLOW")]
+    no_follow: bool,
+
+    /// Speak HTTP/2 without negotiating it first. The CDN already negotiates HTTP/2 over TLS;
+    /// this is for plain-HTTP mirrors that speak h2c and breaks against any other
+    #[clap(long, env = "LOADERSPOT_HTTP2_PRIOR_KNOWLEDGE")]
+    http2_prior_knowledge: bool,
+
+    /// Don't accept gzip/deflate/brotli bodies. Only versions.json and --download read a body;
+    /// -v logs how much of it was transferred and decoded
+    #[clap(long, env = "LOADERSPOT_NO_COMPRESSION")]
+    no_compression: bool,
+
+    /// Idle connections to keep open per host for reuse. By default every connection a
+    /// search opens is kept, so --connections sockets are reused instead of reconnecting
+    #[clap(long, value_name = "N", env = "LOADERSPOT_POOL_MAX_IDLE_PER_HOST")]
+    pool_max_idle_per_host: Option<usize>,
+
+    /// Seconds an idle connection stays open for reuse, 0 to keep it until the server closes it.
+    /// Raise it when --rps spaces requests out further than this
+    #[clap(long, value_name = "SECS", env = "LOADERSPOT_POOL_IDLE_TIMEOUT", default_value_t = DEFAULT_POOL_IDLE_TIMEOUT.as_secs())]
+    pool_idle_timeout: u64,
+
+    /// Extra root certificate (PEM or DER) to trust, e.g. for a private mirror
+    #[clap(long, value_name = "PATH", env = "LOADERSPOT_CACERT")]
+    cacert: Option<PathBuf>,
+
+    /// Accept invalid TLS certificates. Anyone on the network path can then forge responses;
+    /// only use this against a mirror you control
+    #[clap(long)]
+    insecure: bool,
+
+    /// Config file to use instead of looking for loaderspot.toml
+    #[clap(long, value_name = "PATH")]
+    config: Option<PathBuf>,
+
+    /// Per-platform path overrides, only settable in the config file's [templates] table
+    #[clap(skip)]
+    templates: PathTemplates,
 }
 
-fn parse_range(range_str: &str) -> (i32, i32) {
-    let parts: Vec<&str> = range_str.split('-').collect();
-    if parts.len() == 2 {
-        let start = parts[0].parse().unwrap_or(0);
-        let end = parts[1].parse().unwrap_or(5000);
-        (start, end)
+/// Versions from --version, stdin (`--version -`) and --versions-file, in
+/// that order and without duplicates. Lines read from stdin or a file are
+/// validated the same way the UI validates its input.
+fn collect_versions(cli: &Cli) -> Result<Vec<String>, String> {
+    let mut versions = Vec::new();
+    let mut listed = Vec::new();
+
+    for version in &cli.version {
+        if version == "-" {
+            let text = io::read_to_string(io::stdin())
+                .map_err(|e| format!("Cannot read versions from stdin: {}", e))?;
+            listed.push(text);
+        } else {
+            versions.push(version.trim().to_string());
+        }
+    }
+    for range in &cli.version_range {
+        versions.extend(expand_version_range(range)?);
+    }
+    if let Some(path) = &cli.versions_file {
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+        listed.push(text);
+    }
+
+    let validate = if cli.strict_version {
+        validate_version_strict
     } else {
-        (0, 5000)
+        validate_version
+    };
+    for text in &listed {
+        for line in version_list_lines(text) {
+            if validate(line) {
+                versions.push(line.to_string());
+            } else {
+                warning!("invalid_version", "skipping invalid version '{}'", line);
+            }
+        }
+    }
+
+    let mut unique = Vec::new();
+    for version in versions {
+        if !unique.contains(&version) {
+            unique.push(version);
+        }
     }
+    Ok(unique)
 }
 
 #[tokio::main]
 async fn main() {
-    let cli = Cli::parse();
+    // clap exits with 2 on usage errors, which is reserved for "nothing found".
+    let exit_on_clap_error = |e: clap::Error| -> ! {
+        // The flags weren't parsed, so look for --json-errors by hand.
+        if e.use_stderr() && std::env::args().any(|arg| arg == "--json-errors") {
+            JSON_ERRORS.store(true, Ordering::Relaxed);
+            let message = e.to_string();
+            let message = message
+                .trim_end()
+                .strip_prefix("error: ")
+                .unwrap_or(message.trim_end());
+            error!("bad_arguments", "{}", message);
+        } else {
+            let _ = e.print();
+        }
+        std::process::exit(if e.use_stderr() { 1 } else { 0 });
+    };
+    let matches = Cli::command()
+        .try_get_matches()
+        .unwrap_or_else(|e| exit_on_clap_error(e));
+    let mut cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| exit_on_clap_error(e));
+    if let Some(shell) = cli.generate_completions {
+        let mut command = Cli::command();
+        let name = command.get_name().to_string();
+        clap_complete::generate(shell, &mut command, name, &mut std::io::stdout());
+        return;
+    }
+    JSON_ERRORS.store(cli.json_errors, Ordering::Relaxed);
+    if let Err(e) = config::apply(&mut cli, &matches) {
+        error!("bad_config", "{}", e);
+        std::process::exit(1);
+    }
+
+    QUIET.store(cli.quiet, Ordering::Relaxed);
+    let log_level = match cli.verbose {
+        _ if cli.quiet => log::LevelFilter::Error,
+        0 => log::LevelFilter::Warn,
+        1 => log::LevelFilter::Debug,
+        _ => log::LevelFilter::Trace,
+    };
+    // Only our own crates get louder; reqwest/hyper debug output is noise here.
+    env_logger::Builder::new()
+        .filter_level(log_level.min(log::LevelFilter::Warn))
+        .filter_module("loaderspot_core", log_level)
+        .filter_module("loaderspot_cli", log_level)
+        .parse_default_env()
+        .format_timestamp_millis()
+        .init();
+
+    require_table_or_json("--compare", cli.compare, cli.output_format());
+    require_table_or_json("--count-only", cli.count_only, cli.output_format());
+    require_table_or_json("--unique-builds", cli.unique_builds, cli.output_format());
+    if cli.from_cache {
+        print_cached_results(&cli);
+        return;
+    }
+    if let Some(path) = &cli.urls_file {
+        check_url_list(&cli, path).await;
+        return;
+    }
+    if let Some(path) = &cli.rescan_failures {
+        rescan_failures(&cli, path).await;
+        return;
+    }
 
     let platforms = if cli.platform.contains(&"all".to_string()) {
         vec!["win", "mac"]
@@ -176,7 +622,7 @@ async fn main() {
 
     let arches = if cli.arch.contains(&"all".to_string()) {
         if platforms.contains(&"win") && platforms.contains(&"mac") {
-             vec!["x86", "x64", "arm64", "intel", "arm64"]
+            vec!["x86", "x64", "arm64", "intel", "arm64"]
         } else if platforms.contains(&"win") {
             vec!["x86", "x64", "arm64"]
         } else if platforms.contains(&"mac") {
@@ -192,11 +638,11 @@ async fn main() {
     for platform in &platforms {
         for arch in &arches {
             let platform_arch = match (*platform, *arch) {
-                ("win", "x86") => Some(PlatformArch::WinX86),
-                ("win", "x64") => Some(PlatformArch::WinX64),
-                ("win", "arm64") => Some(PlatformArch::WinArm64),
-                ("mac", "intel") => Some(PlatformArch::MacOsIntel),
-                ("mac", "arm64") => Some(PlatformArch::MacOsArm64),
+                ("win", "x86") => Some(Platform::WinX86),
+                ("win", "x64") => Some(Platform::WinX64),
+                ("win", "arm64") => Some(Platform::WinArm64),
+                ("mac", "intel") => Some(Platform::MacOsIntel),
+                ("mac", "arm64") => Some(Platform::MacOsArm64),
                 _ => None,
             };
             if let Some(pa) = platform_arch {
@@ -208,134 +654,2529 @@ async fn main() {
     }
 
     if platform_arches.is_empty() {
-        eprintln!("Error: No valid platform and architecture combinations provided.");
+        error!(
+            "no_platforms",
+            "No valid platform and architecture combinations provided."
+        );
         std::process::exit(1);
     }
 
-    let connections = cli.connections.clamp(50, 300);
-    let range = cli.range.clone();
-    let client = Client::builder()
-        .timeout(std::time::Duration::from_secs(30))
-        .build()
-        .unwrap();
+    let versions = match collect_versions(&cli) {
+        Ok(versions) if !versions.is_empty() => versions,
+        Ok(_) => {
+            error!("no_versions", "No valid versions provided.");
+            std::process::exit(1);
+        }
+        Err(e) => {
+            error!("bad_versions", "{}", e);
+            std::process::exit(1);
+        }
+    };
 
-    let spinner_style = ProgressStyle::with_template("{spinner}")
-        .unwrap()
-        .tick_chars("⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏"); // A classic rotating spinner
-    let pb = ProgressBar::new_spinner();
-    pb.set_style(spinner_style);
-    pb.enable_steady_tick(Duration::from_millis(80));
+    if cli.compare && versions.len() < 2 {
+        error!(
+            "not_enough_versions",
+            "--compare needs at least two versions."
+        );
+        std::process::exit(1);
+    }
 
-    let versions = cli.version.clone();
-    let client_clone = client.clone();
-    let ladder_search = cli.ladder_search;
-    let versions_clone_for_task = versions.clone();
+    let ranges = match parse_build_ranges(&cli.range) {
+        Ok(ranges) => ranges,
+        Err(e) => {
+            error!("bad_range", "{}", e);
+            std::process::exit(1);
+        }
+    };
 
-    let search_task = tokio::spawn(async move {
-        let mut results = Vec::new();
+    if cli
+        .ladder_options()
+        .is_some_and(|ladder| ladder.max_build() > i32::MAX as u64)
+    {
+        error!("bad_ladder", "--smart-window + --smart-increment x --smart-iterations is past the largest build number.");
+        std::process::exit(1);
+    }
 
-        for version in &versions_clone_for_task {
-            let mut all_found_urls_for_version = Vec::new();
-            let mut arches_to_search = platform_arches.clone();
+    if cli.dry_run {
+        print_dry_run(
+            &versions,
+            &platform_arches,
+            cli.ladder_options(),
+            &ranges,
+            cli.step,
+            &cli.templates,
+            cli.try_dmg,
+        );
+        return;
+    }
 
-            if !should_use_win_x86(version) && arches_to_search.contains(&PlatformArch::WinX86) {
-                if versions_clone_for_task.len() == 1 && arches_to_search.len() == 1 {
-                    eprintln!("Warning: x86 architecture for Windows is no longer supported for versions newer than 1.2.53.");
-                    continue;
-                }
-                arches_to_search.retain(|&p| p != PlatformArch::WinX86);
+    let connections = checked_connections(&cli);
+    let client = search_client(&cli);
+    let versions = if cli.only_new {
+        let known = match fetch_versions_json(&client)
+            .await
+            .and_then(|json| KnownVersions::parse(&json))
+        {
+            Ok(known) => known,
+            Err(e) => {
+                error!("versions_json_unavailable", "{}", e);
+                std::process::exit(1);
             }
+        };
+        let (skipped, new): (Vec<String>, Vec<String>) =
+            versions.into_iter().partition(|v| known.contains(v));
+        if !skipped.is_empty() {
+            status!(
+                "Skipping {} version(s) already in versions.json: {}",
+                skipped.len(),
+                skipped.join(", ")
+            );
+        }
+        new
+    } else {
+        versions
+    };
 
-            if ladder_search {
-                let mut start_number = 0;
-                let mut before_enter = 1000;
-                let additional_searches = 15;
-                let increment = 1000;
-
-                for &platform_arch in &arches_to_search {
-                    let found = search_installers(&client_clone, version, start_number, before_enter, platform_arch, connections).await;
-                    all_found_urls_for_version.extend(found);
-                }
+    // A ladder search usually stops well short of its total, so the bar and
+    // its ETA are an upper bound there. The bar counts builds, while the limit
+    // and estimate count requests, which --try-dmg can double on macOS.
+    let expected = |try_dmg| {
+        let total = expected_requests(
+            &versions,
+            &platform_arches,
+            cli.ladder_options(),
+            &ranges,
+            cli.step,
+            try_dmg,
+        );
+        cli.budget.map_or(total, |budget| total.min(budget))
+    };
+    let total_work = expected(false);
+    let total_requests = expected(cli.try_dmg);
+    let estimate = HumanDuration(estimate_search_time(total_requests, connections, cli.rps));
+    if total_requests > cli.max_requests && !cli.yes {
+        error!(
+            "too_many_requests",
+            "This search sends up to {} requests, about {} at {} connections, more than --max-requests {}. Pass --yes to run it anyway.",
+            total_requests, estimate, connections, cli.max_requests
+        );
+        std::process::exit(1);
+    }
+    status!(
+        "Checking up to {} URLs, about {} at {} connections.",
+        total_requests,
+        estimate,
+        connections
+    );
+    if let Some(ladder) = cli.ladder_options() {
+        status!(
+            "Smart search checks builds up to {} at most.",
+            ladder.max_build()
+        );
+    }
+    let bar_style = ProgressStyle::with_template(
+        "{spinner} [{elapsed_precise}] {wide_bar} {pos}/{len} ~{per_sec} ETA {eta}",
+    )
+    .unwrap()
+    .tick_chars("⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏");
+    let pb = progress_bar(total_work);
+    pb.set_style(bar_style);
+    pb.enable_steady_tick(Duration::from_millis(80));
 
-                for _ in 0..additional_searches {
-                    let latest_urls = get_latest_urls(&all_found_urls_for_version);
-                    let target_len = arches_to_search.iter().filter(|&&p| p != PlatformArch::WinX86 || should_use_win_x86(version)).count();
+    let mut report = RunReport {
+        platforms: platform_arches.clone(),
+        connections,
+        requests_per_second: cli.rps,
+        ..RunReport::start(
+            concat!(env!("CARGO_PKG_NAME"), " ", env!("CARGO_PKG_VERSION")),
+            versions.clone(),
+            match cli.strategy() {
+                Strategy::AutoRange => format!(
+                    "auto range within {}",
+                    RunReport::describe_scope(None, &ranges)
+                ),
+                _ => RunReport::describe_scope(cli.ladder_options().as_ref(), &ranges),
+            },
+        )
+    };
+    let started = Instant::now();
 
-                    if latest_urls.len() >= target_len {
-                        break;
-                    }
+    let params = SearchParams {
+        versions: versions.clone(),
+        platforms: platform_arches.clone(),
+        ranges: ranges.clone(),
+        ladder: cli.ladder_options(),
+        build_hints: false,
+        max_gap: cli.max_gap,
+        try_dmg: cli.try_dmg,
+        step: cli.step,
+    };
+    // What an earlier run already did, and what this one has done so far.
+    let resumed = cli.resume.as_ref().map(|path| {
+        match Checkpoint::load(path).and_then(|checkpoint| checkpoint.ensure_matches(&params).map(|_| checkpoint)) {
+            Ok(checkpoint) => {
+                status!(
+                    "Resuming the search saved {}: {} of {} version(s) done, {} installer(s) found.",
+                    format_age(checkpoint.age()), checkpoint.completed.len(), versions.len(), checkpoint.found.len()
+                );
+                Arc::new(checkpoint)
+            }
+            Err(e) => {
+                error!("bad_checkpoint", "{}", e);
+                std::process::exit(1);
+            }
+        }
+    });
+    let checkpoint_path = cli.resume.clone().or_else(|| cli.checkpoint.clone());
+    let checkpoint = checkpoint_path.as_ref().map(|_| {
+        Arc::new(Mutex::new(
+            resumed
+                .as_deref()
+                .cloned()
+                .unwrap_or_else(|| Checkpoint::new(params)),
+        ))
+    });
 
-                    start_number = before_enter + 1;
-                    before_enter += increment;
+    let mut engine = SearchEngine::new(client.clone(), connections);
+    engine.templates = cli.templates.clone();
+    engine.max_gap = cli.max_gap;
+    engine.try_dmg = cli.try_dmg;
+    engine.step = cli.step;
+    engine.min_size = cli.min_size;
+    engine.require_content_type = cli.require_content_type;
+    engine.reachability = Reachability::watching();
+    if let Some(budget) = cli.budget {
+        engine.budget = RequestBudget::new(budget);
+    }
+    if !cli.no_error_pause {
+        engine.error_storm = ErrorStorm::new(cli.error_window, cli.error_threshold);
+    }
+    if let Some(rps) = cli.rps {
+        engine.rate_limiter = RateLimiter::new(rps);
+    }
+    engine.jitter = Jitter::new(Duration::from_millis(cli.jitter_ms));
+    if cli.ramp {
+        Ramp::default().apply(&engine.semaphore);
+    }
+    {
+        // How the first second went, to tell whether --ramp helps on this network.
+        let processed = engine.processed.clone();
+        let network_errors = engine.network_errors.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            log::debug!(
+                "First second: {} checks, {} without a response",
+                processed.load(Ordering::Relaxed),
+                network_errors.load(Ordering::Relaxed)
+            );
+        });
+    }
 
-                    let mut missing_arches = Vec::new();
-                    for &platform_arch in &arches_to_search {
-                        if !latest_urls.contains_key(platform_arch.to_string()) {
-                            missing_arches.push(platform_arch);
-                        }
-                    }
+    let mut ndjson_printer = None;
+    // With --verify a hit may still be dropped, so nothing is streamed before that.
+    if cli.output_format() == OutputFormat::Ndjson && !cli.verify {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+        let streamed = Mutex::new(HashSet::new());
+        engine.on_found = Some(Arc::new(move |event: &FoundEvent| {
+            if !streamed.lock().unwrap().insert(event.url.clone()) {
+                return;
+            }
+            let line = json!({
+                "platform": event.platform.code(),
+                "version": &*event.version,
+                "build": event.build,
+                "url": event.url,
+                "extension": installer_extension(&event.url),
+                "size": event.size,
+                "last_modified": event.last_modified,
+            });
+            let _ = tx.send(line.to_string());
+        }));
+        ndjson_printer = Some(tokio::spawn(async move {
+            while let Some(line) = rx.recv().await {
+                println!("{}", line);
+            }
+        }));
+    }
+    if let Some(checkpoint) = &checkpoint {
+        let checkpoint = checkpoint.clone();
+        let inner = engine.on_found.take();
+        engine.on_found = Some(Arc::new(move |event: &FoundEvent| {
+            {
+                let mut checkpoint = checkpoint.lock().unwrap();
+                if !checkpoint
+                    .found
+                    .iter()
+                    .any(|installer| installer.url == event.url)
+                {
+                    checkpoint.found.push(CachedInstaller {
+                        version: event.version.to_string(),
+                        platform: event.platform,
+                        build: event.build,
+                        url: event.url.clone(),
+                        size: event.size,
+                        last_modified: event.last_modified.clone(),
+                    });
+                }
+            }
+            if let Some(inner) = &inner {
+                inner(event);
+            }
+        }));
+    }
+    // A ladder search jumps around, so it only resumes whole versions.
+    let checked_builds = checkpoint
+        .as_ref()
+        .filter(|_| matches!(cli.strategy(), Strategy::Ranges))
+        .map(|_| CheckedBuilds::default());
+    engine.checked_builds = checked_builds.clone();
+    let failed_checks = checkpoint.as_ref().map(|_| FailedChecks::default());
+    engine.failed_checks = failed_checks.clone();
 
-                    for &platform_arch in &missing_arches {
-                        let found = search_installers(&client_clone, version, start_number, before_enter, platform_arch, connections).await;
-                        all_found_urls_for_version.extend(found);
-                    }
+    let engine = Arc::new(engine);
+    let interrupted = Arc::new(AtomicBool::new(false));
+    {
+        let cancel_flag = engine.cancel_flag.clone();
+        let interrupted = interrupted.clone();
+        let pb = pb.clone();
+        tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_err() {
+                return;
+            }
+            interrupted.store(true, Ordering::Relaxed);
+            cancel_flag.store(true, Ordering::Relaxed);
+            pb.suspend(|| status!("Interrupted, stopping the search. Press Ctrl-C again to quit."));
+            let _ = tokio::signal::ctrl_c().await;
+            std::process::exit(EXIT_INTERRUPTED);
+        });
+    }
+    let progress_ticker = {
+        let processed = engine.processed.clone();
+        let pb = pb.clone();
+        tokio::spawn(async move {
+            loop {
+                pb.set_position(processed.load(Ordering::Relaxed));
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        })
+    };
+    // Set while paused with Ctrl-Z, which only Ctrl-Z or SIGCONT undo.
+    let user_paused = Arc::new(AtomicBool::new(false));
+    #[cfg(unix)]
+    let pause_toggler =
+        match pause_on_signals(engine.pause_flag.clone(), user_paused.clone(), pb.clone()) {
+            Ok(toggler) => Some(toggler),
+            Err(e) => {
+                warning!("cannot_pause", "cannot pause with Ctrl-Z: {}", e);
+                None
+            }
+        };
+    // Nobody is there to press resume, so the search resumes by itself.
+    let storm_watcher = {
+        let storm = engine.error_storm.clone();
+        let pause_flag = engine.pause_flag.clone();
+        let user_paused = user_paused.clone();
+        let pb = pb.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                if !storm.tripped() {
+                    continue;
                 }
-            } else {
-                let (start, end) = parse_range(&range);
-                for &platform_arch in &arches_to_search {
-                    let found = search_installers(&client_clone, version, start, end, platform_arch, connections).await;
-                    all_found_urls_for_version.extend(found);
+                pb.suspend(|| {
+                    warning!(
+                        "network_down",
+                        "network appears down, paused for {}s",
+                        NETWORK_DOWN_PAUSE.as_secs()
+                    )
+                });
+                tokio::time::sleep(NETWORK_DOWN_PAUSE).await;
+                storm.reset();
+                if !user_paused.load(Ordering::Relaxed) {
+                    pause_flag.store(false, Ordering::Relaxed);
                 }
             }
+        })
+    };
+    let checkpoint_saver =
+        checkpoint
+            .clone()
+            .zip(checkpoint_path.clone())
+            .map(|(checkpoint, path)| {
+                let checked_builds = checked_builds.clone();
+                let failed_checks = failed_checks.clone();
+                let pb = pb.clone();
+                tokio::spawn(async move {
+                    loop {
+                        tokio::time::sleep(CHECKPOINT_INTERVAL).await;
+                        if let Err(e) = save_checkpoint(
+                            &checkpoint,
+                            checked_builds.as_ref(),
+                            failed_checks.as_ref(),
+                            &path,
+                        ) {
+                            pb.suspend(|| error!("checkpoint_not_saved", "{}", e));
+                            return;
+                        }
+                    }
+                })
+            });
+    let version_limit = Arc::new(Semaphore::new(cli.version_concurrency.max(1)));
+    let single_version = versions.len() == 1;
 
-            let mut latest_urls = get_latest_urls(&all_found_urls_for_version);
-            latest_urls.insert("version".to_string(), version.clone());
-            results.push(latest_urls);
+    let mut version_tasks = Vec::new();
+    for version in versions.clone() {
+        if resumed
+            .as_ref()
+            .is_some_and(|resumed| resumed.is_completed(&version))
+        {
+            continue;
         }
-        results
-    });
+        let engine = engine.clone();
+        let version_limit = version_limit.clone();
+        let platform_arches = platform_arches.clone();
+        let strategy = cli.strategy();
+        let ranges = ranges.clone();
+        let resumed = resumed.clone();
+        let checkpoint = checkpoint.clone();
+
+        version_tasks.push(tokio::spawn(async move {
+            let _permit = version_limit.acquire_owned().await.unwrap();
+            let result = search_version(
+                &engine,
+                version.clone(),
+                platform_arches,
+                single_version,
+                strategy,
+                &ranges,
+                resumed.as_deref(),
+            )
+            .await;
+            if let Some(checkpoint) =
+                checkpoint.filter(|_| !engine.cancel_flag.load(Ordering::Relaxed))
+            {
+                checkpoint.lock().unwrap().complete_version(&version);
+            }
+            result
+        }));
+    }
 
-    let results = search_task.await.unwrap();
+    let mut results = Vec::new();
+    for task in version_tasks {
+        if let Some(entry) = task.await.unwrap() {
+            results.push(entry);
+        }
+    }
+    progress_ticker.abort();
+    storm_watcher.abort();
+    #[cfg(unix)]
+    if let Some(toggler) = pause_toggler {
+        toggler.abort();
+    }
+    if let Some(saver) = checkpoint_saver {
+        saver.abort();
+    }
     pb.finish_and_clear();
 
-    if versions.len() == 1 {
-        let json_output = serde_json::to_string_pretty(&results[0]).unwrap();
-        println!("{}", json_output);
-    } else {
-        let json_output = serde_json::to_string_pretty(&results).unwrap();
-        println!("{}", json_output);
+    // Nothing was searched, so there is nothing to report, cache or announce.
+    if engine.reachability.unreachable() {
+        if let Some((checkpoint, path)) = checkpoint.as_ref().zip(checkpoint_path.as_ref()) {
+            if let Err(e) = save_checkpoint(
+                checkpoint,
+                checked_builds.as_ref(),
+                failed_checks.as_ref(),
+                path,
+            ) {
+                error!("checkpoint_not_saved", "{}", e);
+            }
+        }
+        error!(
+            "unreachable",
+            "Cannot reach {}, check your connection.", engine.base_url
+        );
+        std::process::exit(1);
     }
-}
 
-fn get_latest_urls(found_urls: &[(String, PlatformArch)]) -> HashMap<String, String> {
-    let mut platform_urls = HashMap::new();
-    let version_pattern = Regex::new(r"-(\d+)\.(exe|tbz)$").unwrap();
+    if let Some((checkpoint, path)) = checkpoint.as_ref().zip(checkpoint_path.as_ref()) {
+        let failures = failed_checks.as_ref().map_or(0, FailedChecks::len);
+        if interrupted.load(Ordering::Relaxed) || engine.budget.exhausted() {
+            match save_checkpoint(
+                checkpoint,
+                checked_builds.as_ref(),
+                failed_checks.as_ref(),
+                path,
+            ) {
+                Ok(()) => status!("Progress saved; continue with --resume {}", path.display()),
+                Err(e) => error!("checkpoint_not_saved", "{}", e),
+            }
+        } else if failures > 0 {
+            match save_checkpoint(
+                checkpoint,
+                checked_builds.as_ref(),
+                failed_checks.as_ref(),
+                path,
+            ) {
+                Ok(()) => status!(
+                    "{} check(s) got no answer; try just those again with --rescan-failures {}",
+                    failures,
+                    path.display()
+                ),
+                Err(e) => error!("checkpoint_not_saved", "{}", e),
+            }
+        } else if path.exists() {
+            if let Err(e) = fs::remove_file(path) {
+                error!(
+                    "checkpoint_not_removed",
+                    "Cannot remove checkpoint {}: {}",
+                    path.display(),
+                    e
+                );
+            }
+        }
+    }
+    // Put back what the earlier run found, in the order of `versions`.
+    if let Some(resumed) = &resumed {
+        let mut merged = Vec::new();
+        for version in &versions {
+            let mut found: Vec<_> = resumed
+                .found
+                .iter()
+                .filter(|installer| &installer.version == version)
+                .map(FoundInstaller::from)
+                .collect();
+            let searched = match results
+                .iter_mut()
+                .find(|result: &&mut VersionResult| &result.version == version)
+            {
+                Some(result) => {
+                    found.append(&mut result.found);
+                    result.searched.take()
+                }
+                None if resumed.is_completed(version) => None,
+                None => continue,
+            };
+            merged.push(VersionResult {
+                version: version.clone(),
+                found,
+                searched,
+            });
+        }
+        results = merged;
+    }
+    if cli.verify && !interrupted.load(Ordering::Relaxed) {
+        let hits = results
+            .iter()
+            .flat_map(|result| result.found.iter().cloned())
+            .collect();
+        let (confirmed, unconfirmed) = engine.confirm_hits(hits, CONFIRM_DELAY).await;
+        log::debug!(
+            "Verified {} hits, {} not confirmed",
+            confirmed.len(),
+            unconfirmed.len()
+        );
+        for (FoundInstaller { url, platform, .. }, status) in &unconfirmed {
+            let status = status.map_or("no response".to_string(), |status| status.to_string());
+            warning!(
+                "unconfirmed_hit",
+                "{} {} didn't answer a second check ({}), dropped",
+                platform.code(),
+                url,
+                status
+            );
+        }
+        let dropped: HashSet<&str> = unconfirmed
+            .iter()
+            .map(|(installer, _)| installer.url.as_str())
+            .collect();
+        for result in &mut results {
+            result
+                .found
+                .retain(|installer| !dropped.contains(installer.url.as_str()));
+        }
+    }
+
+    dedup_across_versions(&mut results);
 
-    for (url, platform) in found_urls {
-        if let Some(captures) = version_pattern.captures(url) {
-            if let Some(version_number_match) = captures.get(1) {
-                let version_number = version_number_match.as_str().parse::<u32>().unwrap();
-                let platform_key = platform.to_string().to_string();
+    report.elapsed_secs = started.elapsed().as_secs_f64();
+    report.cancelled = interrupted.load(Ordering::Relaxed);
+    report.stats = run_stats(&engine, &results);
+    let hits = report.stats.hits;
+    let budget = engine.budget.clone();
+    let unexpected_types = engine.unexpected_types.clone();
+    drop(engine);
+    if let Some(printer) = ndjson_printer {
+        let _ = printer.await;
+    }
+
+    if interrupted.load(Ordering::Relaxed) {
+        WARNINGS
+            .lock()
+            .unwrap()
+            .push("interrupted with Ctrl-C; the results are partial".to_string());
+    }
+    report_budget(&budget);
+    report_content_types(&unexpected_types, cli.require_content_type);
+    if cli.auto_range {
+        print_auto_ranges(&results, cli.max_gap.unwrap_or(AUTO_RANGE_GAP));
+    }
+    warn_about_small_installers(&results);
+    // ndjson results were already streamed as they were found, unless --verify held them back.
+    if cli.compare {
+        print_comparison(cli.output_format(), &versions, &results);
+    } else if cli.count_only {
+        print_counts(
+            cli.output_format(),
+            &versions,
+            &results,
+            Some(&report.stats),
+        );
+    } else if cli.unique_builds {
+        print_unique_builds(
+            cli.output_format(),
+            &versions,
+            &results,
+            Some(&report.stats),
+        );
+    } else if cli.output_format() != OutputFormat::Ndjson || cli.verify {
+        print_results(
+            cli.output_format(),
+            cli.output_shape,
+            &versions,
+            &results,
+            Some(&report.stats),
+            Some(started.elapsed()),
+            cli.download.as_deref(),
+        );
+    }
+    if let Some(path) = &cli.report {
+        report.searched = results
+            .iter()
+            .filter_map(|result| result.searched.clone())
+            .collect();
+        report.found = results_to_cache(&results).found;
+        if let Err(e) = report.save(path) {
+            error!("report_not_saved", "{}", e);
+        }
+    }
+    // A partial search is not cached, announced or downloaded.
+    if interrupted.load(Ordering::Relaxed) {
+        std::process::exit(EXIT_INTERRUPTED);
+    }
+    if cli.notify {
+        notify_completion(hits, results.len(), started.elapsed());
+    }
+
+    if let Some(path) = &cli.cache_file {
+        if let Err(e) = results_to_cache(&results).save(path) {
+            error!("cache_not_saved", "{}", e);
+        }
+    }
 
-                let entry = platform_urls.entry(platform_key).or_insert_with(|| (url.clone(), version_number));
+    if let Some(webhook) = &cli.webhook {
+        send_webhook(&client, webhook, &results).await;
+    }
 
-                if version_number > entry.1 {
-                    *entry = (url.clone(), version_number);
+    if let Some(dir) = &cli.download {
+        let manifest = match cli.manifest.as_deref().map(Manifest::load).transpose() {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                error!("bad_manifest", "{}", e);
+                std::process::exit(1);
+            }
+        };
+        match client_options(&cli, DOWNLOAD_TIMEOUT).build() {
+            Ok(download_client) => {
+                let failed =
+                    download_results(&download_client, &results, dir, manifest.as_ref()).await;
+                if failed > 0 && manifest.is_some() {
+                    error!(
+                        "verification_failed",
+                        "{} file(s) failed verification", failed
+                    );
+                    std::process::exit(1);
                 }
             }
+            Err(e) => error!("bad_network_settings", "{}", e),
         }
     }
 
-    let latest_urls: HashMap<String, String> = platform_urls
-        .into_iter()
-        .map(|(k, (v, _))| (k, v))
-        .collect();
+    if !found_anything(&results) {
+        let stats = &report.stats;
+        if stats.builds_checked > 0 && stats.network_errors == stats.builds_checked {
+            error!(
+                "network_unavailable",
+                "Network unavailable, every request failed."
+            );
+            std::process::exit(1);
+        }
+        std::process::exit(EXIT_NOTHING_FOUND);
+    }
+}
 
-    if latest_urls.is_empty() {
-        let mut empty_map = HashMap::new();
-        empty_map.insert("unknown".to_string(), "unknown".to_string());
-        return empty_map;
+/// Ctrl-Z (SIGTSTP) pauses the search instead of stopping the process, and
+/// resumes it when pressed again; SIGCONT, e.g. from `kill -CONT`, resumes it.
+/// Paused tasks hold on to their connection permits, so a resumed search
+/// picks up at the same concurrency.
+#[cfg(unix)]
+fn pause_on_signals(
+    pause_flag: Arc<AtomicBool>,
+    user_paused: Arc<AtomicBool>,
+    pb: ProgressBar,
+) -> io::Result<tokio::task::JoinHandle<()>> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut stop = signal(SignalKind::from_raw(libc::SIGTSTP))?;
+    let mut resume = signal(SignalKind::from_raw(libc::SIGCONT))?;
+    Ok(tokio::spawn(async move {
+        loop {
+            let pause = tokio::select! {
+                Some(()) = stop.recv() => !user_paused.load(Ordering::Relaxed),
+                Some(()) = resume.recv() => false,
+                else => return,
+            };
+            if user_paused.swap(pause, Ordering::Relaxed) == pause {
+                continue;
+            }
+            pause_flag.store(pause, Ordering::Relaxed);
+            pb.suspend(|| {
+                if pause {
+                    status!("Paused; press Ctrl-Z again to resume.");
+                } else {
+                    status!("Resumed.");
+                }
+            });
+        }
+    }))
+}
+
+/// How [`search_version`] looks for the builds of a version.
+#[derive(Clone, Copy)]
+enum Strategy {
+    /// Every build of --range.
+    Ranges,
+    Ladder(LadderOptions),
+    /// --auto-range within each of the ranges.
+    AutoRange,
+}
+
+impl Cli {
+    fn strategy(&self) -> Strategy {
+        match self.ladder_options() {
+            Some(ladder) => Strategy::Ladder(ladder),
+            None if self.auto_range => Strategy::AutoRange,
+            None => Strategy::Ranges,
+        }
     }
 
-    latest_urls
+    /// The staircase layout, when --ladder-search is on.
+    fn ladder_options(&self) -> Option<LadderOptions> {
+        self.ladder_search.then_some(LadderOptions {
+            initial_window: self.smart_window,
+            increment: self.smart_increment,
+            max_iterations: self.smart_iterations,
+        })
+    }
+
+    /// `--output-format`, or table for a terminal and pretty JSON for a pipe.
+    fn output_format(&self) -> OutputFormat {
+        self.output_format.unwrap_or_else(|| {
+            if io::stdout().is_terminal() {
+                OutputFormat::Table
+            } else {
+                OutputFormat::Pretty
+            }
+        })
+    }
+}
+
+/// A progress bar that stays hidden under --quiet.
+fn progress_bar(len: u64) -> ProgressBar {
+    if QUIET.load(Ordering::Relaxed) {
+        ProgressBar::hidden()
+    } else {
+        ProgressBar::new(len)
+    }
+}
+
+/// `--connections`, or exits when it is 0.
+fn checked_connections(cli: &Cli) -> usize {
+    if cli.connections == 0 {
+        error!("bad_connections", "--connections must be at least 1.");
+        std::process::exit(1);
+    }
+    if cli.connections > SOFT_MAX_CONNECTIONS {
+        warning!(
+            "many_connections",
+            "{} connections is above the recommended maximum of {}; the CDN may rate-limit this scan.",
+            cli.connections, SOFT_MAX_CONNECTIONS
+        );
+    }
+    cli.connections
+}
+
+/// The client that sends the search requests, or exits when the network settings are invalid.
+fn search_client(cli: &Cli) -> Client {
+    let client = match client_options(cli, Duration::from_secs(cli.timeout)).build() {
+        Ok(client) => client,
+        Err(e) => {
+            error!("bad_network_settings", "{}", e);
+            std::process::exit(1);
+        }
+    };
+    // Not a status line: --quiet must not hide this one.
+    if cli.insecure {
+        let message =
+            "--insecure is set; TLS certificates are NOT verified and any response may be forged.";
+        if JSON_ERRORS.load(Ordering::Relaxed) {
+            report_problem("warning", "insecure", message);
+        } else {
+            eprintln!("WARNING: {}", message);
+        }
+    }
+    client
+}
+
+/// Network settings shared by the search, webhook and download clients.
+fn client_options(cli: &Cli, timeout: Duration) -> ClientOptions {
+    let ip_family = if cli.ipv4_only {
+        IpFamily::V4
+    } else if cli.ipv6_only {
+        IpFamily::V6
+    } else {
+        IpFamily::Any
+    };
+    ClientOptions {
+        timeout,
+        proxy: cli.proxy.clone(),
+        local_address: cli.local_address.clone(),
+        ip_family,
+        user_agent: cli.user_agent.clone(),
+        headers: cli.headers.clone(),
+        follow_redirects: !cli.no_follow,
+        ca_cert: cli.cacert.clone(),
+        accept_invalid_certs: cli.insecure,
+        http2_prior_knowledge: cli.http2_prior_knowledge,
+        pool_max_idle_per_host: cli.pool_max_idle_per_host,
+        pool_idle_timeout: Some(Duration::from_secs(cli.pool_idle_timeout))
+            .filter(|t| !t.is_zero()),
+        compression: !cli.no_compression,
+    }
+}
+
+/// Brings `checkpoint` up to date with `checked_builds` and `failed_checks` and writes it.
+fn save_checkpoint(
+    checkpoint: &Mutex<Checkpoint>,
+    checked_builds: Option<&CheckedBuilds>,
+    failed_checks: Option<&FailedChecks>,
+    path: &Path,
+) -> Result<(), String> {
+    let mut checkpoint = checkpoint.lock().unwrap();
+    if let Some(checked_builds) = checked_builds {
+        checkpoint.record(checked_builds);
+    }
+    if let Some(failed_checks) = failed_checks {
+        checkpoint.record_failures(failed_checks);
+    }
+    checkpoint.save(path)
+}
+
+/// The builds --auto-range found each platform to span, e.g. "Auto range 1.2.60.564.gcc6305cb
+/// WIN64: builds 300-330", or a warning for a version it found nothing of.
+fn print_auto_ranges(results: &[VersionResult], gap: u32) {
+    for result in results {
+        if result.found.is_empty() {
+            warning!(
+                "auto_range_empty",
+                "--auto-range found no builds of {}; a run shorter than {} builds between two probes is missed, --range alone checks every build",
+                result.version, gap
+            );
+            continue;
+        }
+        for platform in Platform::all() {
+            let builds: Vec<i32> = result
+                .found
+                .iter()
+                .filter(|installer| installer.platform == platform)
+                .filter_map(|installer| build_number(&installer.url))
+                .collect();
+            if let (Some(low), Some(high)) = (builds.iter().min(), builds.iter().max()) {
+                status!(
+                    "Auto range {} {}: builds {}-{}",
+                    result.version,
+                    platform.code(),
+                    low,
+                    high
+                );
+            }
+        }
+    }
+}
+
+/// Flags every installer too small to be a real one, with its size.
+fn warn_about_small_installers(results: &[VersionResult]) {
+    for result in results {
+        for installer in &result.found {
+            if let Some(size) = installer
+                .size
+                .filter(|&size| is_suspiciously_small(Some(size)))
+            {
+                warning!(
+                    "small_installer",
+                    "{} {} is only {} bytes, likely a CDN placeholder; --min-size {} skips such files",
+                    installer.platform.code(), installer.url, size, SUSPICIOUS_SIZE
+                );
+            }
+        }
+    }
+}
+
+/// Flags the hits that came with a Content-Type installers don't have, dropped or not.
+fn report_content_types(unexpected_types: &ContentTypeCounts, required: bool) {
+    if unexpected_types.total() == 0 {
+        return;
+    }
+    if required {
+        warning!(
+            "unexpected_content_type",
+            "dropped {} hit(s) served as something other than an installer: {}",
+            unexpected_types.total(),
+            unexpected_types.summary()
+        );
+    } else {
+        warning!(
+            "unexpected_content_type",
+            "{} hit(s) served as something other than an installer, likely error pages: {}; --require-content-type drops them",
+            unexpected_types.total(), unexpected_types.summary()
+        );
+    }
+}
+
+/// How much of --budget a run used, with a warning when it ran out.
+fn report_budget(budget: &RequestBudget) {
+    let Some(limit) = budget.limit() else {
+        return;
+    };
+    status!("Request budget: {} of {} used.", budget.used(), limit);
+    if budget.exhausted() {
+        warning!(
+            "budget_exhausted",
+            "the --budget of {} requests ran out before the run finished; the results are partial",
+            limit
+        );
+    }
+}
+
+/// Keeps each URL only where it was found first; builds redirecting to one
+/// file would list it once per build.
+fn dedup_across_versions(results: &mut [VersionResult]) {
+    let mut seen = HashSet::new();
+    for result in results {
+        result
+            .found
+            .retain(|installer| seen.insert(installer.url.clone()));
+    }
+}
+
+/// What `engine` did for `results`, which have to be deduplicated already so
+/// every hit counts once.
+fn run_stats(engine: &SearchEngine, results: &[VersionResult]) -> RunStats {
+    let network_errors = engine.network_errors.load(Ordering::Relaxed);
+    let processed = engine.processed.load(Ordering::Relaxed);
+    let hits = results
+        .iter()
+        .map(|result| result.found.len() as u64)
+        .sum::<u64>();
+    RunStats {
+        builds_checked: processed,
+        requests: engine.request_counts.requests(),
+        retries: engine.request_counts.retries(),
+        hits,
+        misses: processed.saturating_sub(hits + network_errors),
+        network_errors,
+    }
+}
+
+fn found_anything(results: &[VersionResult]) -> bool {
+    results.iter().any(|result| !result.found.is_empty())
+}
+
+/// `stats` and `elapsed` are `None` for results read from the cache, when no search ran.
+fn print_results(
+    output_format: OutputFormat,
+    output_shape: OutputShape,
+    versions: &[String],
+    results: &[VersionResult],
+    stats: Option<&RunStats>,
+    elapsed: Option<Duration>,
+    download_dir: Option<&Path>,
+) {
+    let output = json!({
+        "schema_version": SCHEMA_VERSION,
+        "versions": versions,
+        "results": match output_shape {
+            OutputShape::Grouped => grouped_json(results),
+            OutputShape::Flat => Value::Array(results.iter().map(|r| Value::Object(r.to_json())).collect()),
+        },
+        "searched": searched_json(results),
+        "stats": stats,
+        "warnings": *WARNINGS.lock().unwrap(),
+    });
+    let json_output = match output_format {
+        OutputFormat::Json => serde_json::to_string(&output).unwrap(),
+        OutputFormat::Pretty => serde_json::to_string_pretty(&output).unwrap(),
+        OutputFormat::Ndjson => {
+            for result in results {
+                for installer in &result.found {
+                    let line = json!({
+                        "platform": installer.platform.code(),
+                        "version": result.version,
+                        "build": build_number(&installer.url),
+                        "url": installer.url,
+                        "extension": installer_extension(&installer.url),
+                        "size": installer.size,
+                        "last_modified": installer.last_modified,
+                    });
+                    println!("{}", line);
+                }
+            }
+            return;
+        }
+        OutputFormat::Table => {
+            print_table(results, versions.len() == 1);
+            for result in results {
+                if let Some(searched) = &result.searched {
+                    status!("Searched {}: {}", result.version, searched.describe());
+                }
+            }
+            return;
+        }
+        OutputFormat::Curl | OutputFormat::Wget => {
+            let tool = match output_format {
+                OutputFormat::Wget => Downloader::Wget,
+                _ => Downloader::Curl,
+            };
+            for (_, _, _, _, url) in installer_rows(results) {
+                println!("{}", download_command(tool, url, download_dir));
+            }
+            return;
+        }
+        OutputFormat::Urls => {
+            for (_, _, _, _, url) in installer_rows(results) {
+                println!("{}", url);
+            }
+            return;
+        }
+        OutputFormat::LatestUrls => {
+            for url in newest_urls(results).values() {
+                println!("{}", url);
+            }
+            return;
+        }
+        OutputFormat::Oneline => {
+            println!("{}", oneline(versions, results, elapsed));
+            return;
+        }
+    };
+    println!("{}", json_output);
+}
+
+/// A row of [`installer_rows`]: platform, version, build, last modified and URL.
+type InstallerRow<'a> = (&'static str, &'a str, String, &'a str, &'a str);
+
+/// Every installer found, sorted by platform and then newest build first.
+fn installer_rows(results: &[VersionResult]) -> Vec<InstallerRow<'_>> {
+    let mut rows: Vec<InstallerRow> = results
+        .iter()
+        .flat_map(|result| {
+            result.found.iter().map(|installer| {
+                let build =
+                    build_number(&installer.url).map_or_else(|| "?".to_string(), |b| b.to_string());
+                let last_modified = installer.last_modified.as_deref().unwrap_or("-");
+                (
+                    installer.platform.code(),
+                    result.version.as_str(),
+                    build,
+                    last_modified,
+                    installer.url.as_str(),
+                )
+            })
+        })
+        .collect();
+    rows.sort_by(|a, b| {
+        let build = |row: &InstallerRow| build_number(row.4).unwrap_or(0);
+        a.0.cmp(b.0)
+            .then(build(b).cmp(&build(a)))
+            .then_with(|| a.1.cmp(b.1))
+    });
+    rows
+}
+
+/// Every installer found as aligned columns. The version column is left out
+/// for a single version.
+fn print_table(results: &[VersionResult], single_version: bool) {
+    let rows = installer_rows(results);
+    if rows.is_empty() {
+        println!("No installers found.");
+        return;
+    }
+
+    let mut header = vec![
+        ("PLATFORM", Align::Left),
+        ("VERSION", Align::Left),
+        ("BUILD", Align::Right),
+        ("LAST MODIFIED", Align::Left),
+        ("URL", Align::Left),
+    ];
+    let mut cells: Vec<Vec<String>> = rows
+        .iter()
+        .map(|(platform, version, build, modified, url)| {
+            vec![
+                platform.to_string(),
+                version.to_string(),
+                build.clone(),
+                modified.to_string(),
+                url.to_string(),
+            ]
+        })
+        .collect();
+    if single_version {
+        header.remove(1);
+        for row in &mut cells {
+            row.remove(1);
+        }
+    }
+    print_columns(&header, &cells);
+}
+
+/// How a column of [`table_lines`] lines up its cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Align {
+    Left,
+    Right,
+}
+
+/// `header` and `rows` as columns two spaces apart, each as wide as its
+/// widest cell, without trailing blanks.
+fn table_lines(header: &[(&str, Align)], rows: &[Vec<String>]) -> Vec<String> {
+    let widths: Vec<usize> = header
+        .iter()
+        .enumerate()
+        .map(|(i, (name, _))| {
+            rows.iter()
+                .filter_map(|row| row.get(i))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or(0)
+                .max(name.chars().count())
+        })
+        .collect();
+    let line = |cells: Vec<&str>| {
+        let padded: Vec<String> = cells
+            .into_iter()
+            .zip(header.iter().zip(&widths))
+            .map(|(cell, ((_, align), &width))| match align {
+                Align::Left => format!("{:<w$}", cell, w = width),
+                Align::Right => format!("{:>w$}", cell, w = width),
+            })
+            .collect();
+        padded.join("  ").trim_end().to_string()
+    };
+
+    let mut lines = vec![line(header.iter().map(|(name, _)| *name).collect())];
+    for row in rows {
+        lines.push(line(row.iter().map(String::as_str).collect()));
+    }
+    lines
+}
+
+/// Prints [`table_lines`].
+fn print_columns(header: &[(&str, Align)], rows: &[Vec<String>]) {
+    for line in table_lines(header, rows) {
+        println!("{}", line);
+    }
+}
+
+/// Exits with an error when `flag` is `enabled` but `format` is neither a
+/// table nor JSON.
+fn require_table_or_json(flag: &str, enabled: bool, format: OutputFormat) {
+    if enabled
+        && !matches!(
+            format,
+            OutputFormat::Table | OutputFormat::Json | OutputFormat::Pretty
+        )
+    {
+        error!(
+            "unsupported_format",
+            "{} prints a table or JSON; use --output-format table, json or pretty.", flag
+        );
+        std::process::exit(1);
+    }
+}
+
+/// `{version: {platform: [{build, url, extension, size, last_modified}, ...]}}` with every build found,
+/// newest first. Keys are sorted, so two runs diff cleanly.
+fn grouped_json(results: &[VersionResult]) -> Value {
+    let mut versions = Map::new();
+    for result in results {
+        let mut found: Vec<_> = result
+            .found
+            .iter()
+            .map(|installer| {
+                (
+                    installer.platform.code(),
+                    build_number(&installer.url).unwrap_or(0),
+                    &installer.url,
+                    &installer.size,
+                    &installer.last_modified,
+                )
+            })
+            .collect();
+        found.sort_by(|a, b| a.0.cmp(b.0).then(b.1.cmp(&a.1)).then_with(|| a.2.cmp(b.2)));
+
+        let mut platforms = Map::new();
+        for (code, build, url, size, last_modified) in found {
+            if let Value::Array(builds) = platforms.entry(code).or_insert_with(|| json!([])) {
+                builds.push(json!({
+                    "build": build, "url": url, "extension": installer_extension(url), "size": size,
+                    "last_modified": last_modified,
+                }));
+            }
+        }
+        versions.insert(result.version.clone(), Value::Object(platforms));
+    }
+    Value::Object(versions)
+}
+
+/// `{version: {"platforms": {platform: [first, last] or null}, "skipped": [platform, ...]}}`: the
+/// builds each platform was really checked from and to. Empty for cached results.
+fn searched_json(results: &[VersionResult]) -> Value {
+    let mut versions = Map::new();
+    for result in results {
+        let Some(searched) = &result.searched else {
+            continue;
+        };
+        let platforms: Map<String, Value> = searched
+            .platforms
+            .iter()
+            .map(|platform| (platform.platform.code().to_string(), json!(platform.builds)))
+            .collect();
+        let skipped: Vec<&str> = searched.skipped.iter().map(Platform::code).collect();
+        versions.insert(
+            result.version.clone(),
+            json!({ "platforms": platforms, "skipped": skipped }),
+        );
+    }
+    Value::Object(versions)
+}
+
+/// Per platform, every build found for any of `versions`, with the indexes of the versions it was
+/// found for.
+type BuildComparison = BTreeMap<Platform, BTreeMap<i32, Vec<usize>>>;
+
+fn compare_builds(versions: &[String], results: &[VersionResult]) -> BuildComparison {
+    let mut comparison = BuildComparison::new();
+    for (index, version) in versions.iter().enumerate() {
+        let Some(result) = results.iter().find(|result| &result.version == version) else {
+            continue;
+        };
+        for FoundInstaller { url, platform, .. } in &result.found {
+            let Some(build) = build_number(url) else {
+                continue;
+            };
+            let found_for = comparison
+                .entry(*platform)
+                .or_default()
+                .entry(build)
+                .or_default();
+            if !found_for.contains(&index) {
+                found_for.push(index);
+            }
+        }
+    }
+    comparison
+}
+
+/// Builds found per platform of each version, in [`Platform::all`] order. Platforms the search
+/// covered without a hit count 0; cached results only know the ones with hits.
+fn build_counts(result: &VersionResult) -> Vec<(Platform, usize)> {
+    Platform::all()
+        .into_iter()
+        .filter_map(|platform| {
+            let count = result
+                .found
+                .iter()
+                .filter(|installer| installer.platform == platform)
+                .count();
+            let searched = result
+                .searched
+                .as_ref()
+                .is_some_and(|searched| searched.platforms.iter().any(|s| s.platform == platform));
+            (count > 0 || searched).then_some((platform, count))
+        })
+        .collect()
+}
+
+/// `--count-only`: the tally of [`build_counts`] and its total instead of the URLs.
+fn print_counts(
+    output_format: OutputFormat,
+    versions: &[String],
+    results: &[VersionResult],
+    stats: Option<&RunStats>,
+) {
+    let counts: Vec<(&str, Vec<(Platform, usize)>)> = results
+        .iter()
+        .map(|result| (result.version.as_str(), build_counts(result)))
+        .collect();
+    let total: usize = counts
+        .iter()
+        .flat_map(|(_, platforms)| platforms)
+        .map(|(_, count)| count)
+        .sum();
+
+    if output_format != OutputFormat::Table {
+        let mut by_version = Map::new();
+        for (version, platforms) in &counts {
+            let platforms: Map<String, Value> = platforms
+                .iter()
+                .map(|(platform, count)| (platform.code().to_string(), json!(count)))
+                .collect();
+            by_version.insert(version.to_string(), Value::Object(platforms));
+        }
+        let output = json!({
+            "schema_version": SCHEMA_VERSION,
+            "versions": versions,
+            "counts": by_version,
+            "total": total,
+            "stats": stats,
+            "warnings": *WARNINGS.lock().unwrap(),
+        });
+        match output_format {
+            OutputFormat::Json => println!("{}", output),
+            _ => println!("{}", serde_json::to_string_pretty(&output).unwrap()),
+        }
+        return;
+    }
+
+    let mut header = vec![
+        ("VERSION", Align::Left),
+        ("PLATFORM", Align::Left),
+        ("BUILDS", Align::Right),
+    ];
+    let mut rows: Vec<Vec<String>> = counts
+        .iter()
+        .flat_map(|(version, platforms)| {
+            platforms.iter().map(|(platform, count)| {
+                vec![
+                    version.to_string(),
+                    platform.code().to_string(),
+                    count.to_string(),
+                ]
+            })
+        })
+        .collect();
+    if versions.len() == 1 {
+        header.remove(0);
+        for row in &mut rows {
+            row.remove(0);
+        }
+        rows.push(vec!["TOTAL".to_string(), total.to_string()]);
+    } else {
+        rows.push(vec!["TOTAL".to_string(), String::new(), total.to_string()]);
+    }
+    print_columns(&header, &rows);
+}
+
+/// `--unique-builds`: the distinct build numbers found per platform across all versions, as
+/// one row per platform or `{platform: [build, ...]}`.
+fn print_unique_builds(
+    output_format: OutputFormat,
+    versions: &[String],
+    results: &[VersionResult],
+    stats: Option<&RunStats>,
+) {
+    let unique: Vec<(Platform, Vec<i32>)> = compare_builds(versions, results)
+        .into_iter()
+        .map(|(platform, builds)| (platform, builds.into_keys().collect()))
+        .collect();
+    let total: usize = unique.iter().map(|(_, builds)| builds.len()).sum();
+
+    if output_format != OutputFormat::Table {
+        let platforms: Map<String, Value> = unique
+            .iter()
+            .map(|(platform, builds)| (platform.code().to_string(), json!(builds)))
+            .collect();
+        let output = json!({
+            "schema_version": SCHEMA_VERSION,
+            "versions": versions,
+            "unique_builds": platforms,
+            "total": total,
+            "stats": stats,
+            "warnings": *WARNINGS.lock().unwrap(),
+        });
+        match output_format {
+            OutputFormat::Json => println!("{}", output),
+            _ => println!("{}", serde_json::to_string_pretty(&output).unwrap()),
+        }
+        return;
+    }
+
+    if unique.is_empty() {
+        println!("No installers found.");
+        return;
+    }
+    let mut rows: Vec<Vec<String>> = unique
+        .iter()
+        .map(|(platform, builds)| {
+            let numbers: Vec<String> = builds.iter().map(i32::to_string).collect();
+            vec![
+                platform.code().to_string(),
+                builds.len().to_string(),
+                numbers.join(", "),
+            ]
+        })
+        .collect();
+    rows.push(vec!["TOTAL".to_string(), total.to_string(), String::new()]);
+    print_columns(
+        &[
+            ("PLATFORM", Align::Left),
+            ("BUILDS", Align::Right),
+            ("NUMBERS", Align::Left),
+        ],
+        &rows,
+    );
+}
+
+/// `--compare`: one row per platform and build with a column per version, or the same as
+/// `{platform: [{build, versions, missing}, ...]}`. Builds missing from some versions are marked.
+fn print_comparison(output_format: OutputFormat, versions: &[String], results: &[VersionResult]) {
+    let comparison = compare_builds(versions, results);
+    let missing = |found_for: &[usize]| -> Vec<&str> {
+        (0..versions.len())
+            .filter(|i| !found_for.contains(i))
+            .map(|i| versions[i].as_str())
+            .collect()
+    };
+
+    if output_format != OutputFormat::Table {
+        let mut platforms = Map::new();
+        for (platform, builds) in &comparison {
+            let rows: Vec<Value> = builds
+                .iter()
+                .map(|(build, found_for)| {
+                    json!({
+                        "build": build,
+                        "versions": found_for.iter().map(|&i| &versions[i]).collect::<Vec<_>>(),
+                        "missing": missing(found_for),
+                    })
+                })
+                .collect();
+            platforms.insert(platform.code().to_string(), Value::Array(rows));
+        }
+        let output = json!({
+            "schema_version": SCHEMA_VERSION,
+            "versions": versions,
+            "comparison": platforms,
+            "warnings": *WARNINGS.lock().unwrap(),
+        });
+        match output_format {
+            OutputFormat::Json => println!("{}", output),
+            _ => println!("{}", serde_json::to_string_pretty(&output).unwrap()),
+        }
+        return;
+    }
+
+    if comparison.is_empty() {
+        println!("No installers found.");
+        return;
+    }
+    let mut header = vec![("PLATFORM", Align::Left), ("BUILD", Align::Right)];
+    header.extend(
+        versions
+            .iter()
+            .map(|version| (version.as_str(), Align::Left)),
+    );
+    header.push(("NOTE", Align::Left));
+    let mut rows = Vec::new();
+    for (platform, builds) in &comparison {
+        for (build, found_for) in builds {
+            let mut row = vec![platform.code().to_string(), build.to_string()];
+            for i in 0..versions.len() {
+                let cell = if found_for.contains(&i) { "yes" } else { "-" };
+                row.push(cell.to_string());
+            }
+            row.push(match found_for[..] {
+                _ if found_for.len() == versions.len() => String::new(),
+                [only] => format!("only in {}", versions[only]),
+                _ => format!("missing from {}", missing(found_for).join(", ")),
+            });
+            rows.push(row);
+        }
+    }
+    print_columns(&header, &rows);
+}
+
+/// Rings the terminal bell and shows a desktop notification. Without a notification
+/// backend, e.g. no D-Bus session, the bell is all there is.
+fn notify_completion(found: u64, versions: usize, elapsed: Duration) {
+    eprint!("\x07");
+    let body = format!(
+        "Found {} installer(s) for {} version(s) in {}s",
+        found,
+        versions,
+        elapsed.as_secs()
+    );
+    let shown = notify_rust::Notification::new()
+        .appname("LoaderSpot")
+        .summary("Search finished")
+        .body(&body)
+        .show();
+    if let Err(e) = shown {
+        log::debug!("No desktop notification: {}", e);
+    }
+}
+
+/// Posts the latest installer of each platform to a Discord/Slack webhook.
+/// Nothing is sent when the search found nothing; failures are only logged.
+async fn send_webhook(client: &Client, webhook: &str, results: &[VersionResult]) {
+    let mut lines = Vec::new();
+    let mut installers = Vec::new();
+    for result in results {
+        let mut latest: Vec<(String, String)> = get_latest_urls(&result.found)
+            .into_iter()
+            .filter(|(platform, _)| platform != "unknown")
+            .collect();
+        latest.sort();
+
+        for (platform, url) in latest {
+            let build = build_number(&url).unwrap_or(0);
+            lines.push(
+                WEBHOOK_LINE_TEMPLATE
+                    .replace("{platform}", &platform)
+                    .replace("{version}", &result.version)
+                    .replace("{build}", &build.to_string())
+                    .replace("{url}", &url),
+            );
+            installers.push(json!({
+                "platform": platform,
+                "version": result.version,
+                "build": build,
+                "url": url,
+            }));
+        }
+    }
+
+    if lines.is_empty() {
+        return;
+    }
+
+    let text = format!(
+        "Found {} Spotify installer(s):\n{}",
+        lines.len(),
+        lines.join("\n")
+    );
+    // Discord reads `content`, Slack reads `text`; both ignore the rest.
+    let payload = json!({
+        "content": text,
+        "text": text,
+        "installers": installers,
+    });
+
+    match client.post(webhook).json(&payload).send().await {
+        Ok(response) if response.status().is_success() => {}
+        Ok(response) => warning!("webhook_failed", "webhook returned {}", response.status()),
+        Err(e) => warning!("webhook_failed", "webhook failed: {}", e),
+    }
+}
+
+fn results_to_cache(results: &[VersionResult]) -> ResultsCache {
+    let versions = results.iter().map(|r| r.version.clone()).collect();
+    let found = results
+        .iter()
+        .flat_map(|r| {
+            r.found.iter().map(|installer| CachedInstaller {
+                version: r.version.clone(),
+                platform: installer.platform,
+                build: build_number(&installer.url).unwrap_or(0),
+                url: installer.url.clone(),
+                size: installer.size,
+                last_modified: installer.last_modified.clone(),
+            })
+        })
+        .collect();
+    ResultsCache::new(versions, found)
+}
+
+/// The http(s) URLs listed in --urls-file, or stdin for `-`, without duplicates.
+/// Blank lines and lines starting with # are skipped.
+fn collect_urls(path: &Path) -> Result<Vec<String>, String> {
+    let text = if path == Path::new("-") {
+        io::read_to_string(io::stdin())
+            .map_err(|e| format!("Cannot read URLs from stdin: {}", e))?
+    } else {
+        fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?
+    };
+    let mut urls: Vec<String> = Vec::new();
+    for line in version_list_lines(&text) {
+        if !line.starts_with("http://") && !line.starts_with("https://") {
+            warning!("invalid_url", "skipping '{}', not an http(s) URL", line);
+        } else if !urls.iter().any(|url| url == line) {
+            urls.push(line.to_string());
+        }
+    }
+    Ok(urls)
+}
+
+/// `--urls-file`: checks the listed URLs as they are, with the search's connection limit,
+/// rate limit and retries but no versions, platforms or build ranges.
+async fn check_url_list(cli: &Cli, path: &Path) {
+    let urls = match collect_urls(path) {
+        Ok(urls) if !urls.is_empty() => urls,
+        Ok(_) => {
+            error!("no_urls", "No URLs in {}.", path.display());
+            std::process::exit(1);
+        }
+        Err(e) => {
+            error!("bad_urls_file", "{}", e);
+            std::process::exit(1);
+        }
+    };
+    let connections = checked_connections(cli);
+    let engine = list_engine(cli, connections);
+    status!(
+        "Checking {} URL(s) at {} connections.",
+        urls.len(),
+        connections
+    );
+    let pb = progress_bar(urls.len() as u64);
+    let progress_ticker = progress_ticker(&engine, &pb);
+    let checked = engine.check_urls(&urls).await;
+    progress_ticker.abort();
+    pb.finish_and_clear();
+
+    report_budget(&engine.budget);
+    report_content_types(&engine.unexpected_types, cli.require_content_type);
+    print_url_checks(cli.output_format(), &checked, cli.download.as_deref());
+    if engine.cancel_flag.load(Ordering::Relaxed) && !engine.budget.exhausted() {
+        std::process::exit(EXIT_INTERRUPTED);
+    }
+    if !checked.iter().any(|(_, probe)| probe.exists) {
+        std::process::exit(EXIT_NOTHING_FOUND);
+    }
+}
+
+/// An engine for checking a list of URLs, --urls-file or --rescan-failures, that Ctrl-C cancels.
+fn list_engine(cli: &Cli, connections: usize) -> Arc<SearchEngine> {
+    let mut engine = SearchEngine::new(search_client(cli), connections);
+    if let Some(budget) = cli.budget {
+        engine.budget = RequestBudget::new(budget);
+    }
+    if !cli.no_error_pause {
+        engine.error_storm = ErrorStorm::new(cli.error_window, cli.error_threshold);
+    }
+    if let Some(rps) = cli.rps {
+        engine.rate_limiter = RateLimiter::new(rps);
+    }
+    engine.jitter = Jitter::new(Duration::from_millis(cli.jitter_ms));
+    engine.require_content_type = cli.require_content_type;
+    if cli.ramp {
+        Ramp::default().apply(&engine.semaphore);
+    }
+    let engine = Arc::new(engine);
+    let cancel_flag = engine.cancel_flag.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            cancel_flag.store(true, Ordering::Relaxed);
+        }
+    });
+    engine
+}
+
+/// Moves `pb` along with the checks `engine` has done. Abort it when they are finished.
+fn progress_ticker(engine: &SearchEngine, pb: &ProgressBar) -> tokio::task::JoinHandle<()> {
+    let processed = engine.processed.clone();
+    let pb = pb.clone();
+    tokio::spawn(async move {
+        loop {
+            pb.set_position(processed.load(Ordering::Relaxed));
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    })
+}
+
+/// --rescan-failures: checks the failed checks saved in the checkpoint at `path` again,
+/// prints the installers that turned up and keeps the checkpoint up to date.
+async fn rescan_failures(cli: &Cli, path: &Path) {
+    let mut checkpoint = match Checkpoint::load(path) {
+        Ok(checkpoint) => checkpoint,
+        Err(e) => {
+            error!("bad_checkpoint", "{}", e);
+            std::process::exit(1);
+        }
+    };
+    if checkpoint.failed.is_empty() {
+        status!("No failed checks in {}; nothing to rescan.", path.display());
+        return;
+    }
+    let connections = checked_connections(cli);
+    let engine = list_engine(cli, connections);
+    let failed = std::mem::take(&mut checkpoint.failed);
+    status!(
+        "Rescanning {} failed check(s) at {} connections.",
+        failed.len(),
+        connections
+    );
+    let pb = progress_bar(failed.len() as u64);
+    let progress_ticker = progress_ticker(&engine, &pb);
+    let rescan = engine.rescan_failures(&failed).await;
+    progress_ticker.abort();
+    pb.finish_and_clear();
+
+    status!(
+        "Recovered {} of {} failed check(s), {} still failing; {} installer(s) found.",
+        rescan.recovered,
+        failed.len(),
+        rescan.still_failing.len(),
+        rescan.found.len()
+    );
+    report_budget(&engine.budget);
+    report_content_types(&engine.unexpected_types, cli.require_content_type);
+    for installer in &rescan.found {
+        if !checkpoint
+            .found
+            .iter()
+            .any(|known| known.url == installer.url)
+        {
+            checkpoint.found.push(installer.clone());
+        }
+    }
+    checkpoint.failed = rescan.still_failing;
+    let finished = checkpoint
+        .params
+        .versions
+        .iter()
+        .all(|version| checkpoint.is_completed(version));
+    if finished && checkpoint.failed.is_empty() {
+        if let Err(e) = fs::remove_file(path) {
+            error!(
+                "checkpoint_not_removed",
+                "Cannot remove checkpoint {}: {}",
+                path.display(),
+                e
+            );
+        }
+    } else if let Err(e) = checkpoint.save(path) {
+        error!("checkpoint_not_saved", "{}", e);
+    }
+
+    let versions = checkpoint.params.versions.clone();
+    let results: Vec<VersionResult> = versions
+        .iter()
+        .map(|version| VersionResult {
+            version: version.clone(),
+            found: rescan
+                .found
+                .iter()
+                .filter(|installer| &installer.version == version)
+                .map(FoundInstaller::from)
+                .collect(),
+            searched: None,
+        })
+        .collect();
+    print_results(
+        cli.output_format(),
+        cli.output_shape,
+        &versions,
+        &results,
+        None,
+        None,
+        cli.download.as_deref(),
+    );
+    if engine.cancel_flag.load(Ordering::Relaxed) && !engine.budget.exhausted() {
+        std::process::exit(EXIT_INTERRUPTED);
+    }
+    if !found_anything(&results) {
+        std::process::exit(EXIT_NOTHING_FOUND);
+    }
+}
+
+/// Every URL of --urls-file with its status; the download formats only list the live ones.
+fn print_url_checks(
+    output_format: OutputFormat,
+    checked: &[(String, Probe)],
+    download_dir: Option<&Path>,
+) {
+    let entry = |url: &str, probe: &Probe| {
+        json!({
+            "url": url,
+            "exists": probe.exists,
+            "status": probe.status,
+            "final_url": probe.url,
+            "size": probe.content_length,
+            "last_modified": probe.last_modified,
+            "content_type": probe.content_type,
+        })
+    };
+    let live = checked
+        .iter()
+        .filter(|(_, probe)| probe.exists)
+        .map(|(_, probe)| probe.url.as_str());
+    match output_format {
+        OutputFormat::Json | OutputFormat::Pretty => {
+            let output = json!({
+                "schema_version": SCHEMA_VERSION,
+                "checked": checked.iter().map(|(url, probe)| entry(url, probe)).collect::<Vec<_>>(),
+                "warnings": *WARNINGS.lock().unwrap(),
+            });
+            if output_format == OutputFormat::Json {
+                println!("{}", output);
+            } else {
+                println!("{}", serde_json::to_string_pretty(&output).unwrap());
+            }
+        }
+        OutputFormat::Ndjson => {
+            for (url, probe) in checked {
+                println!("{}", entry(url, probe));
+            }
+        }
+        OutputFormat::Table => {
+            let sizes: Vec<String> = checked
+                .iter()
+                .map(|(_, probe)| {
+                    probe
+                        .content_length
+                        .map_or_else(|| "-".to_string(), |size| size.to_string())
+                })
+                .collect();
+            let size_width = sizes
+                .iter()
+                .map(String::len)
+                .max()
+                .unwrap_or(0)
+                .max("SIZE".len());
+            let modified_width = checked
+                .iter()
+                .map(|(_, probe)| probe.last_modified.as_deref().map_or(1, str::len))
+                .max()
+                .unwrap_or(0)
+                .max("LAST MODIFIED".len());
+            println!(
+                "{:<6}  {:>sw$}  {:<mw$}  URL",
+                "STATUS",
+                "SIZE",
+                "LAST MODIFIED",
+                sw = size_width,
+                mw = modified_width
+            );
+            for ((url, probe), size) in checked.iter().zip(&sizes) {
+                let status = if probe.status == 0 {
+                    "-".to_string()
+                } else {
+                    probe.status.to_string()
+                };
+                let shown = if probe.url != *url {
+                    format!("{} -> {}", url, probe.url)
+                } else {
+                    url.clone()
+                };
+                println!(
+                    "{:<6}  {:>sw$}  {:<mw$}  {}",
+                    status,
+                    size,
+                    probe.last_modified.as_deref().unwrap_or("-"),
+                    shown,
+                    sw = size_width,
+                    mw = modified_width
+                );
+            }
+        }
+        OutputFormat::Curl | OutputFormat::Wget => {
+            let tool = if output_format == OutputFormat::Wget {
+                Downloader::Wget
+            } else {
+                Downloader::Curl
+            };
+            for url in live {
+                println!("{}", download_command(tool, url, download_dir));
+            }
+        }
+        OutputFormat::Urls | OutputFormat::LatestUrls => {
+            for url in live {
+                println!("{}", url);
+            }
+        }
+        OutputFormat::Oneline => {
+            println!(
+                "{} checked={} live={}",
+                now_iso8601(),
+                checked.len(),
+                live.count()
+            );
+        }
+    }
+}
+
+fn now_iso8601() -> String {
+    format_iso8601(
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs()),
+    )
+}
+
+/// E.g. `2024-01-02T03:04:05Z version=1.2.60.564.gcc6305cb found=4 platforms=WIN64,OSX-ARM64 elapsed=12.3s`,
+/// with every version comma-separated; `elapsed` is left out for cached results.
+fn oneline(versions: &[String], results: &[VersionResult], elapsed: Option<Duration>) -> String {
+    let found: usize = results.iter().map(|result| result.found.len()).sum();
+    let platforms: Vec<&str> = Platform::all()
+        .into_iter()
+        .filter(|platform| {
+            results.iter().any(|result| {
+                result
+                    .found
+                    .iter()
+                    .any(|installer| installer.platform == *platform)
+            })
+        })
+        .map(|platform| platform.code())
+        .collect();
+    let platforms = if platforms.is_empty() {
+        "-".to_string()
+    } else {
+        platforms.join(",")
+    };
+    let mut line = format!(
+        "{} version={} found={} platforms={}",
+        now_iso8601(),
+        versions.join(","),
+        found,
+        platforms
+    );
+    if let Some(elapsed) = elapsed {
+        line.push_str(&format!(" elapsed={:.1}s", elapsed.as_secs_f64()));
+    }
+    line
+}
+
+fn print_cached_results(cli: &Cli) {
+    let path = cli.cache_file.as_deref().unwrap();
+    let cache = match ResultsCache::load(path) {
+        Ok(cache) => cache,
+        Err(e) => {
+            error!("bad_cache", "{}", e);
+            std::process::exit(1);
+        }
+    };
+    status!(
+        "Using results cached {} ({})",
+        format_age(cache.age()),
+        path.display()
+    );
+
+    let results: Vec<VersionResult> = cache
+        .versions
+        .iter()
+        .filter(|version| cli.version.is_empty() || cli.version.contains(version))
+        .map(|version| VersionResult {
+            version: version.clone(),
+            found: cache
+                .found
+                .iter()
+                .filter(|installer| &installer.version == version)
+                .filter(|installer| {
+                    cli.min_size
+                        .is_none_or(|min_size| installer.size.is_none_or(|size| size >= min_size))
+                })
+                .map(FoundInstaller::from)
+                .collect(),
+            searched: None,
+        })
+        .collect();
+
+    let versions: Vec<String> = results
+        .iter()
+        .map(|result| result.version.clone())
+        .collect();
+    warn_about_small_installers(&results);
+    if cli.compare {
+        if versions.len() < 2 {
+            error!(
+                "not_enough_versions",
+                "--compare needs at least two versions; the cache has {}.",
+                versions.len()
+            );
+            std::process::exit(1);
+        }
+        print_comparison(cli.output_format(), &versions, &results);
+    } else if cli.count_only {
+        print_counts(cli.output_format(), &versions, &results, None);
+    } else if cli.unique_builds {
+        print_unique_builds(cli.output_format(), &versions, &results, None);
+    } else {
+        print_results(
+            cli.output_format(),
+            cli.output_shape,
+            &versions,
+            &results,
+            None,
+            None,
+            cli.download.as_deref(),
+        );
+    }
+
+    if !found_anything(&results) {
+        std::process::exit(EXIT_NOTHING_FOUND);
+    }
+}
+
+/// A `FAIL <file>: <reason>` line, or a `verification_failed` error with --json-errors.
+fn verification_failed(name: &str, reason: &str) {
+    if JSON_ERRORS.load(Ordering::Relaxed) {
+        report_problem(
+            "error",
+            "verification_failed",
+            &format!("{}: {}", name, reason),
+        );
+    } else {
+        eprintln!("FAIL {}: {}", name, reason);
+    }
+}
+
+/// Downloads the latest installer of every platform found, one at a time,
+/// checking each file against `manifest` when one is given. Returns the number
+/// of files that failed to download or verify.
+async fn download_results(
+    client: &Client,
+    results: &[VersionResult],
+    dir: &Path,
+    manifest: Option<&Manifest>,
+) -> usize {
+    let mut failed = 0;
+    let bar_style = ProgressStyle::with_template(
+        "{msg} [{bar:40}] {bytes}/{total_bytes} ({bytes_per_sec}, {eta})",
+    )
+    .unwrap()
+    .progress_chars("=> ");
+
+    for result in results {
+        let latest_urls = get_latest_urls(&result.found);
+        let mut urls: Vec<&String> = latest_urls
+            .iter()
+            .filter(|(platform, _)| platform.as_str() != "unknown")
+            .map(|(_, url)| url)
+            .collect();
+        urls.sort();
+
+        for url in urls {
+            let bar = progress_bar(0);
+            bar.set_style(bar_style.clone());
+            bar.set_message(file_name_from_url(url).unwrap_or(url).to_string());
+
+            let outcome = download_installer(client, url, dir, |downloaded, total| {
+                if let Some(total) = total {
+                    bar.set_length(total);
+                }
+                bar.set_position(downloaded);
+            })
+            .await;
+
+            let path = match outcome {
+                Ok(path) => {
+                    bar.finish_and_clear();
+                    status!("Saved {}", path.display());
+                    path
+                }
+                Err(e) => {
+                    bar.abandon();
+                    error!("download_failed", "{}", e);
+                    failed += 1;
+                    continue;
+                }
+            };
+
+            let Some(manifest) = manifest else {
+                continue;
+            };
+            let size = result
+                .found
+                .iter()
+                .find(|installer| &installer.url == url)
+                .and_then(|installer| installer.size);
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            match manifest.verify(&path, size) {
+                Ok(Verification::Pass { .. }) => status!("PASS {}", name),
+                Ok(Verification::Fail { reason, .. }) => {
+                    verification_failed(&name, &reason);
+                    failed += 1;
+                }
+                Ok(Verification::Unlisted { sha256 }) => {
+                    status!("NEW  {}: sha256 {} (not in manifest)", name, sha256);
+                }
+                Err(e) => {
+                    verification_failed(&name, &e);
+                    failed += 1;
+                }
+            }
+        }
+    }
+
+    failed
+}
+
+struct VersionResult {
+    version: String,
+    found: Vec<FoundInstaller>,
+    /// What the search covered; `None` for cached results.
+    searched: Option<SearchedVersion>,
+}
+
+impl VersionResult {
+    /// `{platform: url, ..., "version", "details": {platform: {size, last_modified}}}`. Metadata
+    /// stays under `details` so the top level is only URLs plus `version`.
+    fn to_json(&self) -> Map<String, Value> {
+        let latest_urls = get_latest_urls(&self.found);
+        let details = get_latest_details(&latest_urls, &self.found);
+
+        let mut entry: Map<String, Value> = latest_urls
+            .into_iter()
+            .map(|(platform, url)| (platform, Value::String(url)))
+            .collect();
+        entry.insert("version".to_string(), Value::String(self.version.clone()));
+        if !details.is_empty() {
+            entry.insert("details".to_string(), Value::Object(details));
+        }
+        entry
+    }
+}
+
+/// Drops Windows x86 for versions that no longer ship it. Returns `None`, after
+/// warning, when that leaves a single-version search with nothing to do.
+fn platforms_for_version(
+    version: &str,
+    platform_arches: &[Platform],
+    single_version: bool,
+) -> Option<Vec<Platform>> {
+    let mut arches_to_search = platform_arches.to_vec();
+
+    if !should_use_win_x86(version) && arches_to_search.contains(&Platform::WinX86) {
+        if single_version && arches_to_search.len() == 1 {
+            warning!("x86_unsupported", "x86 architecture for Windows is no longer supported for versions newer than 1.2.53.");
+            return None;
+        }
+        arches_to_search.retain(|&p| p != Platform::WinX86);
+    }
+
+    Some(arches_to_search)
+}
+
+/// Requests a search over `versions` sends at most; exact unless it is a ladder
+/// search or `try_dmg` is set, where every macOS miss may cost a `.dmg` request.
+fn expected_requests(
+    versions: &[String],
+    platform_arches: &[Platform],
+    ladder: Option<LadderOptions>,
+    ranges: &[(i32, i32)],
+    step: u32,
+    try_dmg: bool,
+) -> u64 {
+    let builds = match ladder {
+        Some(ladder) => ladder.max_builds().div_ceil(step as u64),
+        None => count_builds(ranges, step),
+    };
+    versions
+        .iter()
+        .filter_map(|version| platforms_for_version(version, platform_arches, false))
+        .flatten()
+        .map(|platform| {
+            let has_dmg = try_dmg && platform.dmg_path_template().is_some();
+            if has_dmg {
+                builds * 2
+            } else {
+                builds
+            }
+        })
+        .sum()
+}
+
+/// Prints every URL a search would check, without any requests. The ladder
+/// search only ever checks its first window up front, so only that is listed.
+fn print_dry_run(
+    versions: &[String],
+    platform_arches: &[Platform],
+    ladder: Option<LadderOptions>,
+    ranges: &[(i32, i32)],
+    step: u32,
+    templates: &PathTemplates,
+    try_dmg: bool,
+) {
+    let first_window = ladder.map(|ladder| [(0, ladder.initial_window)]);
+    let ranges = match &first_window {
+        Some(first_window) => &first_window[..],
+        None => ranges,
+    };
+    let single_version = versions.len() == 1;
+
+    let mut total = 0;
+    for version in versions {
+        let Some(platforms) = platforms_for_version(version, platform_arches, single_version)
+        else {
+            continue;
+        };
+        for platform in platforms {
+            for &(start, end) in ranges {
+                for url in generate_urls(version, start, end, step, &[platform], templates) {
+                    println!("{}", url);
+                    total += 1;
+                }
+                if try_dmg {
+                    for path in (start..=end)
+                        .step_by(step as usize)
+                        .filter_map(|number| platform.generate_dmg_path(version, number))
+                    {
+                        println!("{}{}", BASE_URL, path);
+                        total += 1;
+                    }
+                }
+            }
+        }
+    }
+    status!("Total: {} requests", total);
+}
+
+async fn search_version(
+    engine: &Arc<SearchEngine>,
+    version: String,
+    platform_arches: Vec<Platform>,
+    single_version: bool,
+    strategy: Strategy,
+    ranges: &[(i32, i32)],
+    resumed: Option<&Checkpoint>,
+) -> Option<VersionResult> {
+    let version = &version;
+    let mut all_found_urls_for_version = Vec::new();
+    let arches_to_search = platforms_for_version(version, &platform_arches, single_version)?;
+
+    if let Strategy::Ladder(ladder) = strategy {
+        let found = engine
+            .ladder_search(version, &arches_to_search, ladder)
+            .await;
+        all_found_urls_for_version.extend(found);
+    } else if let Strategy::AutoRange = strategy {
+        for &(start, end) in ranges {
+            all_found_urls_for_version.extend(
+                engine
+                    .auto_range_search(version, start, end, &arches_to_search)
+                    .await,
+            );
+        }
+    } else {
+        // Every platform at once; the engine's semaphore still caps the connections in total.
+        let mut platform_tasks = Vec::new();
+        for &platform_arch in &arches_to_search {
+            let ranges = match resumed {
+                Some(resumed) => resumed.remaining_ranges(version, platform_arch, ranges),
+                None => ranges.to_vec(),
+            };
+            let engine = engine.clone();
+            let version = version.clone();
+            platform_tasks.push(tokio::spawn(async move {
+                engine
+                    .search_ranges(&version, &ranges, &[platform_arch])
+                    .await
+            }));
+        }
+        for task in platform_tasks {
+            all_found_urls_for_version.extend(task.await.unwrap());
+        }
+    }
+
+    let skipped: Vec<Platform> = platform_arches
+        .iter()
+        .copied()
+        .filter(|platform| !arches_to_search.contains(platform))
+        .collect();
+    Some(VersionResult {
+        version: version.clone(),
+        found: all_found_urls_for_version,
+        searched: Some(SearchedVersion::new(
+            version,
+            &arches_to_search,
+            skipped,
+            &engine.scanned_builds,
+        )),
+    })
+}
+
+/// File extension of an installer URL: `exe`, `tbz`, or `dmg` with --try-dmg.
+fn installer_extension(url: &str) -> Option<&str> {
+    file_name_from_url(url)?
+        .rsplit_once('.')
+        .map(|(_, extension)| extension)
+}
+
+/// Build number at the end of an installer URL, e.g. 1234 in `...-1234.exe`.
+/// Any extension is accepted, as path templates can be overridden.
+fn build_number(url: &str) -> Option<i32> {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let version_pattern = RE.get_or_init(|| Regex::new(r"-(\d+)\.[A-Za-z0-9]+$").unwrap());
+    version_pattern.captures(url)?.get(1)?.as_str().parse().ok()
+}
+
+/// The newest installer URL of each platform across all versions, keyed by
+/// platform code. A newer version wins over a higher build of an older one.
+fn newest_urls(results: &[VersionResult]) -> BTreeMap<String, String> {
+    let version_key = |version: &str| -> Vec<u32> {
+        version
+            .split('.')
+            .map_while(|part| part.parse().ok())
+            .collect()
+    };
+    let mut newest: BTreeMap<String, (Vec<u32>, String)> = BTreeMap::new();
+    for result in results {
+        let key = version_key(&result.version);
+        for (platform, url) in get_latest_urls(&result.found) {
+            if platform == "unknown" {
+                continue;
+            }
+            match newest.get(&platform) {
+                Some((newest_key, _)) if *newest_key >= key => {}
+                _ => {
+                    newest.insert(platform, (key.clone(), url));
+                }
+            }
+        }
+    }
+    newest
+        .into_iter()
+        .map(|(platform, (_, url))| (platform, url))
+        .collect()
+}
+
+fn get_latest_urls(found_urls: &[FoundInstaller]) -> HashMap<String, String> {
+    let mut platform_urls = HashMap::new();
+
+    for FoundInstaller { url, platform, .. } in found_urls {
+        if let Some(version_number) = build_number(url) {
+            let platform_key = platform.code().to_string();
+
+            let entry = platform_urls
+                .entry(platform_key)
+                .or_insert_with(|| (url.clone(), version_number));
+
+            if version_number > entry.1 {
+                *entry = (url.clone(), version_number);
+            }
+        }
+    }
+
+    let latest_urls: HashMap<String, String> = platform_urls
+        .into_iter()
+        .map(|(k, (v, _))| (k, v))
+        .collect();
+
+    if latest_urls.is_empty() {
+        let mut empty_map = HashMap::new();
+        empty_map.insert("unknown".to_string(), "unknown".to_string());
+        return empty_map;
+    }
+
+    latest_urls
+}
+
+/// Size and Last-Modified (ISO 8601) of each latest URL, `null` where the server didn't send one.
+fn get_latest_details(
+    latest_urls: &HashMap<String, String>,
+    found_urls: &[FoundInstaller],
+) -> Map<String, Value> {
+    latest_urls
+        .iter()
+        .filter_map(|(platform, url)| {
+            found_urls
+                .iter()
+                .find(|installer| &installer.url == url)
+                .map(|installer| {
+                    let detail =
+                        json!({"size": installer.size, "last_modified": installer.last_modified});
+                    (platform.clone(), detail)
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OLD: &str = "1.2.40.599.g606b7f29";
+    const NEW: &str = "1.2.60.500.gaaaaaaaa";
+
+    fn installer(
+        platform: Platform,
+        version: &str,
+        number: i32,
+        size: Option<u64>,
+    ) -> FoundInstaller {
+        FoundInstaller {
+            url: format!("{}{}", BASE_URL, platform.generate_path(version, number)),
+            platform,
+            size,
+            last_modified: None,
+            content_type: None,
+        }
+    }
+
+    fn result(version: &str, found: Vec<FoundInstaller>) -> VersionResult {
+        VersionResult {
+            version: version.to_string(),
+            found,
+            searched: None,
+        }
+    }
+
+    #[test]
+    fn collect_versions_merges_flags_and_files_without_duplicates() {
+        let path =
+            std::env::temp_dir().join(format!("loaderspot-versions-{}.txt", std::process::id()));
+        fs::write(&path, format!("# known\n{}\nnot a version\n{}\n", OLD, NEW)).unwrap();
+        let cli = Cli::try_parse_from([
+            "loaderspot_cli",
+            "--version",
+            NEW,
+            "--version-range",
+            "1.2.61.1..1.2.61.2",
+            "--versions-file",
+            path.to_str().unwrap(),
+        ])
+        .unwrap();
+
+        let versions = collect_versions(&cli);
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(
+            versions.unwrap(),
+            [NEW, "1.2.61.1", "1.2.61.2", OLD].map(String::from)
+        );
+    }
+
+    #[test]
+    fn collect_versions_reports_an_unreadable_file() {
+        let cli = Cli::try_parse_from([
+            "loaderspot_cli",
+            "--versions-file",
+            "/nonexistent/loaderspot-versions.txt",
+        ])
+        .unwrap();
+
+        let err = collect_versions(&cli).unwrap_err();
+        assert!(
+            err.starts_with("Cannot read /nonexistent/loaderspot-versions.txt"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn to_json_keeps_the_newest_url_per_platform_and_nests_the_details() {
+        let newest = installer(Platform::WinX64, NEW, 20, Some(90));
+        let result = result(
+            NEW,
+            vec![
+                installer(Platform::WinX64, NEW, 10, Some(80)),
+                newest.clone(),
+                installer(Platform::MacOsArm64, NEW, 5, None),
+            ],
+        );
+
+        let json = Value::Object(result.to_json());
+
+        assert_eq!(json["WIN64"], newest.url.as_str());
+        assert_eq!(json["version"], NEW);
+        assert_eq!(
+            json["details"]["WIN64"],
+            json!({"size": 90, "last_modified": null})
+        );
+        assert_eq!(json["details"]["OSX-ARM64"]["size"], Value::Null);
+        assert!(json.get("size").is_none() && json.get("last_modified").is_none());
+    }
+
+    #[test]
+    fn to_json_without_hits_has_no_details() {
+        let json = result(OLD, Vec::new()).to_json();
+
+        assert_eq!(json["unknown"], "unknown");
+        assert!(!json.contains_key("details"));
+    }
+
+    #[test]
+    fn run_stats_counts_a_file_found_under_two_versions_once() {
+        let engine = SearchEngine::new(Client::new(), 1);
+        engine.processed.store(10, Ordering::Relaxed);
+        engine.network_errors.store(2, Ordering::Relaxed);
+        let shared = installer(Platform::WinX64, OLD, 10, None);
+        let mut results = vec![
+            result(OLD, vec![shared.clone()]),
+            result(
+                NEW,
+                vec![shared, installer(Platform::WinX64, NEW, 11, None)],
+            ),
+        ];
+
+        dedup_across_versions(&mut results);
+        let stats = run_stats(&engine, &results);
+
+        assert_eq!(results[1].found.len(), 1);
+        assert_eq!(stats.builds_checked, 10);
+        assert_eq!(stats.hits, 2);
+        assert_eq!(stats.network_errors, 2);
+        assert_eq!(stats.misses, 6);
+    }
+
+    #[test]
+    fn expected_requests_drops_x86_after_1_2_53() {
+        let versions = [OLD.to_string(), NEW.to_string()];
+        let platforms = [Platform::WinX86, Platform::WinX64];
+
+        // 100 builds: two platforms for the old version, one for the new.
+        assert_eq!(
+            expected_requests(&versions, &platforms, None, &[(0, 99)], 1, false),
+            300
+        );
+        assert_eq!(
+            expected_requests(&versions, &platforms, None, &[(0, 99)], 10, false),
+            30
+        );
+    }
+
+    #[test]
+    fn expected_requests_counts_the_dmg_fallback_only_on_macos() {
+        let versions = [NEW.to_string()];
+        let platforms = [Platform::WinX64, Platform::MacOsIntel, Platform::MacOsArm64];
+
+        assert_eq!(
+            expected_requests(&versions, &platforms, None, &[(0, 9)], 1, false),
+            30
+        );
+        assert_eq!(
+            expected_requests(&versions, &platforms, None, &[(0, 9)], 1, true),
+            50
+        );
+    }
+
+    #[test]
+    fn expected_requests_takes_the_largest_ladder_search() {
+        let ladder = LadderOptions {
+            initial_window: 99,
+            increment: 50,
+            max_iterations: 2,
+        };
+
+        let total = expected_requests(
+            &[NEW.to_string()],
+            &[Platform::WinX64],
+            Some(ladder),
+            &[],
+            1,
+            false,
+        );
+        assert_eq!(total, 200);
+    }
 }
@@ -0,0 +1,45 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Shared "send nothing until" deadline, pushed out whenever the server
+/// rate-limits a request. Every search task waits on it before firing.
+#[derive(Clone, Default)]
+pub struct Backoff(Arc<Mutex<Option<Instant>>>);
+
+impl Backoff {
+    pub fn extend(&self, delay: Duration) {
+        let until = Instant::now() + delay;
+        let mut deadline = self.0.lock().unwrap();
+        if deadline.is_none_or(|current| current < until) {
+            *deadline = Some(until);
+        }
+    }
+
+    /// Time left before requests may resume, if a backoff is active.
+    pub fn remaining(&self) -> Option<Duration> {
+        let deadline = (*self.0.lock().unwrap())?;
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        (!remaining.is_zero()).then_some(remaining)
+    }
+
+    pub async fn wait(&self) {
+        while let Some(remaining) = self.remaining() {
+            tokio::time::sleep(remaining).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extend_keeps_the_later_deadline() {
+        let backoff = Backoff::default();
+        assert!(backoff.remaining().is_none());
+
+        backoff.extend(Duration::from_secs(10));
+        backoff.extend(Duration::from_secs(1));
+        assert!(backoff.remaining().unwrap() > Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,113 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Searches sending more requests than this need an explicit go-ahead.
+pub const DEFAULT_MAX_REQUESTS: u64 = 500_000;
+
+/// Rough time one HEAD request to the CDN takes, for estimates only.
+const TYPICAL_REQUEST_TIME: Duration = Duration::from_millis(250);
+
+/// Rough time `requests` take over `connections` connections, capped by
+/// `requests_per_second` when set. Rate limiting and slow networks make
+/// real searches slower.
+pub fn estimate_search_time(
+    requests: u64,
+    connections: usize,
+    requests_per_second: Option<u32>,
+) -> Duration {
+    let mut rate = connections.max(1) as f64 / TYPICAL_REQUEST_TIME.as_secs_f64();
+    if let Some(limit) = requests_per_second.filter(|&limit| limit > 0) {
+        rate = rate.min(limit as f64);
+    }
+    Duration::from_secs_f64(requests as f64 / rate)
+}
+
+/// Caps the URLs a run checks, shared by every task of a search like the
+/// connection limit. Each check takes one request before it is sent, a
+/// `.dmg` fallback another; retries after a 429 aren't charged. Unlimited by
+/// default.
+#[derive(Debug, Clone, Default)]
+pub struct RequestBudget {
+    limit: Option<u64>,
+    taken: Arc<AtomicU64>,
+    exhausted: Arc<AtomicBool>,
+}
+
+impl RequestBudget {
+    pub fn new(limit: u64) -> Self {
+        Self {
+            limit: Some(limit),
+            ..Self::default()
+        }
+    }
+
+    /// Takes one request, or returns false once they are all taken.
+    pub fn take(&self) -> bool {
+        let taken = self.taken.fetch_add(1, Ordering::Relaxed);
+        match self.limit {
+            Some(limit) if taken >= limit => {
+                self.exhausted.store(true, Ordering::Relaxed);
+                false
+            }
+            _ => true,
+        }
+    }
+
+    pub fn limit(&self) -> Option<u64> {
+        self.limit
+    }
+
+    /// Requests taken so far, never more than the limit.
+    pub fn used(&self) -> u64 {
+        let taken = self.taken.load(Ordering::Relaxed);
+        self.limit.map_or(taken, |limit| taken.min(limit))
+    }
+
+    /// Whether a request was refused, i.e. the run stopped short of its end.
+    pub fn exhausted(&self) -> bool {
+        self.exhausted.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refuses_requests_past_the_limit() {
+        let budget = RequestBudget::new(2);
+        let shared = budget.clone();
+        assert!(budget.take());
+        assert!(shared.take());
+        assert!(!budget.exhausted());
+
+        assert!(!budget.take());
+        assert!(shared.exhausted());
+        assert_eq!(shared.used(), 2);
+    }
+
+    #[test]
+    fn default_is_unlimited() {
+        let budget = RequestBudget::default();
+        assert!((0..1000).all(|_| budget.take()));
+        assert_eq!(budget.used(), 1000);
+        assert!(!budget.exhausted());
+    }
+
+    #[test]
+    fn estimates_from_connections_or_the_rate_limit() {
+        assert_eq!(
+            estimate_search_time(1000, 100, None),
+            Duration::from_millis(2500)
+        );
+        assert_eq!(
+            estimate_search_time(1000, 100, Some(10)),
+            Duration::from_secs(100)
+        );
+        assert_eq!(
+            estimate_search_time(1000, 0, Some(0)),
+            Duration::from_secs(250)
+        );
+    }
+}
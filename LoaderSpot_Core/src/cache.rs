@@ -0,0 +1,144 @@
+use crate::platform::Platform;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Bumped whenever the layout of [`ResultsCache`] changes. Files written with
+/// another version are rejected instead of being half-parsed.
+pub const CACHE_FORMAT_VERSION: u32 = 1;
+
+/// One installer found by a search.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedInstaller {
+    pub version: String,
+    pub platform: Platform,
+    pub build: i32,
+    pub url: String,
+    pub size: Option<u64>,
+    /// The server's `Last-Modified`, as ISO 8601 in UTC.
+    #[serde(default)]
+    pub last_modified: Option<String>,
+}
+
+/// Results of the last completed search, as saved on disk.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResultsCache {
+    pub format_version: u32,
+    /// Seconds since the Unix epoch.
+    pub saved_at: u64,
+    pub versions: Vec<String>,
+    pub found: Vec<CachedInstaller>,
+}
+
+impl ResultsCache {
+    pub fn new(versions: Vec<String>, found: Vec<CachedInstaller>) -> Self {
+        Self {
+            format_version: CACHE_FORMAT_VERSION,
+            saved_at: unix_now(),
+            versions,
+            found,
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read cache {}: {}", path.display(), e))?;
+        let value: serde_json::Value = serde_json::from_str(&text)
+            .map_err(|e| format!("Cache {} is not valid JSON: {}", path.display(), e))?;
+
+        let format_version = value.get("format_version").and_then(|v| v.as_u64());
+        if format_version != Some(CACHE_FORMAT_VERSION as u64) {
+            return Err(format!(
+                "Cache {} has unsupported format version {}; run a new search to replace it",
+                path.display(),
+                format_version.map_or("(missing)".to_string(), |v| v.to_string())
+            ));
+        }
+
+        serde_json::from_value(value)
+            .map_err(|e| format!("Cache {} is corrupt: {}", path.display(), e))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Cannot create {}: {}", dir.display(), e))?;
+        }
+        let text = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(path, text).map_err(|e| format!("Cannot write cache {}: {}", path.display(), e))
+    }
+
+    /// How long ago the cache was written.
+    pub fn age(&self) -> Duration {
+        Duration::from_secs(unix_now().saturating_sub(self.saved_at))
+    }
+}
+
+/// Coarse "how stale" text, e.g. "3 hours ago".
+pub fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    let (count, unit) = match secs {
+        0..=59 => return "just now".to_string(),
+        60..=3599 => (secs / 60, "minute"),
+        3600..=86399 => (secs / 3600, "hour"),
+        _ => (secs / 86400, "day"),
+    };
+    let plural = if count == 1 { "" } else { "s" };
+    format!("{} {}{} ago", count, unit, plural)
+}
+
+pub(crate) fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_file(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir()
+            .join(format!("loaderspot-cache-{}", std::process::id()))
+            .join(name)
+    }
+
+    #[test]
+    fn round_trips_through_disk() {
+        let path = scratch_file("round_trip.json");
+        let cache = ResultsCache::new(
+            vec!["1.2.60.564.gcc6305cb".to_string()],
+            vec![CachedInstaller {
+                version: "1.2.60.564.gcc6305cb".to_string(),
+                platform: Platform::WinX64,
+                build: 1234,
+                url: "https://example.com/spotify_installer-1.2.60.564.gcc6305cb-1234.exe"
+                    .to_string(),
+                size: Some(94371840),
+                last_modified: Some("2024-03-01T12:00:00Z".to_string()),
+            }],
+        );
+
+        cache.save(&path).unwrap();
+        assert_eq!(ResultsCache::load(&path).unwrap(), cache);
+    }
+
+    #[test]
+    fn rejects_other_format_versions() {
+        let path = scratch_file("future.json");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, r#"{"format_version": 99, "entries": {}}"#).unwrap();
+
+        let err = ResultsCache::load(&path).unwrap_err();
+        assert!(err.contains("unsupported format version 99"), "{}", err);
+    }
+
+    #[test]
+    fn formats_age() {
+        assert_eq!(format_age(Duration::from_secs(5)), "just now");
+        assert_eq!(format_age(Duration::from_secs(60)), "1 minute ago");
+        assert_eq!(format_age(Duration::from_secs(3 * 3600 + 5)), "3 hours ago");
+        assert_eq!(format_age(Duration::from_secs(2 * 86400)), "2 days ago");
+    }
+}
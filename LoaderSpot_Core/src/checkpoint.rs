@@ -0,0 +1,369 @@
+use crate::cache::{unix_now, CachedInstaller};
+use crate::platform::Platform;
+use crate::search::LadderOptions;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Bumped whenever the layout of [`Checkpoint`] changes.
+pub const CHECKPOINT_FORMAT_VERSION: u32 = 1;
+
+/// Everything that decides which URLs a search checks. A checkpoint only
+/// resumes the search it was written for.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchParams {
+    pub versions: Vec<String>,
+    pub platforms: Vec<Platform>,
+    /// Merged build ranges.
+    pub ranges: Vec<(i32, i32)>,
+    pub ladder: Option<LadderOptions>,
+    pub build_hints: bool,
+    pub max_gap: Option<u32>,
+    pub try_dmg: bool,
+    /// Checkpoints from before `step` existed checked every build.
+    #[serde(default = "every_build")]
+    pub step: u32,
+}
+
+fn every_build() -> u32 {
+    1
+}
+
+/// Next unchecked build of one platform of a version in progress. Every
+/// build of the searched ranges below it has been checked.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NextBuild {
+    pub version: String,
+    pub platform: Platform,
+    pub build: i32,
+}
+
+/// A check that got no answer to go by: no HTTP response at all, or still
+/// 429 after every retry. Unlike a 404 it says nothing about the build.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FailedCheck {
+    pub version: String,
+    pub platform: Platform,
+    pub build: i32,
+    /// The URL whose request failed, the `.dmg` one for a failed fallback.
+    pub url: String,
+}
+
+/// Progress of an unfinished search, saved now and then so it can be picked
+/// up after the program was closed or crashed. Ladder and hinted searches
+/// don't check builds in order, so for them only whole versions count.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub format_version: u32,
+    /// Seconds since the Unix epoch.
+    pub saved_at: u64,
+    pub params: SearchParams,
+    /// Versions whose search finished.
+    pub completed: Vec<String>,
+    pub next_builds: Vec<NextBuild>,
+    pub found: Vec<CachedInstaller>,
+    /// Checks to try again with `SearchEngine::rescan_failures`.
+    #[serde(default)]
+    pub failed: Vec<FailedCheck>,
+}
+
+impl Checkpoint {
+    pub fn new(params: SearchParams) -> Self {
+        Self {
+            format_version: CHECKPOINT_FORMAT_VERSION,
+            saved_at: unix_now(),
+            params,
+            completed: Vec::new(),
+            next_builds: Vec::new(),
+            found: Vec::new(),
+            failed: Vec::new(),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read checkpoint {}: {}", path.display(), e))?;
+        let value: serde_json::Value = serde_json::from_str(&text)
+            .map_err(|e| format!("Checkpoint {} is not valid JSON: {}", path.display(), e))?;
+
+        let format_version = value.get("format_version").and_then(|v| v.as_u64());
+        if format_version != Some(CHECKPOINT_FORMAT_VERSION as u64) {
+            return Err(format!(
+                "Checkpoint {} has unsupported format version {}",
+                path.display(),
+                format_version.map_or("(missing)".to_string(), |v| v.to_string())
+            ));
+        }
+
+        serde_json::from_value(value)
+            .map_err(|e| format!("Checkpoint {} is corrupt: {}", path.display(), e))
+    }
+
+    /// Writes a temporary file first, so a crash mid-write leaves the
+    /// previous checkpoint intact.
+    pub fn save(&mut self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Cannot create {}: {}", dir.display(), e))?;
+        }
+        self.saved_at = unix_now();
+        let text = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, text)
+            .and_then(|_| fs::rename(&tmp, path))
+            .map_err(|e| format!("Cannot write checkpoint {}: {}", path.display(), e))
+    }
+
+    /// How long ago the checkpoint was written.
+    pub fn age(&self) -> Duration {
+        Duration::from_secs(unix_now().saturating_sub(self.saved_at))
+    }
+
+    /// Fails, naming what differs, unless `params` describe the same search.
+    pub fn ensure_matches(&self, params: &SearchParams) -> Result<(), String> {
+        let ours = &self.params;
+        let differences: Vec<&str> = [
+            ("versions", ours.versions != params.versions),
+            ("platforms", ours.platforms != params.platforms),
+            ("ranges", ours.ranges != params.ranges),
+            ("smart search", ours.ladder != params.ladder),
+            ("build hints", ours.build_hints != params.build_hints),
+            ("max gap", ours.max_gap != params.max_gap),
+            ("dmg fallback", ours.try_dmg != params.try_dmg),
+            ("step", ours.step != params.step),
+        ]
+        .into_iter()
+        .filter_map(|(name, differs)| differs.then_some(name))
+        .collect();
+        if differences.is_empty() {
+            Ok(())
+        } else {
+            Err(format!(
+                "The checkpoint is for another search; its {} differ",
+                differences.join(", ")
+            ))
+        }
+    }
+
+    pub fn is_completed(&self, version: &str) -> bool {
+        self.completed.iter().any(|v| v == version)
+    }
+
+    /// The part of `ranges` still to check for `platform` of `version`.
+    pub fn remaining_ranges(
+        &self,
+        version: &str,
+        platform: Platform,
+        ranges: &[(i32, i32)],
+    ) -> Vec<(i32, i32)> {
+        if self.is_completed(version) {
+            return Vec::new();
+        }
+        let next = self
+            .next_builds
+            .iter()
+            .find(|next| next.version == version && next.platform == platform)
+            .map_or(i32::MIN, |next| next.build);
+        ranges
+            .iter()
+            .map(|&(start, end)| (start.max(next), end))
+            .filter(|(start, end)| start <= end)
+            .collect()
+    }
+
+    /// Takes over the progress `checked` has seen so far.
+    pub fn record(&mut self, checked: &CheckedBuilds) {
+        for next in checked.snapshot() {
+            if self.is_completed(&next.version) {
+                continue;
+            }
+            match self
+                .next_builds
+                .iter_mut()
+                .find(|known| known.version == next.version && known.platform == next.platform)
+            {
+                Some(known) => known.build = next.build,
+                None => self.next_builds.push(next),
+            }
+        }
+    }
+
+    /// Adds the checks `failed` has seen fail that aren't listed yet.
+    pub fn record_failures(&mut self, failed: &FailedChecks) {
+        for check in failed.snapshot() {
+            if !self.failed.iter().any(|known| known.url == check.url) {
+                self.failed.push(check);
+            }
+        }
+    }
+
+    pub fn complete_version(&mut self, version: &str) {
+        if !self.is_completed(version) {
+            self.completed.push(version.to_string());
+        }
+        self.next_builds.retain(|next| next.version != version);
+    }
+}
+
+/// Next unchecked build per version and platform of the range searches an
+/// engine ran, see `SearchEngine::checked_builds`.
+#[derive(Clone, Default)]
+pub struct CheckedBuilds(Arc<Mutex<HashMap<(String, Platform), i32>>>);
+
+impl CheckedBuilds {
+    pub(crate) fn set(&self, version: &str, platform: Platform, next: i32) {
+        self.0
+            .lock()
+            .unwrap()
+            .insert((version.to_string(), platform), next);
+    }
+
+    pub fn get(&self, version: &str, platform: Platform) -> Option<i32> {
+        self.0
+            .lock()
+            .unwrap()
+            .get(&(version.to_string(), platform))
+            .copied()
+    }
+
+    pub fn snapshot(&self) -> Vec<NextBuild> {
+        self.0
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|((version, platform), &build)| NextBuild {
+                version: version.clone(),
+                platform: *platform,
+                build,
+            })
+            .collect()
+    }
+}
+
+/// Checks that failed during the searches of an engine, see
+/// `SearchEngine::failed_checks`.
+#[derive(Clone, Default)]
+pub struct FailedChecks(Arc<Mutex<Vec<FailedCheck>>>);
+
+impl FailedChecks {
+    pub(crate) fn add(&self, check: FailedCheck) {
+        self.0.lock().unwrap().push(check);
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn snapshot(&self) -> Vec<FailedCheck> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+impl From<Vec<FailedCheck>> for FailedChecks {
+    fn from(checks: Vec<FailedCheck>) -> Self {
+        Self(Arc::new(Mutex::new(checks)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params() -> SearchParams {
+        SearchParams {
+            versions: vec![
+                "1.2.3.4.gaaaaaaaa".to_string(),
+                "1.2.3.5.gbbbbbbbb".to_string(),
+            ],
+            platforms: vec![Platform::WinX64, Platform::MacOsArm64],
+            ranges: vec![(0, 100), (3000, 3100)],
+            ladder: None,
+            build_hints: false,
+            max_gap: None,
+            try_dmg: false,
+            step: 1,
+        }
+    }
+
+    #[test]
+    fn resumes_each_platform_at_its_next_build() {
+        let mut checkpoint = Checkpoint::new(params());
+        let checked = CheckedBuilds::default();
+        checked.set("1.2.3.4.gaaaaaaaa", Platform::WinX64, 3050);
+        checked.set("1.2.3.5.gbbbbbbbb", Platform::WinX64, 50);
+        checkpoint.record(&checked);
+        checkpoint.complete_version("1.2.3.4.gaaaaaaaa");
+
+        let ranges = &checkpoint.params.ranges.clone();
+        assert!(checkpoint
+            .remaining_ranges("1.2.3.4.gaaaaaaaa", Platform::WinX64, ranges)
+            .is_empty());
+        assert_eq!(
+            checkpoint.remaining_ranges("1.2.3.5.gbbbbbbbb", Platform::WinX64, ranges),
+            [(50, 100), (3000, 3100)]
+        );
+        assert_eq!(
+            checkpoint.remaining_ranges("1.2.3.5.gbbbbbbbb", Platform::MacOsArm64, ranges),
+            [(0, 100), (3000, 3100)]
+        );
+
+        checked.set("1.2.3.5.gbbbbbbbb", Platform::WinX64, 3000);
+        checkpoint.record(&checked);
+        assert_eq!(
+            checkpoint.remaining_ranges("1.2.3.5.gbbbbbbbb", Platform::WinX64, ranges),
+            [(3000, 3100)]
+        );
+        assert_eq!(checkpoint.next_builds.len(), 1);
+    }
+
+    #[test]
+    fn records_each_failed_check_once() {
+        let mut checkpoint = Checkpoint::new(params());
+        let failed = FailedChecks::default();
+        let check = |build: i32| FailedCheck {
+            version: "1.2.3.4.gaaaaaaaa".to_string(),
+            platform: Platform::WinX64,
+            build,
+            url: format!("https://example.com/{}.exe", build),
+        };
+        failed.add(check(7));
+        checkpoint.record_failures(&failed);
+        failed.add(check(9));
+        checkpoint.record_failures(&failed);
+
+        assert_eq!(checkpoint.failed, [check(7), check(9)]);
+    }
+
+    #[test]
+    fn rejects_another_search() {
+        let checkpoint = Checkpoint::new(params());
+        assert!(checkpoint.ensure_matches(&params()).is_ok());
+
+        let other = SearchParams {
+            ranges: vec![(0, 5000)],
+            try_dmg: true,
+            ..params()
+        };
+        let err = checkpoint.ensure_matches(&other).unwrap_err();
+        assert!(err.contains("ranges, dmg fallback"), "{}", err);
+    }
+
+    #[test]
+    fn round_trips_through_a_file() {
+        let path =
+            std::env::temp_dir().join(format!("loaderspot-checkpoint-{}.json", std::process::id()));
+        let mut checkpoint = Checkpoint::new(params());
+        checkpoint.complete_version("1.2.3.4.gaaaaaaaa");
+        checkpoint.save(&path).unwrap();
+
+        assert_eq!(Checkpoint::load(&path).unwrap(), checkpoint);
+        let _ = fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,336 @@
+use log::debug;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::redirect::Policy;
+use reqwest::{Certificate, Client, Proxy};
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, SocketAddr};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Which addresses a host name may resolve to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IpFamily {
+    /// Whatever the OS resolver returns.
+    #[default]
+    Any,
+    V4,
+    V6,
+}
+
+impl IpFamily {
+    fn allows(self, addr: &SocketAddr) -> bool {
+        match self {
+            IpFamily::Any => true,
+            IpFamily::V4 => addr.is_ipv4(),
+            IpFamily::V6 => addr.is_ipv6(),
+        }
+    }
+}
+
+impl std::fmt::Display for IpFamily {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            IpFamily::Any => "Any",
+            IpFamily::V4 => "IPv4",
+            IpFamily::V6 => "IPv6",
+        })
+    }
+}
+
+/// System resolver that drops the addresses of the other IP family.
+struct FamilyResolver(IpFamily);
+
+impl Resolve for FamilyResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let family = self.0;
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|addr| family.allows(addr))
+                .collect();
+            if addrs.is_empty() {
+                return Err(format!("{} has no {} address", name.as_str(), family).into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Settings for the HTTP client shared by every request of a search.
+pub struct ClientOptions {
+    pub timeout: Duration,
+    /// Explicit proxy for all schemes. When unset, reqwest falls back to the
+    /// `HTTP_PROXY`/`HTTPS_PROXY`/`ALL_PROXY` environment variables.
+    /// `socks5://` URLs need the `socks` feature.
+    pub proxy: Option<String>,
+    /// Source IP to send from, for hosts with several interfaces. Host names
+    /// then only resolve to addresses of its family.
+    pub local_address: Option<String>,
+    pub ip_family: IpFamily,
+    /// Replaces reqwest's default `User-Agent`.
+    pub user_agent: Option<String>,
+    /// Extra headers sent with every request, each as `Name: Value`.
+    pub headers: Vec<String>,
+    /// Follow up to `MAX_REDIRECTS` redirects. When off, a redirect counts as
+    /// a hit on the URL that answered with it.
+    pub follow_redirects: bool,
+    /// Extra root certificate to trust, PEM or DER, e.g. a private mirror's
+    /// self-signed one.
+    pub ca_cert: Option<PathBuf>,
+    /// Skips certificate validation altogether. Only for testing against a
+    /// mirror you control.
+    pub accept_invalid_certs: bool,
+    /// Speak HTTP/2 from the first byte instead of negotiating it. HTTPS
+    /// hosts like the CDN already negotiate HTTP/2 when they offer it; this
+    /// is for plain-HTTP mirrors that speak it (h2c) and fails on any other.
+    pub http2_prior_knowledge: bool,
+    /// Idle connections kept open per host. `None` keeps every one, so the
+    /// connections a search opens get reused however many permits it has.
+    pub pool_max_idle_per_host: Option<usize>,
+    /// How long an idle connection stays open for reuse; `None` forever.
+    pub pool_idle_timeout: Option<Duration>,
+    /// Accept gzip, deflate and brotli bodies and decode them on the fly.
+    /// Only bodies that get read gain anything, i.e. `versions.json` and
+    /// downloads; HEAD probes have none.
+    pub compression: bool,
+}
+
+/// How long reqwest keeps idle connections by default.
+pub const DEFAULT_POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Redirects followed before a request fails.
+pub const MAX_REDIRECTS: usize = 10;
+
+impl Default for ClientOptions {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(30),
+            proxy: None,
+            local_address: None,
+            ip_family: IpFamily::Any,
+            user_agent: None,
+            headers: Vec::new(),
+            follow_redirects: true,
+            ca_cert: None,
+            accept_invalid_certs: false,
+            http2_prior_knowledge: false,
+            pool_max_idle_per_host: None,
+            pool_idle_timeout: Some(DEFAULT_POOL_IDLE_TIMEOUT),
+            compression: true,
+        }
+    }
+}
+
+/// Reads a root certificate, PEM if it looks like one and DER otherwise.
+fn load_certificate(path: &PathBuf) -> Result<Certificate, String> {
+    let bytes = std::fs::read(path)
+        .map_err(|e| format!("Cannot read CA certificate {}: {}", path.display(), e))?;
+    let certificate = if bytes.starts_with(b"-----BEGIN") {
+        Certificate::from_pem(&bytes)
+    } else {
+        Certificate::from_der(&bytes)
+    };
+    certificate.map_err(|e| format!("Invalid CA certificate {}: {}", path.display(), e))
+}
+
+/// Logs how much of `url`'s body was read. reqwest drops `Content-Length`
+/// when it decodes a compressed body, so `transferred` is `None` for those
+/// (and for chunked ones) and only the decoded size is known.
+pub(crate) fn log_body_sizes(url: &str, transferred: Option<u64>, decoded: u64) {
+    match transferred {
+        Some(transferred) => debug!(
+            "{}: {} bytes transferred, {} decoded",
+            url, transferred, decoded
+        ),
+        None => debug!(
+            "{}: {} bytes decoded, compressed or chunked in transit",
+            url, decoded
+        ),
+    }
+}
+
+/// Parses one `Name: Value` header line.
+pub fn parse_header(line: &str) -> Result<(HeaderName, HeaderValue), String> {
+    let (name, value) = line
+        .split_once(':')
+        .ok_or_else(|| format!("Invalid header '{}': expected 'Name: Value'", line))?;
+    let name = HeaderName::from_bytes(name.trim().as_bytes())
+        .map_err(|_| format!("Invalid header '{}': bad name '{}'", line, name.trim()))?;
+    let value = HeaderValue::from_str(value.trim())
+        .map_err(|_| format!("Invalid header '{}': bad value", line))?;
+    Ok((name, value))
+}
+
+impl ClientOptions {
+    pub fn build(&self) -> Result<Client, String> {
+        let redirects = if self.follow_redirects {
+            Policy::limited(MAX_REDIRECTS)
+        } else {
+            Policy::none()
+        };
+        let mut builder = Client::builder()
+            .timeout(self.timeout)
+            .redirect(redirects)
+            .pool_max_idle_per_host(self.pool_max_idle_per_host.unwrap_or(usize::MAX))
+            .pool_idle_timeout(self.pool_idle_timeout)
+            .gzip(self.compression)
+            .deflate(self.compression)
+            .brotli(self.compression);
+        if self.http2_prior_knowledge {
+            builder = builder.http2_prior_knowledge();
+        }
+
+        if let Some(agent) = self.user_agent.as_deref().filter(|a| !a.trim().is_empty()) {
+            let agent = HeaderValue::from_str(agent.trim())
+                .map_err(|_| format!("Invalid User-Agent '{}'", agent))?;
+            builder = builder.user_agent(agent);
+        }
+
+        let mut headers = HeaderMap::new();
+        for line in self.headers.iter().filter(|l| !l.trim().is_empty()) {
+            let (name, value) = parse_header(line)?;
+            headers.append(name, value);
+        }
+        if !headers.is_empty() {
+            builder = builder.default_headers(headers);
+        }
+
+        if let Some(path) = self.ca_cert.as_ref().filter(|p| !p.as_os_str().is_empty()) {
+            builder = builder.add_root_certificate(load_certificate(path)?);
+        }
+        if self.accept_invalid_certs {
+            builder = builder.danger_accept_invalid_certs(true);
+        }
+
+        if let Some(proxy) = self.proxy.as_deref().filter(|p| !p.trim().is_empty()) {
+            let proxy = Proxy::all(proxy.trim())
+                .map_err(|e| format!("Invalid proxy URL '{}': {}", proxy, e))?;
+            builder = builder.proxy(proxy);
+        }
+
+        let mut family = self.ip_family;
+        if let Some(address) = self
+            .local_address
+            .as_deref()
+            .filter(|a| !a.trim().is_empty())
+        {
+            let ip: IpAddr = address
+                .trim()
+                .parse()
+                .map_err(|e| format!("Invalid local address '{}': {}", address, e))?;
+            let ip_family = if ip.is_ipv4() {
+                IpFamily::V4
+            } else {
+                IpFamily::V6
+            };
+            if family != IpFamily::Any && family != ip_family {
+                return Err(format!("Local address {} is not an {} address", ip, family));
+            }
+            family = ip_family;
+            builder = builder.local_address(ip);
+        }
+        if family != IpFamily::Any {
+            builder = builder.dns_resolver(Arc::new(FamilyResolver(family)));
+        }
+
+        builder
+            .build()
+            .map_err(|e| format!("Failed to build HTTP client: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_malformed_proxy() {
+        let options = ClientOptions {
+            proxy: Some("not a url".to_string()),
+            ..Default::default()
+        };
+        assert!(options.build().is_err());
+    }
+
+    #[test]
+    fn parses_header_lines() {
+        let (name, value) = parse_header("X-Client-Id: abc:123").unwrap();
+        assert_eq!(name, "x-client-id");
+        assert_eq!(value, "abc:123");
+
+        assert!(parse_header("no colon").is_err());
+        assert!(parse_header("Bad Name: x").is_err());
+        assert!(parse_header("X-Line: a\nb").is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_headers() {
+        let options = ClientOptions {
+            headers: vec!["Accept: */*".to_string(), "nonsense".to_string()],
+            ..Default::default()
+        };
+        let err = options.build().unwrap_err();
+        assert!(err.contains("nonsense"), "{}", err);
+    }
+
+    #[test]
+    fn rejects_bad_local_address() {
+        let options = ClientOptions {
+            local_address: Some("192.168.1".to_string()),
+            ..Default::default()
+        };
+        let err = options.build().unwrap_err();
+        assert!(err.contains("Invalid local address"), "{}", err);
+
+        let options = ClientOptions {
+            local_address: Some("127.0.0.1".to_string()),
+            ip_family: IpFamily::V6,
+            ..Default::default()
+        };
+        assert!(options.build().is_err());
+    }
+
+    #[tokio::test]
+    async fn resolves_only_the_requested_family() {
+        let addrs: Vec<SocketAddr> = FamilyResolver(IpFamily::V4)
+            .resolve("localhost".parse().unwrap())
+            .await
+            .unwrap()
+            .collect();
+        assert!(!addrs.is_empty());
+        assert!(addrs.iter().all(|addr| addr.is_ipv4()));
+    }
+
+    #[test]
+    fn rejects_missing_or_malformed_ca_certificates() {
+        let options = ClientOptions {
+            ca_cert: Some(PathBuf::from("/nonexistent/mirror-ca.pem")),
+            ..Default::default()
+        };
+        let err = options.build().unwrap_err();
+        assert!(err.contains("Cannot read CA certificate"), "{}", err);
+
+        let path = std::env::temp_dir().join(format!("loaderspot-ca-{}.pem", std::process::id()));
+        std::fs::write(&path, "-----BEGIN CERTIFICATE-----\nnot base64\n").unwrap();
+        let options = ClientOptions {
+            ca_cert: Some(path.clone()),
+            ..Default::default()
+        };
+        let result = options.build();
+        // Gone before the assertion can fail.
+        let _ = std::fs::remove_file(&path);
+        let err = result.unwrap_err();
+        assert!(err.contains("Invalid CA certificate"), "{}", err);
+    }
+
+    #[test]
+    fn accepts_http_proxy() {
+        let options = ClientOptions {
+            proxy: Some("http://127.0.0.1:3128".to_string()),
+            ..Default::default()
+        };
+        assert!(options.build().is_ok());
+    }
+}
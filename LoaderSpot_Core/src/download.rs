@@ -0,0 +1,201 @@
+use crate::client::log_body_sizes;
+use crate::search::{content_range_total, header_content_length};
+use log::debug;
+use reqwest::header::{ACCEPT_ENCODING, RANGE};
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::fs::{self, OpenOptions};
+use tokio::io::AsyncWriteExt;
+
+/// File name an installer URL is saved under.
+pub fn file_name_from_url(url: &str) -> Option<&str> {
+    url.rsplit('/')
+        .next()
+        .map(|name| name.split(['?', '#']).next().unwrap_or(name))
+        .filter(|name| !name.is_empty())
+}
+
+/// Command-line tool that [`download_command`] writes a command for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Downloader {
+    #[default]
+    Curl,
+    Wget,
+}
+
+impl std::fmt::Display for Downloader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Downloader::Curl => "curl",
+            Downloader::Wget => "wget",
+        })
+    }
+}
+
+/// Wraps `text` in single quotes for a POSIX shell.
+fn shell_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "'\\''"))
+}
+
+/// A shell command that downloads `url` with `tool`, into `dir` if given and
+/// the current directory otherwise, keeping the file name from the URL.
+pub fn download_command(tool: Downloader, url: &str, dir: Option<&Path>) -> String {
+    match (tool, dir) {
+        (Downloader::Curl, None) => format!("curl -fLO {}", shell_quote(url)),
+        (Downloader::Curl, Some(dir)) => {
+            let name = file_name_from_url(url).unwrap_or("installer");
+            let path = dir.join(name);
+            format!(
+                "curl -fL -o {} {}",
+                shell_quote(&path.to_string_lossy()),
+                shell_quote(url)
+            )
+        }
+        (Downloader::Wget, None) => format!("wget {}", shell_quote(url)),
+        (Downloader::Wget, Some(dir)) => format!(
+            "wget -P {} {}",
+            shell_quote(&dir.to_string_lossy()),
+            shell_quote(url)
+        ),
+    }
+}
+
+/// Streams `url` into `dir`, resuming from `<name>.part` if a previous attempt
+/// left one behind. The `.part` file is only renamed to its final name once
+/// the byte count matches the server's size. `on_progress` receives
+/// `(downloaded, total)` after every chunk.
+pub async fn download_installer(
+    client: &Client,
+    url: &str,
+    dir: &Path,
+    mut on_progress: impl FnMut(u64, Option<u64>),
+) -> Result<PathBuf, String> {
+    let name = file_name_from_url(url).ok_or_else(|| format!("No file name in URL: {}", url))?;
+    fs::create_dir_all(dir)
+        .await
+        .map_err(|e| format!("Cannot create {}: {}", dir.display(), e))?;
+
+    let final_path = dir.join(name);
+    let part_path = dir.join(format!("{}.part", name));
+
+    let mut resumed = true;
+    let mut response = loop {
+        let existing = match fs::metadata(&part_path).await {
+            Ok(meta) if resumed => meta.len(),
+            _ => 0,
+        };
+
+        let mut request = client.get(url);
+        if existing > 0 {
+            // Offsets into the .part file only match the unencoded body.
+            request = request
+                .header(RANGE, format!("bytes={}-", existing))
+                .header(ACCEPT_ENCODING, "identity");
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("Download failed for {}: {}", url, e))?;
+
+        debug!(
+            "GET {} (from byte {}) -> {}",
+            url,
+            existing,
+            response.status()
+        );
+
+        // The partial file is stale or already complete; start over once.
+        if response.status() == StatusCode::RANGE_NOT_SATISFIABLE && resumed {
+            resumed = false;
+            continue;
+        }
+        break response;
+    };
+
+    let status = response.status();
+    let (mut downloaded, total, append) = match status {
+        StatusCode::PARTIAL_CONTENT => {
+            let existing = fs::metadata(&part_path).await.map(|m| m.len()).unwrap_or(0);
+            (existing, content_range_total(response.headers()), true)
+        }
+        StatusCode::OK => (0, header_content_length(response.headers()), false),
+        _ => return Err(format!("Download failed for {}: HTTP {}", url, status)),
+    };
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(append)
+        .truncate(!append)
+        .open(&part_path)
+        .await
+        .map_err(|e| format!("Cannot open {}: {}", part_path.display(), e))?;
+
+    let transferred = response.content_length();
+    let started_at = downloaded;
+    on_progress(downloaded, total);
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Download interrupted for {}: {}", url, e))?
+    {
+        file.write_all(&chunk)
+            .await
+            .map_err(|e| format!("Cannot write {}: {}", part_path.display(), e))?;
+        downloaded += chunk.len() as u64;
+        on_progress(downloaded, total);
+    }
+    file.flush()
+        .await
+        .map_err(|e| format!("Cannot write {}: {}", part_path.display(), e))?;
+    log_body_sizes(url, transferred, downloaded - started_at);
+
+    if let Some(total) = total {
+        if downloaded != total {
+            return Err(format!(
+                "Size mismatch for {}: got {} of {} bytes, kept {} for resuming",
+                name,
+                downloaded,
+                total,
+                part_path.display()
+            ));
+        }
+    }
+
+    fs::rename(&part_path, &final_path)
+        .await
+        .map_err(|e| format!("Cannot rename {}: {}", part_path.display(), e))?;
+    Ok(final_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_name_is_last_path_segment() {
+        assert_eq!(
+            file_name_from_url("https://upgrade.scdn.co/upgrade/client/win32-x86_64/spotify_installer-1.2.3.4.gabc-12.exe"),
+            Some("spotify_installer-1.2.3.4.gabc-12.exe")
+        );
+        assert_eq!(file_name_from_url("https://example.com/dir/"), None);
+    }
+
+    #[test]
+    fn download_commands_quote_urls_and_directories() {
+        let url = "https://example.com/spotify-1.exe";
+        assert_eq!(
+            download_command(Downloader::Curl, url, None),
+            "curl -fLO 'https://example.com/spotify-1.exe'"
+        );
+        assert_eq!(
+            download_command(Downloader::Curl, url, Some(Path::new("my dir"))),
+            "curl -fL -o 'my dir/spotify-1.exe' 'https://example.com/spotify-1.exe'"
+        );
+        assert_eq!(
+            download_command(Downloader::Wget, url, Some(Path::new("it's"))),
+            "wget -P 'it'\\''s' 'https://example.com/spotify-1.exe'"
+        );
+    }
+}
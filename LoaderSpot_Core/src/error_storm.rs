@@ -0,0 +1,106 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Notices when most recent checks got no HTTP response at all, e.g. because
+/// the network went down, so a search can pause instead of counting the rest
+/// of its range as misses. Clean 404s never count. The default never trips.
+#[derive(Clone, Default)]
+pub struct ErrorStorm(Option<Arc<StormState>>);
+
+struct StormState {
+    window: usize,
+    threshold_percent: u8,
+    /// Whether each of the last `window` checks was a network error.
+    recent: Mutex<VecDeque<bool>>,
+    tripped: AtomicBool,
+}
+
+impl ErrorStorm {
+    pub const DEFAULT_WINDOW: usize = 50;
+    pub const DEFAULT_THRESHOLD_PERCENT: u8 = 80;
+
+    /// Trips once more than `threshold_percent` of the last `window` checks
+    /// were network errors. A `window` of 0 never trips.
+    pub fn new(window: usize, threshold_percent: u8) -> Self {
+        if window == 0 {
+            return Self::default();
+        }
+        Self(Some(Arc::new(StormState {
+            window,
+            threshold_percent: threshold_percent.min(100),
+            recent: Mutex::new(VecDeque::with_capacity(window)),
+            tripped: AtomicBool::new(false),
+        })))
+    }
+
+    /// Records one check. Returns true for the check that trips it; while
+    /// tripped, checks are ignored.
+    pub(crate) fn record(&self, network_error: bool) -> bool {
+        let Some(state) = &self.0 else {
+            return false;
+        };
+        if state.tripped.load(Ordering::Relaxed) {
+            return false;
+        }
+        let mut recent = state.recent.lock().unwrap();
+        if recent.len() == state.window {
+            recent.pop_front();
+        }
+        recent.push_back(network_error);
+        if recent.len() < state.window {
+            return false;
+        }
+        let errors = recent.iter().filter(|&&error| error).count();
+        let storm = errors * 100 > state.window * state.threshold_percent as usize;
+        storm && !state.tripped.swap(true, Ordering::Relaxed)
+    }
+
+    /// Whether it tripped and paused the search since the last `reset`.
+    pub fn tripped(&self) -> bool {
+        self.0
+            .as_ref()
+            .is_some_and(|state| state.tripped.load(Ordering::Relaxed))
+    }
+
+    /// Forgets the recorded checks; call it when resuming the search.
+    pub fn reset(&self) {
+        if let Some(state) = &self.0 {
+            state.recent.lock().unwrap().clear();
+            state.tripped.store(false, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trips_once_the_window_is_mostly_errors() {
+        let storm = ErrorStorm::new(10, 50);
+        for _ in 0..5 {
+            assert!(!storm.record(false));
+        }
+        // 5 of 10 is not more than half.
+        for _ in 0..5 {
+            assert!(!storm.record(true));
+        }
+        assert!(storm.record(true));
+        assert!(storm.tripped());
+        assert!(!storm.record(true));
+
+        storm.reset();
+        assert!(!storm.tripped());
+        assert!(!storm.record(true));
+    }
+
+    #[test]
+    fn default_never_trips() {
+        let storm = ErrorStorm::default();
+        for _ in 0..1000 {
+            assert!(!storm.record(true));
+        }
+        assert!(!storm.tripped());
+    }
+}
@@ -0,0 +1,256 @@
+use crate::client::log_body_sizes;
+use crate::platform::Platform;
+use regex::Regex;
+use reqwest::Client;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Builds a hint window reaches past an extrapolated guess.
+pub const HINT_MARGIN: i32 = 300;
+
+pub const VERSIONS_JSON_URL: &str =
+    "https://raw.githubusercontent.com/LoaderSpot/LoaderSpot/refs/heads/main/versions.json";
+
+/// How long [`fetch_versions_json`] waits, whatever the client's timeout, so
+/// a hung GitHub can't hold up the search that needs it.
+const VERSIONS_JSON_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The repository's `versions.json`, unparsed. Fetch it once per run and
+/// parse it into [`KnownBuilds`] and [`KnownVersions`] as needed.
+pub async fn fetch_versions_json(client: &Client) -> Result<String, String> {
+    let response = client
+        .get(VERSIONS_JSON_URL)
+        .timeout(VERSIONS_JSON_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| format!("Cannot fetch versions.json: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("versions.json returned {}", response.status()));
+    }
+    let transferred = response.content_length();
+    let body = response
+        .text()
+        .await
+        .map_err(|e| format!("Cannot fetch versions.json: {}", e))?;
+    log_body_sizes(VERSIONS_JSON_URL, transferred, body.len() as u64);
+    Ok(body)
+}
+
+#[derive(Deserialize)]
+struct VersionsJsonEntry {
+    fullversion: Option<String>,
+    /// `{"win": {"x64": url, ...}, "mac": {"arm64": url, ...}}`; empty for
+    /// platforms that were never published.
+    #[serde(default)]
+    links: HashMap<String, HashMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct KnownBuild {
+    version: [u32; 4],
+    platform: Platform,
+    build: i32,
+}
+
+/// Build numbers of already known versions, from the repository's
+/// `versions.json`. Within one `major.minor.patch` series the build number
+/// grows with the fourth version component, which is enough to guess where
+/// an unknown version's build lies.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KnownBuilds(Vec<KnownBuild>);
+
+/// `1.2.85.519.g549a528b` -> `[1, 2, 85, 519]`.
+fn version_key(version: &str) -> Option<[u32; 4]> {
+    let mut parts = version.split('.').map(|part| part.parse::<u32>().ok());
+    Some([
+        parts.next()??,
+        parts.next()??,
+        parts.next()??,
+        parts.next()??,
+    ])
+}
+
+fn build_from_url(url: &str) -> Option<i32> {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| Regex::new(r"-(\d+)\.[A-Za-z0-9]+$").unwrap());
+    re.captures(url)?.get(1)?.as_str().parse().ok()
+}
+
+fn platform_for_link(os: &str, arch: &str) -> Option<Platform> {
+    match (os, arch) {
+        ("win", "x86") => Some(Platform::WinX86),
+        ("win", "x64") => Some(Platform::WinX64),
+        ("win", "arm64") => Some(Platform::WinArm64),
+        ("mac", "intel") => Some(Platform::MacOsIntel),
+        ("mac", "arm64") => Some(Platform::MacOsArm64),
+        _ => None,
+    }
+}
+
+impl KnownBuilds {
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let entries: HashMap<String, VersionsJsonEntry> =
+            serde_json::from_str(json).map_err(|e| format!("Invalid versions.json: {}", e))?;
+
+        let mut builds = Vec::new();
+        for (key, entry) in entries {
+            let Some(version) = version_key(entry.fullversion.as_deref().unwrap_or(&key)) else {
+                continue;
+            };
+            for (os, arches) in &entry.links {
+                for (arch, url) in arches {
+                    if let (Some(platform), Some(build)) =
+                        (platform_for_link(os, arch), build_from_url(url))
+                    {
+                        builds.push(KnownBuild {
+                            version,
+                            platform,
+                            build,
+                        });
+                    }
+                }
+            }
+        }
+        builds.sort_by_key(|known| (known.platform, known.version));
+        Ok(Self(builds))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Builds of `platform` most likely to hold `version`, or `None` when no
+    /// version of the same series is known. Between two known versions this
+    /// is the span of their builds; past the newest or oldest one the build
+    /// is extrapolated from the two nearest versions, plus `HINT_MARGIN`.
+    pub fn window(&self, version: &str, platform: Platform) -> Option<(i32, i32)> {
+        let key = version_key(version)?;
+        let series: Vec<(u32, i32)> = self
+            .0
+            .iter()
+            .filter(|known| known.platform == platform && known.version[..3] == key[..3])
+            .map(|known| (known.version[3], known.build))
+            .collect();
+
+        if let Some(&(_, build)) = series.iter().find(|(number, _)| *number == key[3]) {
+            return Some(((build - HINT_MARGIN).max(0), build + HINT_MARGIN));
+        }
+
+        let below: Vec<_> = series.iter().filter(|(n, _)| *n < key[3]).collect();
+        let above: Vec<_> = series.iter().filter(|(n, _)| *n > key[3]).collect();
+        // Builds per step of the fourth version component.
+        let slope = |a: &(u32, i32), b: &(u32, i32)| {
+            (b.1 - a.1).max(0) as f64 / (b.0 as f64 - a.0 as f64).abs().max(1.0)
+        };
+
+        match (below.last(), above.first()) {
+            (Some(low), Some(high)) => Some((low.1, high.1.max(low.1))),
+            (Some(low), None) => {
+                let rate = match below.len() {
+                    1 => 0.0,
+                    n => slope(below[n - 2], low),
+                };
+                let guess = low.1 + (rate * (key[3] - low.0) as f64).round() as i32;
+                Some((low.1, guess + HINT_MARGIN))
+            }
+            (None, Some(high)) => {
+                let rate = match above.get(1) {
+                    Some(next) => slope(high, next),
+                    None => 0.0,
+                };
+                let guess = high.1 - (rate * (high.0 - key[3]) as f64).round() as i32;
+                Some(((guess - HINT_MARGIN).max(0), high.1))
+            }
+            (None, None) => None,
+        }
+    }
+}
+
+/// Versions already listed in `versions.json`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KnownVersions(HashSet<String>);
+
+impl KnownVersions {
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let entries: HashMap<String, VersionsJsonEntry> =
+            serde_json::from_str(json).map_err(|e| format!("Invalid versions.json: {}", e))?;
+        let mut versions = HashSet::new();
+        for (key, entry) in entries {
+            versions.extend(entry.fullversion);
+            versions.insert(key);
+        }
+        Ok(Self(versions))
+    }
+
+    /// Matches the full version, or the key for a version without a hash.
+    pub fn contains(&self, version: &str) -> bool {
+        self.0.contains(version)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VERSIONS_JSON: &str = r#"{
+        "1.2.85.507": {
+            "fullversion": "1.2.85.507.g795fff93",
+            "links": {"win": {"x86": "", "x64": "https://upgrade.scdn.co/upgrade/client/win32-x86_64/spotify_installer-1.2.85.507.g795fff93-3545.exe"}}
+        },
+        "1.2.85.513": {
+            "fullversion": "1.2.85.513.g45f09625",
+            "links": {"win": {"x64": "https://upgrade.scdn.co/upgrade/client/win32-x86_64/spotify_installer-1.2.85.513.g45f09625-3679.exe"}}
+        },
+        "1.2.85.519": {
+            "fullversion": "1.2.85.519.g549a528b",
+            "links": {"mac": {"arm64": "https://upgrade.scdn.co/upgrade/client/osx-arm64/spotify-autoupdate-1.2.85.519.g549a528b-4022.tbz"}}
+        }
+    }"#;
+
+    #[test]
+    fn parses_builds_from_links() {
+        let known = KnownBuilds::parse(VERSIONS_JSON).unwrap();
+        assert_eq!(known.0.len(), 3);
+        assert!(KnownBuilds::parse("[]").is_err());
+    }
+
+    #[test]
+    fn knows_versions_with_and_without_hash() {
+        let known = KnownVersions::parse(VERSIONS_JSON).unwrap();
+        assert!(known.contains("1.2.85.513.g45f09625"));
+        assert!(known.contains("1.2.85.513"));
+        assert!(!known.contains("1.2.85.513.gaaaaaaaa"));
+        assert!(!known.contains("1.2.85.525.g12345678"));
+    }
+
+    #[test]
+    fn spans_the_neighbouring_builds() {
+        let known = KnownBuilds::parse(VERSIONS_JSON).unwrap();
+        assert_eq!(
+            known.window("1.2.85.510.gaaaaaaaa", Platform::WinX64),
+            Some((3545, 3679))
+        );
+    }
+
+    #[test]
+    fn extrapolates_past_the_newest_known_version() {
+        let known = KnownBuilds::parse(VERSIONS_JSON).unwrap();
+        // 134 builds over 6 versions, so about 22 per version after 513.
+        assert_eq!(
+            known.window("1.2.85.519.gaaaaaaaa", Platform::WinX64),
+            Some((3679, 3679 + 134 + HINT_MARGIN))
+        );
+    }
+
+    #[test]
+    fn has_no_hint_for_unknown_series_or_platforms() {
+        let known = KnownBuilds::parse(VERSIONS_JSON).unwrap();
+        assert_eq!(known.window("1.2.86.100.gaaaaaaaa", Platform::WinX64), None);
+        assert_eq!(
+            known.window("1.2.85.510.gaaaaaaaa", Platform::WinArm64),
+            None
+        );
+    }
+}
@@ -0,0 +1,89 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Random wait of up to `max` before each request, so the connections of a
+/// search don't all fire at the same instant. A waiting request holds its
+/// connection, so large values cost throughput. The default never waits.
+#[derive(Clone, Default)]
+pub struct Jitter(Option<Arc<JitterState>>);
+
+struct JitterState {
+    max: Duration,
+    /// SplitMix64 state; every draw advances it by the same constant.
+    state: AtomicU64,
+}
+
+impl Jitter {
+    /// Up to `max`, seeded from the clock; zero never waits.
+    pub fn new(max: Duration) -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_nanos() as u64);
+        Self::seeded(max, seed)
+    }
+
+    /// Like [`Jitter::new`], but the same `seed` gives the same waits.
+    pub fn seeded(max: Duration, seed: u64) -> Self {
+        if max.is_zero() {
+            return Self::default();
+        }
+        Self(Some(Arc::new(JitterState {
+            max,
+            state: AtomicU64::new(seed),
+        })))
+    }
+
+    /// The next wait, between zero and `max`.
+    pub(crate) fn next_delay(&self) -> Duration {
+        let Some(state) = &self.0 else {
+            return Duration::ZERO;
+        };
+        let mut z = state
+            .state
+            .fetch_add(0x9e37_79b9_7f4a_7c15, Ordering::Relaxed)
+            .wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        let max_micros = state.max.as_micros().min(u64::MAX as u128) as u64;
+        Duration::from_micros(z % (max_micros + 1))
+    }
+
+    pub(crate) async fn wait(&self) {
+        let delay = self.next_delay();
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn waits_stay_within_the_maximum() {
+        let jitter = Jitter::seeded(Duration::from_millis(50), 7);
+        let delays: Vec<Duration> = (0..1000).map(|_| jitter.next_delay()).collect();
+        assert!(delays.iter().all(|&d| d <= Duration::from_millis(50)));
+        // Spread out, not stuck at one value.
+        assert!(delays.iter().any(|&d| d < Duration::from_millis(10)));
+        assert!(delays.iter().any(|&d| d > Duration::from_millis(40)));
+    }
+
+    #[test]
+    fn the_same_seed_gives_the_same_waits() {
+        let a = Jitter::seeded(Duration::from_millis(100), 42);
+        let b = Jitter::seeded(Duration::from_millis(100), 42);
+        for _ in 0..10 {
+            assert_eq!(a.next_delay(), b.next_delay());
+        }
+    }
+
+    #[test]
+    fn zero_and_the_default_never_wait() {
+        assert_eq!(Jitter::new(Duration::ZERO).next_delay(), Duration::ZERO);
+        assert_eq!(Jitter::default().next_delay(), Duration::ZERO);
+    }
+}
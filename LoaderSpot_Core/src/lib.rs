@@ -0,0 +1,57 @@
+//! Shared search logic for the LoaderSpot UI and CLI.
+
+mod backoff;
+mod budget;
+mod cache;
+mod checkpoint;
+mod client;
+mod date;
+mod download;
+mod error_storm;
+mod hints;
+mod jitter;
+mod platform;
+mod progress;
+mod ramp;
+mod rate_limit;
+mod reachability;
+mod report;
+mod search;
+mod verify;
+mod version;
+
+pub use backoff::Backoff;
+pub use budget::{estimate_search_time, RequestBudget, DEFAULT_MAX_REQUESTS};
+pub use cache::{format_age, CachedInstaller, ResultsCache, CACHE_FORMAT_VERSION};
+pub use checkpoint::{
+    CheckedBuilds, Checkpoint, FailedCheck, FailedChecks, NextBuild, SearchParams,
+    CHECKPOINT_FORMAT_VERSION,
+};
+pub use client::{parse_header, ClientOptions, IpFamily, DEFAULT_POOL_IDLE_TIMEOUT, MAX_REDIRECTS};
+pub use date::{format_iso8601, parse_http_date};
+pub use download::{download_command, download_installer, file_name_from_url, Downloader};
+pub use error_storm::ErrorStorm;
+pub use hints::{fetch_versions_json, KnownBuilds, KnownVersions, HINT_MARGIN, VERSIONS_JSON_URL};
+pub use jitter::Jitter;
+pub use platform::{
+    count_builds, generate_url, generate_urls, merge_build_ranges, parse_build_range,
+    parse_build_ranges, PathTemplates, Platform, BASE_URL,
+};
+pub use progress::{
+    ContentTypeCounts, PlatformProgress, RequestCounts, ScannedBuilds, StatusCounts,
+};
+pub use ramp::Ramp;
+pub use rate_limit::RateLimiter;
+pub use reachability::Reachability;
+pub use report::{RunReport, RunStats, SearchedPlatform, SearchedVersion};
+pub use search::{
+    check_url, dedup_found, is_installer_content_type, is_suspiciously_small, probe, probe_at,
+    ConfirmedHits, FoundCallback, FoundEvent, FoundInstaller, LadderOptions, Probe, Rescan,
+    SearchEngine, AUTO_RANGE_GAP, CONFIRM_DELAY, INSTALLER_CONTENT_TYPES, SUSPICIOUS_SIZE,
+};
+pub use verify::{sha256_file, Manifest, ManifestEntry, Verification};
+pub use version::{
+    expand_version_range, extract_base_version, find_versions, numbered_version_lines,
+    short_version, should_use_win_x86, validate_version, validate_version_strict,
+    version_list_lines,
+};
@@ -0,0 +1,408 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+pub const BASE_URL: &str = "https://upgrade.scdn.co/upgrade/client/";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Platform {
+    WinX86,
+    WinX64,
+    WinArm64,
+    MacOsIntel,
+    MacOsArm64,
+}
+
+impl Platform {
+    /// Human readable name, used by the UI.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Platform::WinX86 => "Windows x86",
+            Platform::WinX64 => "Windows x64",
+            Platform::WinArm64 => "Windows arm64",
+            Platform::MacOsIntel => "macOS intel",
+            Platform::MacOsArm64 => "macOS arm64",
+        }
+    }
+
+    /// Short key used in the CLI JSON output.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Platform::WinX86 => "WIN32",
+            Platform::WinX64 => "WIN64",
+            Platform::WinArm64 => "WIN-ARM64",
+            Platform::MacOsIntel => "OSX",
+            Platform::MacOsArm64 => "OSX-ARM64",
+        }
+    }
+
+    /// Inverse of [`Platform::code`], ignoring case.
+    pub fn from_code(code: &str) -> Option<Platform> {
+        Platform::all()
+            .into_iter()
+            .find(|platform| platform.code().eq_ignore_ascii_case(code))
+    }
+
+    pub fn path_template(&self) -> &'static str {
+        match self {
+            Platform::WinX86 => "win32-x86/spotify_installer-{version}-{number}.exe",
+            Platform::WinX64 => "win32-x86_64/spotify_installer-{version}-{number}.exe",
+            Platform::WinArm64 => "win32-arm64/spotify_installer-{version}-{number}.exe",
+            Platform::MacOsIntel => "osx-x86_64/spotify-autoupdate-{version}-{number}.tbz",
+            Platform::MacOsArm64 => "osx-arm64/spotify-autoupdate-{version}-{number}.tbz",
+        }
+    }
+
+    pub fn generate_path(&self, version: &str, number: i32) -> String {
+        fill_template(self.path_template(), version, number)
+    }
+
+    /// Path of the `.dmg` some macOS builds ship as instead of the `.tbz`;
+    /// `None` for Windows.
+    pub fn dmg_path_template(&self) -> Option<&'static str> {
+        match self {
+            Platform::MacOsIntel => Some("osx-x86_64/spotify-{version}-{number}.dmg"),
+            Platform::MacOsArm64 => Some("osx-arm64/spotify-{version}-{number}.dmg"),
+            _ => None,
+        }
+    }
+
+    pub fn generate_dmg_path(&self, version: &str, number: i32) -> Option<String> {
+        self.dmg_path_template()
+            .map(|template| fill_template(template, version, number))
+    }
+
+    pub fn all() -> Vec<Platform> {
+        vec![
+            Platform::WinX86,
+            Platform::WinX64,
+            Platform::WinArm64,
+            Platform::MacOsIntel,
+            Platform::MacOsArm64,
+        ]
+    }
+}
+
+fn fill_template(template: &str, version: &str, number: i32) -> String {
+    template
+        .replace("{version}", version)
+        .replace("{number}", &number.to_string())
+}
+
+/// Path templates that replace [`Platform::path_template`] at runtime, for
+/// when the CDN renames its installers. Platforms without an override keep
+/// the built-in template.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PathTemplates(HashMap<Platform, String>);
+
+impl PathTemplates {
+    /// Overrides the template of `platform`. It must contain both `{version}`
+    /// and `{number}`, or every generated URL would be the same.
+    pub fn set(&mut self, platform: Platform, template: &str) -> Result<(), String> {
+        for placeholder in ["{version}", "{number}"] {
+            if !template.contains(placeholder) {
+                return Err(format!(
+                    "Path template for {} is missing {}: {}",
+                    platform.code(),
+                    placeholder,
+                    template
+                ));
+            }
+        }
+        self.0
+            .insert(platform, template.trim_start_matches('/').to_string());
+        Ok(())
+    }
+
+    pub fn template(&self, platform: Platform) -> &str {
+        self.0
+            .get(&platform)
+            .map_or(platform.path_template(), String::as_str)
+    }
+
+    pub fn generate_path(&self, platform: Platform, version: &str, number: i32) -> String {
+        fill_template(self.template(platform), version, number)
+    }
+
+    pub fn generate_url(&self, platform: Platform, version: &str, number: i32) -> String {
+        format!(
+            "{}{}",
+            BASE_URL,
+            self.generate_path(platform, version, number)
+        )
+    }
+}
+
+/// Installer URL of build `number` of `version` on the Spotify CDN, using the
+/// built-in path for `platform`.
+///
+/// ```
+/// use loaderspot_core::{generate_url, Platform};
+///
+/// assert_eq!(
+///     generate_url(Platform::WinX64, "1.2.13.661.ga588f749", 4550),
+///     "https://upgrade.scdn.co/upgrade/client/win32-x86_64/spotify_installer-1.2.13.661.ga588f749-4550.exe"
+/// );
+/// ```
+pub fn generate_url(platform: Platform, version: &str, number: i32) -> String {
+    format!("{}{}", BASE_URL, platform.generate_path(version, number))
+}
+
+/// Parses a build range like `0-5000`, or `1234` for that build alone.
+pub fn parse_build_range(range: &str) -> Result<(i32, i32), String> {
+    let number = |part: &str| {
+        part.trim()
+            .parse::<i32>()
+            .ok()
+            .filter(|n| *n >= 0)
+            .ok_or_else(|| {
+                format!(
+                    "Invalid build range '{}': '{}' is not a build number",
+                    range, part
+                )
+            })
+    };
+    let (start, end) = match range.split_once('-') {
+        Some((start, end)) => (number(start)?, number(end)?),
+        None => {
+            let build = number(range)?;
+            (build, build)
+        }
+    };
+    if end < start {
+        return Err(format!(
+            "Invalid build range '{}': end must be >= start",
+            range
+        ));
+    }
+    Ok((start, end))
+}
+
+/// Sorts `ranges` and joins the ones that overlap or touch, so no build is
+/// listed twice.
+pub fn merge_build_ranges(mut ranges: Vec<(i32, i32)>) -> Vec<(i32, i32)> {
+    ranges.sort_unstable();
+    let mut merged: Vec<(i32, i32)> = Vec::with_capacity(ranges.len());
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start as i64 <= last.1 as i64 + 1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+/// Parses a comma-separated list of [`parse_build_range`] ranges, like
+/// `0-100,3000-3100`, merged with [`merge_build_ranges`].
+pub fn parse_build_ranges(list: &str) -> Result<Vec<(i32, i32)>, String> {
+    let ranges = list
+        .split(',')
+        .map(parse_build_range)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(merge_build_ranges(ranges))
+}
+
+/// Number of builds a search with `step` checks in `ranges`, which must not
+/// overlap.
+pub fn count_builds(ranges: &[(i32, i32)], step: u32) -> u64 {
+    let step = step.max(1) as u64;
+    ranges
+        .iter()
+        .map(|(start, end)| (end - start) as u64 / step + 1)
+        .sum()
+}
+
+/// Every URL a search of `start..=end` with `step` would check, in the order
+/// it checks them.
+pub fn generate_urls(
+    version: &str,
+    start: i32,
+    end: i32,
+    step: u32,
+    platforms: &[Platform],
+    templates: &PathTemplates,
+) -> Vec<String> {
+    let step = step.max(1) as usize;
+    platforms
+        .iter()
+        .flat_map(|&platform| {
+            (start..=end)
+                .step_by(step)
+                .map(move |number| templates.generate_url(platform, version, number))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generates_windows_urls() {
+        assert_eq!(
+            generate_url(Platform::WinX64, "1.2.60.564.gcc6305cb", 1234),
+            "https://upgrade.scdn.co/upgrade/client/win32-x86_64/spotify_installer-1.2.60.564.gcc6305cb-1234.exe"
+        );
+        assert_eq!(
+            generate_url(Platform::WinArm64, "1.2.60.564.gcc6305cb", 7),
+            "https://upgrade.scdn.co/upgrade/client/win32-arm64/spotify_installer-1.2.60.564.gcc6305cb-7.exe"
+        );
+    }
+
+    #[test]
+    fn generates_a_url_for_every_platform() {
+        let cases = [
+            (
+                Platform::WinX86,
+                "win32-x86/spotify_installer-1.2.53.440.g7b2f582a-9.exe",
+            ),
+            (
+                Platform::WinX64,
+                "win32-x86_64/spotify_installer-1.2.53.440.g7b2f582a-9.exe",
+            ),
+            (
+                Platform::WinArm64,
+                "win32-arm64/spotify_installer-1.2.53.440.g7b2f582a-9.exe",
+            ),
+            (
+                Platform::MacOsIntel,
+                "osx-x86_64/spotify-autoupdate-1.2.53.440.g7b2f582a-9.tbz",
+            ),
+            (
+                Platform::MacOsArm64,
+                "osx-arm64/spotify-autoupdate-1.2.53.440.g7b2f582a-9.tbz",
+            ),
+        ];
+        assert_eq!(cases.len(), Platform::all().len());
+        for (platform, path) in cases {
+            assert_eq!(
+                generate_url(platform, "1.2.53.440.g7b2f582a", 9),
+                format!("{}{}", BASE_URL, path)
+            );
+            assert_eq!(
+                PathTemplates::default().generate_url(platform, "1.2.53.440.g7b2f582a", 9),
+                format!("{}{}", BASE_URL, path)
+            );
+        }
+    }
+
+    #[test]
+    fn generates_macos_urls() {
+        assert_eq!(
+            generate_url(Platform::MacOsArm64, "1.2.60.564.gcc6305cb", 1234),
+            "https://upgrade.scdn.co/upgrade/client/osx-arm64/spotify-autoupdate-1.2.60.564.gcc6305cb-1234.tbz"
+        );
+    }
+
+    #[test]
+    fn only_macos_has_a_dmg_path() {
+        assert_eq!(
+            Platform::MacOsIntel.generate_dmg_path("1.2.60.564.gcc6305cb", 12),
+            Some("osx-x86_64/spotify-1.2.60.564.gcc6305cb-12.dmg".to_string())
+        );
+        assert_eq!(
+            Platform::WinX64.generate_dmg_path("1.2.60.564.gcc6305cb", 12),
+            None
+        );
+    }
+
+    #[test]
+    fn generates_urls_for_a_range() {
+        let urls = generate_urls(
+            "1.2.60.564.gcc6305cb",
+            5,
+            6,
+            1,
+            &[Platform::WinX64, Platform::MacOsIntel],
+            &PathTemplates::default(),
+        );
+        assert_eq!(urls.len(), 4);
+        assert!(urls[0].ends_with("win32-x86_64/spotify_installer-1.2.60.564.gcc6305cb-5.exe"));
+        assert!(urls[3].ends_with("osx-x86_64/spotify-autoupdate-1.2.60.564.gcc6305cb-6.tbz"));
+
+        let urls = generate_urls(
+            "1.2.60.564.gcc6305cb",
+            5,
+            25,
+            10,
+            &[Platform::WinX64],
+            &PathTemplates::default(),
+        );
+        assert_eq!(urls.len(), 3);
+        assert!(urls[2].ends_with("-25.exe"));
+    }
+
+    #[test]
+    fn parses_build_ranges() {
+        assert_eq!(parse_build_range("0-5000"), Ok((0, 5000)));
+        assert_eq!(parse_build_range("1234"), Ok((1234, 1234)));
+        assert_eq!(parse_build_range(" 10 - 20 "), Ok((10, 20)));
+    }
+
+    #[test]
+    fn rejects_bad_build_ranges() {
+        let err = parse_build_range("5000-0").unwrap_err();
+        assert!(err.contains("end must be >= start"), "{}", err);
+        assert!(parse_build_range("1-2-3").is_err());
+        assert!(parse_build_range("-5").is_err());
+        assert!(parse_build_range("abc").is_err());
+        assert!(parse_build_range("").is_err());
+    }
+
+    #[test]
+    fn parses_and_merges_range_lists() {
+        assert_eq!(
+            parse_build_ranges("3000-3100, 0-100"),
+            Ok(vec![(0, 100), (3000, 3100)])
+        );
+        assert_eq!(
+            parse_build_ranges("0-100,50-200,201-250,7"),
+            Ok(vec![(0, 250)])
+        );
+        assert_eq!(count_builds(&[(0, 100), (3000, 3100)], 1), 202);
+        assert_eq!(count_builds(&[(0, 100), (3000, 3100)], 10), 22);
+        assert_eq!(count_builds(&[(0, 9)], 10), 1);
+        assert!(parse_build_ranges("0-100,").is_err());
+    }
+
+    #[test]
+    fn overridden_templates_replace_the_built_in_ones() {
+        let mut templates = PathTemplates::default();
+        templates
+            .set(
+                Platform::MacOsArm64,
+                "osx-arm64/spotify-{version}-{number}.dmg",
+            )
+            .unwrap();
+
+        assert_eq!(
+            templates.generate_url(Platform::MacOsArm64, "1.2.60.564.gcc6305cb", 9),
+            "https://upgrade.scdn.co/upgrade/client/osx-arm64/spotify-1.2.60.564.gcc6305cb-9.dmg"
+        );
+        assert_eq!(
+            templates.generate_url(Platform::WinX64, "1.2.60.564.gcc6305cb", 9),
+            generate_url(Platform::WinX64, "1.2.60.564.gcc6305cb", 9)
+        );
+    }
+
+    #[test]
+    fn rejects_templates_without_placeholders() {
+        let mut templates = PathTemplates::default();
+        let err = templates
+            .set(
+                Platform::WinX64,
+                "win32-x86_64/spotify_installer-{version}.exe",
+            )
+            .unwrap_err();
+        assert!(err.contains("{number}"), "{}", err);
+        assert_eq!(templates, PathTemplates::default());
+    }
+
+    #[test]
+    fn parses_codes_case_insensitively() {
+        for platform in Platform::all() {
+            assert_eq!(Platform::from_code(platform.code()), Some(platform));
+        }
+        assert_eq!(Platform::from_code("win-arm64"), Some(Platform::WinArm64));
+        assert_eq!(Platform::from_code("linux"), None);
+    }
+}
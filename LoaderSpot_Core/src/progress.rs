@@ -0,0 +1,234 @@
+use crate::platform::Platform;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Checked-URL count per platform, shared between the search tasks and a
+/// frontend the same way `SearchEngine::processed` is.
+#[derive(Clone, Default)]
+pub struct PlatformProgress(Arc<Mutex<HashMap<Platform, u64>>>);
+
+impl PlatformProgress {
+    pub fn add(&self, platform: Platform) {
+        *self.0.lock().unwrap().entry(platform).or_default() += 1;
+    }
+
+    pub fn get(&self, platform: Platform) -> u64 {
+        self.0.lock().unwrap().get(&platform).copied().unwrap_or(0)
+    }
+
+    pub fn reset(&self) {
+        self.0.lock().unwrap().clear();
+    }
+}
+
+/// How often each HTTP status answered a build that was not found, where 0
+/// stands for no response at all. An all-404 scan needs a wider range; 403s
+/// point at blocking or geo restrictions instead.
+#[derive(Clone, Default)]
+pub struct StatusCounts(Arc<Mutex<BTreeMap<u16, u64>>>);
+
+impl StatusCounts {
+    pub fn add(&self, status: u16) {
+        *self.0.lock().unwrap().entry(status).or_default() += 1;
+    }
+
+    /// `(status, count)` pairs, most frequent first.
+    pub fn counts(&self) -> Vec<(u16, u64)> {
+        let mut counts: Vec<_> = self
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(&status, &count)| (status, count))
+            .collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        counts
+    }
+
+    /// One line like `404 x4990, 403 x10, no response x1`.
+    pub fn summary(&self) -> String {
+        self.counts()
+            .into_iter()
+            .map(|(status, count)| match status {
+                0 => format!("no response x{}", count),
+                status => format!("{} x{}", status, count),
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    pub fn reset(&self) {
+        self.0.lock().unwrap().clear();
+    }
+}
+
+/// How often each unexpected `Content-Type` came with a hit, e.g. the
+/// `text/html` of an error page served with a 200.
+#[derive(Clone, Default)]
+pub struct ContentTypeCounts(Arc<Mutex<BTreeMap<String, u64>>>);
+
+impl ContentTypeCounts {
+    pub fn add(&self, content_type: &str) {
+        *self
+            .0
+            .lock()
+            .unwrap()
+            .entry(content_type.to_string())
+            .or_default() += 1;
+    }
+
+    /// `(content type, count)` pairs, most frequent first.
+    pub fn counts(&self) -> Vec<(String, u64)> {
+        let mut counts: Vec<_> = self
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(content_type, &count)| (content_type.clone(), count))
+            .collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        counts
+    }
+
+    /// How many hits came with an unexpected type.
+    pub fn total(&self) -> u64 {
+        self.0.lock().unwrap().values().sum()
+    }
+
+    /// One line like `text/html x3, text/plain x1`.
+    pub fn summary(&self) -> String {
+        self.counts()
+            .into_iter()
+            .map(|(content_type, count)| format!("{} x{}", content_type, count))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    pub fn reset(&self) {
+        self.0.lock().unwrap().clear();
+    }
+}
+
+/// Lowest and highest build checked per version and platform. A ladder
+/// search goes past the range asked for, `max_gap` and a cancel stop short
+/// of it, so this is what a search really covered.
+#[derive(Clone, Default)]
+pub struct ScannedBuilds(Arc<Mutex<HashMap<(String, Platform), BuildSpan>>>);
+
+/// Lowest and highest build.
+type BuildSpan = (i32, i32);
+
+impl ScannedBuilds {
+    pub(crate) fn add(&self, version: &str, platform: Platform, build: i32) {
+        self.0
+            .lock()
+            .unwrap()
+            .entry((version.to_string(), platform))
+            .and_modify(|(low, high)| {
+                *low = (*low).min(build);
+                *high = (*high).max(build);
+            })
+            .or_insert((build, build));
+    }
+
+    pub fn get(&self, version: &str, platform: Platform) -> Option<(i32, i32)> {
+        self.0
+            .lock()
+            .unwrap()
+            .get(&(version.to_string(), platform))
+            .copied()
+    }
+
+    pub fn reset(&self) {
+        self.0.lock().unwrap().clear();
+    }
+}
+
+/// URL checks a search sent, and how many of them retried a rate-limited one.
+#[derive(Clone, Default)]
+pub struct RequestCounts {
+    requests: Arc<AtomicU64>,
+    retries: Arc<AtomicU64>,
+}
+
+impl RequestCounts {
+    pub(crate) fn add_request(&self) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn add_retry(&self) {
+        self.retries.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn requests(&self) -> u64 {
+        self.requests.load(Ordering::Relaxed)
+    }
+
+    pub fn retries(&self) -> u64 {
+        self.retries.load(Ordering::Relaxed)
+    }
+
+    pub fn reset(&self) {
+        self.requests.store(0, Ordering::Relaxed);
+        self.retries.store(0, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_each_platform_separately() {
+        let progress = PlatformProgress::default();
+        progress.add(Platform::WinX64);
+        progress.clone().add(Platform::WinX64);
+        progress.add(Platform::MacOsArm64);
+
+        assert_eq!(progress.get(Platform::WinX64), 2);
+        assert_eq!(progress.get(Platform::MacOsArm64), 1);
+        assert_eq!(progress.get(Platform::WinArm64), 0);
+
+        progress.reset();
+        assert_eq!(progress.get(Platform::WinX64), 0);
+    }
+
+    #[test]
+    fn summarises_statuses_by_frequency() {
+        let counts = StatusCounts::default();
+        for status in [404, 403, 404, 0, 404] {
+            counts.add(status);
+        }
+        assert_eq!(counts.summary(), "404 x3, no response x1, 403 x1");
+    }
+
+    #[test]
+    fn summarises_content_types_by_frequency() {
+        let counts = ContentTypeCounts::default();
+        for content_type in ["text/plain", "text/html", "text/html"] {
+            counts.add(content_type);
+        }
+        assert_eq!(counts.summary(), "text/html x2, text/plain x1");
+        assert_eq!(counts.total(), 3);
+    }
+
+    #[test]
+    fn widens_the_scanned_range_of_each_version_and_platform() {
+        let scanned = ScannedBuilds::default();
+        for build in [40, 12, 97, 55] {
+            scanned.add("1.2.3.4.gaaaaaaaa", Platform::WinX64, build);
+        }
+        scanned.add("1.2.3.5.gbbbbbbbb", Platform::WinX64, 3);
+
+        assert_eq!(
+            scanned.get("1.2.3.4.gaaaaaaaa", Platform::WinX64),
+            Some((12, 97))
+        );
+        assert_eq!(
+            scanned.get("1.2.3.5.gbbbbbbbb", Platform::WinX64),
+            Some((3, 3))
+        );
+        assert_eq!(scanned.get("1.2.3.4.gaaaaaaaa", Platform::MacOsArm64), None);
+    }
+}
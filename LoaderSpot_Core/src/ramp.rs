@@ -0,0 +1,82 @@
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+
+/// Opens the connection limit gradually instead of all at once, so a cold
+/// connection pool gets time for its TLS handshakes before every request
+/// competes for one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ramp {
+    /// Connections allowed at first.
+    pub start: usize,
+    /// How often the allowed connections double.
+    pub interval: Duration,
+}
+
+impl Default for Ramp {
+    fn default() -> Self {
+        Self {
+            start: 4,
+            interval: Duration::from_millis(500),
+        }
+    }
+}
+
+impl Ramp {
+    /// Cuts `semaphore` down to `start` permits and doubles them every
+    /// `interval` until it is back at the permits it had. Call it before
+    /// anything acquires a permit, from within a Tokio runtime.
+    pub fn apply(&self, semaphore: &Arc<Semaphore>) {
+        let max = semaphore.available_permits();
+        let mut current = self.start.max(1);
+        if current >= max {
+            return;
+        }
+        // Held back permits are forgotten here and handed back below.
+        let Ok(held) = semaphore.try_acquire_many((max - current) as u32) else {
+            return;
+        };
+        held.forget();
+
+        let semaphore = semaphore.clone();
+        let interval = self.interval;
+        tokio::spawn(async move {
+            while current < max {
+                tokio::time::sleep(interval).await;
+                let next = (current * 2).min(max);
+                semaphore.add_permits(next - current);
+                current = next;
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn doubles_up_to_the_original_limit() {
+        let semaphore = Arc::new(Semaphore::new(10));
+        let ramp = Ramp {
+            start: 2,
+            interval: Duration::from_millis(100),
+        };
+        ramp.apply(&semaphore);
+        assert_eq!(semaphore.available_permits(), 2);
+
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert_eq!(semaphore.available_permits(), 4);
+
+        // 8, then capped at 10 rather than 16.
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert_eq!(semaphore.available_permits(), 10);
+    }
+
+    #[tokio::test]
+    async fn a_start_at_or_above_the_limit_changes_nothing() {
+        let semaphore = Arc::new(Semaphore::new(3));
+        Ramp::default().apply(&semaphore);
+        assert_eq!(semaphore.available_permits(), 3);
+    }
+}
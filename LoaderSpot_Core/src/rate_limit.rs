@@ -0,0 +1,67 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Caps how many requests per second a search sends, however many
+/// connections are open. Requests are spaced evenly, one slot after the
+/// other; the default lets everything through.
+#[derive(Clone, Default)]
+pub struct RateLimiter(Option<Arc<RateState>>);
+
+struct RateState {
+    interval: Duration,
+    next_slot: Mutex<Option<Instant>>,
+}
+
+impl RateLimiter {
+    /// At most `per_second` requests a second; 0 means no limit.
+    pub fn new(per_second: u32) -> Self {
+        if per_second == 0 {
+            return Self::default();
+        }
+        Self(Some(Arc::new(RateState {
+            interval: Duration::from_secs(1) / per_second,
+            next_slot: Mutex::new(None),
+        })))
+    }
+
+    /// Waits for the next free slot.
+    pub async fn wait(&self) {
+        let Some(state) = &self.0 else {
+            return;
+        };
+        let slot = {
+            let now = Instant::now();
+            let mut next_slot = state.next_slot.lock().unwrap();
+            let slot = next_slot.map_or(now, |next| next.max(now));
+            *next_slot = Some(slot + state.interval);
+            slot
+        };
+        tokio::time::sleep_until(slot.into()).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn spaces_requests_evenly() {
+        let limiter = RateLimiter::new(20);
+        let started = Instant::now();
+        for _ in 0..5 {
+            limiter.wait().await;
+        }
+        // The first slot is free, the other four are 50ms apart.
+        assert!(started.elapsed() >= Duration::from_millis(200));
+    }
+
+    #[tokio::test]
+    async fn zero_is_unlimited() {
+        let limiter = RateLimiter::new(0);
+        let started = Instant::now();
+        for _ in 0..1000 {
+            limiter.wait().await;
+        }
+        assert!(started.elapsed() < Duration::from_millis(100));
+    }
+}
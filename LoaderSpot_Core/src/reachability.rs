@@ -0,0 +1,78 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Notices a server that can't be reached at all, e.g. because its name
+/// doesn't resolve or the machine is offline: connecting failed before any
+/// response arrived. A search then stops at once instead of counting its
+/// whole range as misses. Once the server has answered, later failures are
+/// left to [`ErrorStorm`]. The default never trips; see [`watching`].
+///
+/// [`ErrorStorm`]: crate::ErrorStorm
+/// [`watching`]: Reachability::watching
+#[derive(Clone, Default)]
+pub struct Reachability(Option<Arc<ReachState>>);
+
+#[derive(Default)]
+struct ReachState {
+    answered: AtomicBool,
+    unreachable: AtomicBool,
+}
+
+impl Reachability {
+    /// One that trips on the first connection error before any response.
+    pub fn watching() -> Self {
+        Self(Some(Arc::default()))
+    }
+
+    /// Records one request: whether any response arrived and, if not,
+    /// whether connecting failed. Returns true for the request that finds
+    /// the server unreachable.
+    pub(crate) fn record(&self, answered: bool, connect_error: bool) -> bool {
+        let Some(state) = &self.0 else {
+            return false;
+        };
+        if answered {
+            state.answered.store(true, Ordering::Relaxed);
+            return false;
+        }
+        connect_error
+            && !state.answered.load(Ordering::Relaxed)
+            && !state.unreachable.swap(true, Ordering::Relaxed)
+    }
+
+    /// Whether connecting failed before the server ever answered.
+    pub fn unreachable(&self) -> bool {
+        self.0
+            .as_ref()
+            .is_some_and(|state| state.unreachable.load(Ordering::Relaxed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unreachable_when_connecting_fails_first() {
+        let reachability = Reachability::watching();
+        assert!(!reachability.record(false, false));
+        assert!(reachability.record(false, true));
+        assert!(!reachability.record(false, true));
+        assert!(reachability.unreachable());
+    }
+
+    #[test]
+    fn reachable_once_anything_answered() {
+        let reachability = Reachability::watching();
+        assert!(!reachability.record(true, false));
+        assert!(!reachability.record(false, true));
+        assert!(!reachability.unreachable());
+    }
+
+    #[test]
+    fn default_never_trips() {
+        let reachability = Reachability::default();
+        assert!(!reachability.record(false, true));
+        assert!(!reachability.unreachable());
+    }
+}
@@ -0,0 +1,347 @@
+use crate::cache::{unix_now, CachedInstaller};
+use crate::date::civil_from_unix;
+use crate::platform::Platform;
+use crate::progress::ScannedBuilds;
+use crate::search::LadderOptions;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+/// Request counters of a run; see [`RequestCounts`](crate::RequestCounts).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunStats {
+    pub builds_checked: u64,
+    /// URL checks sent, retries and `.dmg` fallbacks included.
+    pub requests: u64,
+    pub retries: u64,
+    pub hits: u64,
+    pub misses: u64,
+    /// Checks that never got an HTTP response.
+    pub network_errors: u64,
+}
+
+/// What a search really covered for one version: the builds checked per
+/// platform, and the platforms left out because the version was never
+/// published for them, as Windows x86 after 1.2.53.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchedVersion {
+    pub version: String,
+    pub platforms: Vec<SearchedPlatform>,
+    pub skipped: Vec<Platform>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchedPlatform {
+    pub platform: Platform,
+    /// Lowest and highest build checked, `None` when no build was.
+    pub builds: Option<(i32, i32)>,
+}
+
+impl SearchedVersion {
+    pub fn new(
+        version: &str,
+        platforms: &[Platform],
+        skipped: Vec<Platform>,
+        scanned: &ScannedBuilds,
+    ) -> Self {
+        Self {
+            version: version.to_string(),
+            platforms: platforms
+                .iter()
+                .map(|&platform| SearchedPlatform {
+                    platform,
+                    builds: scanned.get(version, platform),
+                })
+                .collect(),
+            skipped,
+        }
+    }
+
+    /// One line like `WIN64 builds 0-5000, OSX none; WIN32 skipped, not
+    /// published for this version`.
+    pub fn describe(&self) -> String {
+        let mut parts: Vec<String> = self
+            .platforms
+            .iter()
+            .map(|searched| match searched.builds {
+                Some((low, high)) if low == high => {
+                    format!("{} build {}", searched.platform.code(), low)
+                }
+                Some((low, high)) => {
+                    format!("{} builds {}-{}", searched.platform.code(), low, high)
+                }
+                None => format!("{} none", searched.platform.code()),
+            })
+            .collect();
+        if parts.is_empty() {
+            parts.push("nothing".to_string());
+        }
+        let mut line = parts.join(", ");
+        if !self.skipped.is_empty() {
+            let skipped: Vec<&str> = self.skipped.iter().map(Platform::code).collect();
+            let _ = write!(
+                line,
+                "; {} skipped, not published for this version",
+                skipped.join(", ")
+            );
+        }
+        line
+    }
+}
+
+/// Record of one search run, for pasting into an archive log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunReport {
+    /// Program name and version, e.g. `loaderspot_cli 0.1.0`.
+    pub tool: String,
+    /// Seconds since the Unix epoch.
+    pub started_at: u64,
+    pub elapsed_secs: f64,
+    /// Stopped before every build was checked.
+    pub cancelled: bool,
+    pub versions: Vec<String>,
+    /// What was scanned, e.g. `builds 0-5000`.
+    pub scope: String,
+    pub platforms: Vec<Platform>,
+    pub connections: usize,
+    pub requests_per_second: Option<u32>,
+    pub stats: RunStats,
+    /// Reports from before it was recorded have none.
+    #[serde(default)]
+    pub searched: Vec<SearchedVersion>,
+    pub found: Vec<CachedInstaller>,
+}
+
+impl RunReport {
+    /// A report for a run starting now; the caller fills in the rest as the
+    /// run ends.
+    pub fn start(tool: &str, versions: Vec<String>, scope: String) -> Self {
+        Self {
+            tool: tool.to_string(),
+            started_at: unix_now(),
+            elapsed_secs: 0.0,
+            cancelled: false,
+            versions,
+            scope,
+            platforms: Vec::new(),
+            connections: 0,
+            requests_per_second: None,
+            stats: RunStats::default(),
+            searched: Vec::new(),
+            found: Vec::new(),
+        }
+    }
+
+    /// `scope` text for a ladder search, or else a search of `ranges`.
+    pub fn describe_scope(ladder: Option<&LadderOptions>, ranges: &[(i32, i32)]) -> String {
+        if let Some(ladder) = ladder {
+            return format!("smart search, builds 0-{} at most", ladder.max_build());
+        }
+        let ranges: Vec<String> = ranges
+            .iter()
+            .map(|&(start, end)| {
+                if start == end {
+                    start.to_string()
+                } else {
+                    format!("{}-{}", start, end)
+                }
+            })
+            .collect();
+        format!("builds {}", ranges.join(", "))
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::from("# LoaderSpot report\n\n");
+        let platforms: Vec<&str> = self.platforms.iter().map(Platform::code).collect();
+        let rate = self
+            .requests_per_second
+            .map_or(String::new(), |rps| format!(", at most {} requests/s", rps));
+        let stats = &self.stats;
+        let _ = writeln!(md, "- Tool: {}", self.tool);
+        let _ = writeln!(md, "- Started: {}", format_utc(self.started_at));
+        let _ = writeln!(
+            md,
+            "- Elapsed: {:.1}s{}",
+            self.elapsed_secs,
+            if self.cancelled { " (cancelled)" } else { "" }
+        );
+        let _ = writeln!(md, "- Versions: {}", self.versions.join(", "));
+        let _ = writeln!(md, "- Scope: {}", self.scope);
+        let _ = writeln!(md, "- Platforms: {}", platforms.join(", "));
+        let _ = writeln!(md, "- Connections: {}{}", self.connections, rate);
+        let _ = writeln!(
+            md,
+            "- Requests: {} ({} retries)",
+            stats.requests, stats.retries
+        );
+        let _ = writeln!(
+            md,
+            "- Builds checked: {}: {} found, {} missed, {} without response",
+            stats.builds_checked, stats.hits, stats.misses, stats.network_errors
+        );
+
+        if !self.searched.is_empty() {
+            md.push_str("\n## Searched\n\n");
+            for searched in &self.searched {
+                let _ = writeln!(md, "- {}: {}", searched.version, searched.describe());
+            }
+        }
+
+        md.push_str("\n## Found\n\n");
+        if self.found.is_empty() {
+            md.push_str("Nothing found.\n");
+            return md;
+        }
+        md.push_str("| Platform | Version | Build | URL | Size | Last modified |\n");
+        md.push_str("|---|---|---|---|---|---|\n");
+        for installer in &self.found {
+            let _ = writeln!(
+                md,
+                "| {} | {} | {} | {} | {} | {} |",
+                installer.platform.code(),
+                installer.version,
+                installer.build,
+                installer.url,
+                installer
+                    .size
+                    .map_or("?".to_string(), |size| size.to_string()),
+                installer.last_modified.as_deref().unwrap_or("?")
+            );
+        }
+        md
+    }
+
+    /// Writes Markdown to a `.md` path and pretty JSON to anything else.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let markdown = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("md"));
+        let text = if markdown {
+            self.to_markdown()
+        } else {
+            serde_json::to_string_pretty(self).map_err(|e| e.to_string())?
+        };
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Cannot create {}: {}", dir.display(), e))?;
+        }
+        fs::write(path, text).map_err(|e| format!("Cannot write report {}: {}", path.display(), e))
+    }
+}
+
+/// `2026-10-16 01:51:07 UTC` for seconds since the Unix epoch.
+fn format_utc(secs: u64) -> String {
+    let (year, month, day, hour, minute, second) = civil_from_unix(secs);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        year, month, day, hour, minute, second
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> RunReport {
+        RunReport {
+            started_at: 1_700_000_000,
+            elapsed_secs: 12.34,
+            platforms: vec![Platform::WinX64],
+            connections: 100,
+            requests_per_second: Some(20),
+            stats: RunStats {
+                builds_checked: 10,
+                requests: 11,
+                retries: 1,
+                hits: 1,
+                misses: 9,
+                network_errors: 0,
+            },
+            searched: vec![SearchedVersion {
+                version: "1.2.60.564.gcc6305cb".to_string(),
+                platforms: vec![SearchedPlatform {
+                    platform: Platform::WinX64,
+                    builds: Some((0, 9)),
+                }],
+                skipped: vec![Platform::WinX86],
+            }],
+            found: vec![CachedInstaller {
+                version: "1.2.60.564.gcc6305cb".to_string(),
+                platform: Platform::WinX64,
+                build: 7,
+                url: "https://example.com/spotify_installer-1.2.60.564.gcc6305cb-7.exe".to_string(),
+                size: None,
+                last_modified: None,
+            }],
+            ..RunReport::start(
+                "loaderspot_cli 0.1.0",
+                vec!["1.2.60.564.gcc6305cb".to_string()],
+                "builds 0-9".to_string(),
+            )
+        }
+    }
+
+    #[test]
+    fn formats_utc_dates() {
+        assert_eq!(format_utc(0), "1970-01-01 00:00:00 UTC");
+        assert_eq!(format_utc(1_700_000_000), "2023-11-14 22:13:20 UTC");
+        assert_eq!(format_utc(951_825_600), "2000-02-29 12:00:00 UTC");
+    }
+
+    #[test]
+    fn describes_the_scope() {
+        assert_eq!(
+            RunReport::describe_scope(None, &[(0, 100), (3000, 3000)]),
+            "builds 0-100, 3000"
+        );
+        assert_eq!(
+            RunReport::describe_scope(Some(&LadderOptions::default()), &[(0, 5000)]),
+            "smart search, builds 0-16000 at most"
+        );
+    }
+
+    #[test]
+    fn renders_markdown() {
+        let md = report().to_markdown();
+        assert!(
+            md.contains("- Started: 2023-11-14 22:13:20 UTC\n"),
+            "{}",
+            md
+        );
+        assert!(
+            md.contains("- Connections: 100, at most 20 requests/s\n"),
+            "{}",
+            md
+        );
+        assert!(
+            md.contains("- Builds checked: 10: 1 found, 9 missed, 0 without response\n"),
+            "{}",
+            md
+        );
+        assert!(
+            md.contains(
+                "- 1.2.60.564.gcc6305cb: WIN64 builds 0-9; WIN32 skipped, not published for this version\n"
+            ),
+            "{}",
+            md
+        );
+        assert!(md.ends_with(
+            "| WIN64 | 1.2.60.564.gcc6305cb | 7 | https://example.com/spotify_installer-1.2.60.564.gcc6305cb-7.exe | ? | ? |\n"
+        ));
+    }
+
+    #[test]
+    fn picks_the_format_from_the_extension() {
+        let dir = std::env::temp_dir().join(format!("loaderspot-report-{}", std::process::id()));
+        let report = report();
+
+        report.save(&dir.join("run.md")).unwrap();
+        let md = fs::read_to_string(dir.join("run.md")).unwrap();
+        assert!(md.starts_with("# LoaderSpot report"));
+
+        report.save(&dir.join("run.json")).unwrap();
+        let json = fs::read_to_string(dir.join("run.json")).unwrap();
+        assert_eq!(serde_json::from_str::<RunReport>(&json).unwrap(), report);
+    }
+}
@@ -0,0 +1,1293 @@
+use crate::backoff::Backoff;
+use crate::budget::RequestBudget;
+use crate::cache::CachedInstaller;
+use crate::checkpoint::{CheckedBuilds, FailedCheck, FailedChecks};
+use crate::date::{format_iso8601, parse_http_date};
+use crate::error_storm::ErrorStorm;
+use crate::hints::KnownBuilds;
+use crate::jitter::Jitter;
+use crate::platform::{generate_url, PathTemplates, Platform, BASE_URL};
+use crate::progress::{
+    ContentTypeCounts, PlatformProgress, RequestCounts, ScannedBuilds, StatusCounts,
+};
+use crate::rate_limit::RateLimiter;
+use crate::reachability::Reachability;
+use log::{debug, trace, warn};
+use reqwest::header::{
+    HeaderMap, ACCEPT_ENCODING, CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE, LAST_MODIFIED,
+    LOCATION, RANGE, RETRY_AFTER,
+};
+use reqwest::{Client, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicI32, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tokio::task::JoinHandle;
+
+/// Real installers are tens of megabytes; a "found" file smaller than this is
+/// most likely a placeholder the CDN serves instead.
+pub const SUSPICIOUS_SIZE: u64 = 1024 * 1024;
+
+/// Probe spacing and run of misses that end a scan in
+/// [`SearchEngine::auto_range_search`] unless a `max_gap` is set.
+pub const AUTO_RANGE_GAP: u32 = 50;
+
+/// How long [`SearchEngine::confirm_hits`] waits before checking again, so a
+/// glitching CDN edge has a moment to recover.
+pub const CONFIRM_DELAY: Duration = Duration::from_secs(2);
+
+/// Whether an installer of `size` bytes looks like a placeholder. An unknown
+/// size does not.
+pub fn is_suspiciously_small(size: Option<u64>) -> bool {
+    size.is_some_and(|size| size < SUSPICIOUS_SIZE)
+}
+
+/// `Content-Type`s the CDN and its mirrors serve installers with. Anything
+/// else, `text/html` above all, is an error page answering with a 200.
+pub const INSTALLER_CONTENT_TYPES: &[&str] = &[
+    "application/octet-stream",
+    "binary/octet-stream",
+    "application/x-apple-diskimage",
+    "application/x-bzip2",
+    "application/x-bzip",
+    "application/x-tar",
+    "application/x-msdownload",
+    "application/x-msdos-program",
+    "application/vnd.microsoft.portable-executable",
+];
+
+/// Whether `content_type` is one of [`INSTALLER_CONTENT_TYPES`], ignoring
+/// case and parameters such as `charset`.
+pub fn is_installer_content_type(content_type: &str) -> bool {
+    let media_type = content_type.split(';').next().unwrap_or("").trim();
+    INSTALLER_CONTENT_TYPES
+        .iter()
+        .any(|expected| media_type.eq_ignore_ascii_case(expected))
+}
+
+/// A confirmed installer, handed to `SearchEngine::on_found` as soon as it is seen.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FoundEvent {
+    pub version: Arc<str>,
+    pub build: i32,
+    pub url: String,
+    pub platform: Platform,
+    pub size: Option<u64>,
+    /// `Last-Modified` as ISO 8601, when the server sent a valid one.
+    pub last_modified: Option<String>,
+}
+
+/// An installer a search found, with what the response told about it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FoundInstaller {
+    pub url: String,
+    pub platform: Platform,
+    pub size: Option<u64>,
+    /// `Last-Modified` as ISO 8601, when the server sent a valid one.
+    pub last_modified: Option<String>,
+    pub content_type: Option<String>,
+}
+
+/// Cached results don't keep the content type.
+impl From<&CachedInstaller> for FoundInstaller {
+    fn from(installer: &CachedInstaller) -> Self {
+        Self {
+            url: installer.url.clone(),
+            platform: installer.platform,
+            size: installer.size,
+            last_modified: installer.last_modified.clone(),
+            content_type: None,
+        }
+    }
+}
+
+pub type FoundCallback = Arc<dyn Fn(&FoundEvent) + Send + Sync>;
+
+/// What [`SearchEngine::rescan_failures`] made of a list of failed checks.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Rescan {
+    /// Checks that got an answer this time, hit or miss.
+    pub recovered: usize,
+    pub found: Vec<CachedInstaller>,
+    /// Checks that failed again, and any a cancel left unchecked.
+    pub still_failing: Vec<FailedCheck>,
+}
+
+/// What [`SearchEngine::confirm_hits`] returns: the confirmed hits, and the
+/// others with the status of their second check.
+pub type ConfirmedHits = (Vec<FoundInstaller>, Vec<(FoundInstaller, Option<u16>)>);
+
+/// Reads `Content-Length` straight from the headers; reqwest reports the
+/// (empty) body length for HEAD responses instead.
+pub(crate) fn header_content_length(headers: &HeaderMap) -> Option<u64> {
+    headers
+        .get(CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse().ok())
+}
+
+/// `Last-Modified` as ISO 8601 in UTC, e.g. `2024-03-01T12:34:56Z`. Missing
+/// or malformed headers give `None`.
+pub(crate) fn header_last_modified(headers: &HeaderMap) -> Option<String> {
+    let value = headers.get(LAST_MODIFIED)?.to_str().ok()?;
+    let parsed = parse_http_date(value);
+    if parsed.is_none() {
+        debug!("Ignoring malformed Last-Modified: {}", value);
+    }
+    parsed.map(format_iso8601)
+}
+
+/// `Content-Type` as sent, when it is there.
+pub(crate) fn header_content_type(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+}
+
+/// Total size from a `Content-Range: bytes 0-0/<total>` header.
+pub(crate) fn content_range_total(headers: &HeaderMap) -> Option<u64> {
+    headers
+        .get(CONTENT_RANGE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.rsplit('/').next())
+        .and_then(|v| v.parse().ok())
+}
+
+/// A redirect the client did not follow, which only happens when
+/// `ClientOptions::follow_redirects` is off. It is reported as a hit on the
+/// URL that answered with it.
+fn unfollowed_redirect(url: &str, response: &Response) -> bool {
+    let Some(location) = response.headers().get(LOCATION) else {
+        return false;
+    };
+    if !response.status().is_redirection() {
+        return false;
+    }
+    debug!(
+        "{} redirects to {}, not following",
+        url,
+        location.to_str().unwrap_or("?")
+    );
+    true
+}
+
+/// What a response that found the file tells about it, read from its headers
+/// in one pass: the status, `size` as the caller worked it out, the
+/// modification date, the content type and the URL after redirects.
+fn found_probe(response: &Response, size: Option<u64>) -> Probe {
+    let headers = response.headers();
+    Probe {
+        exists: true,
+        status: response.status().as_u16(),
+        content_length: size,
+        last_modified: header_last_modified(headers),
+        url: response.url().to_string(),
+        content_type: header_content_type(headers),
+    }
+}
+
+/// A redirect the client did not follow is a hit on the URL that answered
+/// with it, and says nothing about the file behind it.
+fn redirect_probe(status: StatusCode, url: &str) -> Probe {
+    Probe {
+        exists: true,
+        status: status.as_u16(),
+        content_length: None,
+        last_modified: None,
+        url: url.to_string(),
+        content_type: None,
+    }
+}
+
+/// A file that isn't there, with the status that said so, or 0 when no
+/// response arrived.
+fn missing_probe(status: u16, url: &str) -> Probe {
+    Probe {
+        exists: false,
+        status,
+        content_length: None,
+        last_modified: None,
+        url: url.to_string(),
+        content_type: None,
+    }
+}
+
+/// Some CDN edges reject HEAD outright; a one-byte ranged GET confirms the
+/// file exists without downloading it. Returns what it tells about the file
+/// if it exists, otherwise the status if any response arrived.
+async fn exists_via_ranged_get(client: &Client, url: &str) -> Result<Probe, Option<StatusCode>> {
+    // A range of a compressed body would report the compressed size.
+    let request = client
+        .get(url)
+        .header(RANGE, "bytes=0-0")
+        .header(ACCEPT_ENCODING, "identity");
+    match request.send().await {
+        Ok(response) => {
+            let status = response.status();
+            debug!("GET {} (bytes=0-0) -> {}", url, status);
+            match status {
+                StatusCode::PARTIAL_CONTENT => Ok(found_probe(
+                    &response,
+                    content_range_total(response.headers()),
+                )),
+                StatusCode::OK => Ok(found_probe(
+                    &response,
+                    header_content_length(response.headers()),
+                )),
+                _ if unfollowed_redirect(url, &response) => Ok(redirect_probe(status, url)),
+                _ => Err(Some(status)),
+            }
+        }
+        Err(e) => {
+            debug!("GET {} (bytes=0-0) failed: {}", url, e);
+            Err(None)
+        }
+    }
+}
+
+/// Backoff used when a 429 arrives without a usable `Retry-After`.
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(5);
+/// How many times a single URL is retried after being rate-limited.
+const MAX_RATE_LIMIT_RETRIES: u32 = 5;
+
+enum UrlStatus {
+    /// Everything the response that found the file told about it.
+    Found(Probe),
+    /// The status that answered; 429 once rate-limit retries ran out.
+    Missing(StatusCode),
+    /// The 429, or 503 with a `Retry-After`, and how long to back off.
+    RateLimited(StatusCode, Duration),
+    /// The request never got an HTTP response; true when connecting
+    /// failed, e.g. because the host name didn't resolve.
+    NetworkError(bool),
+}
+
+/// Parses the delay-seconds form of `Retry-After`.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    headers
+        .get(RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .map(Duration::from_secs)
+}
+
+async fn probe_url(client: &Client, url: &str) -> UrlStatus {
+    trace!("HEAD {}", url);
+    match client.head(url).send().await {
+        Ok(response) => {
+            let status = response.status();
+            debug!("HEAD {} -> {}", url, status);
+            if status.is_success() {
+                UrlStatus::Found(found_probe(
+                    &response,
+                    header_content_length(response.headers()),
+                ))
+            } else if unfollowed_redirect(url, &response) {
+                UrlStatus::Found(redirect_probe(status, url))
+            } else if matches!(
+                status,
+                StatusCode::METHOD_NOT_ALLOWED | StatusCode::NOT_IMPLEMENTED
+            ) {
+                match exists_via_ranged_get(client, url).await {
+                    Ok(probe) => UrlStatus::Found(probe),
+                    Err(get_status) => UrlStatus::Missing(get_status.unwrap_or(status)),
+                }
+            } else if status == StatusCode::TOO_MANY_REQUESTS {
+                warn!("Rate limited (429) on {}", url);
+                UrlStatus::RateLimited(
+                    status,
+                    retry_after(response.headers()).unwrap_or(DEFAULT_RETRY_AFTER),
+                )
+            } else if status == StatusCode::SERVICE_UNAVAILABLE {
+                match retry_after(response.headers()) {
+                    Some(delay) => UrlStatus::RateLimited(status, delay),
+                    None => UrlStatus::Missing(status),
+                }
+            } else {
+                UrlStatus::Missing(status)
+            }
+        }
+        Err(e) => {
+            warn!("Network error for {}: {}", url, e);
+            UrlStatus::NetworkError(e.is_connect())
+        }
+    }
+}
+
+/// Checks `url` the way a search does, with no retries, and returns what
+/// it found out about the file if it exists: the URL it was found at, which
+/// differs from `url` when the server redirected, its status, size,
+/// `Last-Modified` as ISO 8601 and content type. All of it comes from the
+/// HEAD response; only a server that rejects HEAD gets a second request.
+pub async fn check_url(client: &Client, url: &str) -> Option<Probe> {
+    match probe_url(client, url).await {
+        UrlStatus::Found(probe) => Some(probe),
+        UrlStatus::Missing(_) | UrlStatus::RateLimited(..) | UrlStatus::NetworkError(_) => None,
+    }
+}
+
+/// Outcome of checking one installer URL with [`probe`], [`probe_at`] or
+/// [`check_url`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Probe {
+    pub exists: bool,
+    /// HTTP status of the last response, or 0 when none arrived.
+    pub status: u16,
+    pub content_length: Option<u64>,
+    /// `Last-Modified` as ISO 8601, when the file exists and it was sent.
+    pub last_modified: Option<String>,
+    /// URL the file was found at, which differs from the probed one after a
+    /// redirect. A miss keeps the probed URL.
+    pub url: String,
+    /// `Content-Type` of the file, when it exists and it was sent.
+    pub content_type: Option<String>,
+}
+
+impl Probe {
+    /// The content type, when it is not one of [`INSTALLER_CONTENT_TYPES`].
+    pub fn unexpected_type(&self) -> Option<&str> {
+        self.content_type
+            .as_deref()
+            .filter(|content_type| !is_installer_content_type(content_type))
+    }
+}
+
+/// Checks whether build `number` of `version` exists for `platform` on the
+/// CDN, with a single request and no retries; see [`generate_url`] for the
+/// URL. This is the building block for custom search strategies.
+///
+/// ```no_run
+/// use loaderspot_core::{probe, Platform};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let client = reqwest::Client::new();
+///     let result = probe(&client, Platform::WinX64, "1.2.13.661.ga588f749", 4550).await;
+///     if result.exists {
+///         println!("found, {:?} bytes", result.content_length);
+///     }
+/// }
+/// ```
+pub async fn probe(client: &Client, platform: Platform, version: &str, number: i32) -> Probe {
+    probe_at(client, &generate_url(platform, version, number)).await
+}
+
+/// [`probe`] for an arbitrary URL, e.g. one built from [`PathTemplates`] or
+/// a mirror. Servers that reject HEAD are asked for the first byte instead.
+/// Redirects are followed as the client is configured to.
+pub async fn probe_at(client: &Client, url: &str) -> Probe {
+    match probe_url(client, url).await {
+        UrlStatus::Found(probe) => probe,
+        UrlStatus::Missing(status) | UrlStatus::RateLimited(status, _) => {
+            missing_probe(status.as_u16(), url)
+        }
+        UrlStatus::NetworkError(_) => missing_probe(0, url),
+    }
+}
+
+/// Like `probe_url`, but honours and extends the shared backoff, retrying
+/// the URL once the server lets us back in. Every attempt also waits out
+/// `jitter` and a slot from `rate`, and is tallied in `counts`. Never returns
+/// `RateLimited`.
+async fn probe_url_with_backoff(
+    client: &Client,
+    url: &str,
+    backoff: &Backoff,
+    rate: &RateLimiter,
+    jitter: &Jitter,
+    counts: &RequestCounts,
+) -> UrlStatus {
+    let mut retries = 0;
+    loop {
+        jitter.wait().await;
+        backoff.wait().await;
+        rate.wait().await;
+        counts.add_request();
+        match probe_url(client, url).await {
+            UrlStatus::RateLimited(_, delay) if retries < MAX_RATE_LIMIT_RETRIES => {
+                backoff.extend(delay);
+                retries += 1;
+                counts.add_retry();
+                debug!(
+                    "Retrying {} in {:?} ({}/{})",
+                    url, delay, retries, MAX_RATE_LIMIT_RETRIES
+                );
+            }
+            UrlStatus::RateLimited(..) => {
+                warn!("Giving up on {} after {} retries", url, retries);
+                return UrlStatus::Missing(StatusCode::TOO_MANY_REQUESTS);
+            }
+            status => return status,
+        }
+    }
+}
+
+/// One platform's progress within a `search`: its highest hit so far, the
+/// builds whose task has not finished, which `max_gap` has to wait out, and
+/// the first build not checked yet along with the checked ones above it.
+/// Only every `step`th build from the start gets checked.
+struct PlatformScan {
+    highest_hit: AtomicI32,
+    pending: Mutex<BTreeSet<i32>>,
+    stopped: AtomicBool,
+    checked: Mutex<(i32, BTreeSet<i32>)>,
+    step: i32,
+}
+
+impl PlatformScan {
+    fn new(start: i32, step: i32) -> Self {
+        Self {
+            highest_hit: AtomicI32::new(-1),
+            pending: Mutex::new(BTreeSet::new()),
+            stopped: AtomicBool::new(false),
+            checked: Mutex::new((start, BTreeSet::new())),
+            step,
+        }
+    }
+
+    /// Marks `number` as done. Returns the new first unchecked build when
+    /// that moved.
+    fn mark_checked(&self, number: i32) -> Option<i32> {
+        let mut checked = self.checked.lock().unwrap();
+        let (next, above) = &mut *checked;
+        if number != *next {
+            above.insert(number);
+            return None;
+        }
+        *next = next.saturating_add(self.step);
+        while above.remove(next) {
+            *next = next.saturating_add(self.step);
+        }
+        Some(*next)
+    }
+
+    /// Whether `number` lies more than `gap` builds past the highest hit.
+    /// Before the first hit nothing is past the gap.
+    fn past_gap(&self, number: i32, gap: u32) -> bool {
+        let hit = self.highest_hit.load(Ordering::Relaxed);
+        hit >= 0 && number as i64 > hit as i64 + gap as i64
+    }
+
+    fn pending_below(&self, number: i32) -> bool {
+        self.pending
+            .lock()
+            .unwrap()
+            .first()
+            .is_some_and(|&lowest| lowest < number)
+    }
+}
+
+/// Keeps a build in its platform's pending set until its task ends, however
+/// it ends, aborts included.
+struct PendingBuild {
+    scan: Arc<PlatformScan>,
+    number: i32,
+}
+
+impl PendingBuild {
+    fn new(scan: Arc<PlatformScan>, number: i32) -> Self {
+        scan.pending.lock().unwrap().insert(number);
+        Self { scan, number }
+    }
+}
+
+impl Drop for PendingBuild {
+    fn drop(&mut self) {
+        self.scan.pending.lock().unwrap().remove(&self.number);
+    }
+}
+
+/// Scans build numbers for a version across platforms.
+///
+/// The shared fields let a frontend drive and observe a running search. The
+/// semaphore is owned by the engine, so concurrent `search` calls on one
+/// engine share the connection limit.
+pub struct SearchEngine {
+    pub client: Client,
+    pub base_url: String,
+    /// Runtime overrides of the per-platform paths.
+    pub templates: PathTemplates,
+    pub semaphore: Arc<Semaphore>,
+    pub pause_flag: Arc<AtomicBool>,
+    pub cancel_flag: Arc<AtomicBool>,
+    /// Builds checked, each once however many retries it took.
+    pub processed: Arc<AtomicU64>,
+    /// `processed` split up by platform.
+    pub platform_processed: PlatformProgress,
+    /// URLs that got no HTTP response at all.
+    pub network_errors: Arc<AtomicU64>,
+    /// Pauses every task while the server is rate-limiting us.
+    pub backoff: Backoff,
+    /// Caps the requests per second on top of the connection limit.
+    pub rate_limiter: RateLimiter,
+    /// Spreads requests out by waiting a random moment before each.
+    pub jitter: Jitter,
+    /// Invoked for every hit as soon as it is confirmed.
+    pub on_found: Option<FoundCallback>,
+    /// Ends a platform's scan once that many builds past its highest hit have
+    /// all missed; before the first hit it never ends early.
+    pub max_gap: Option<u32>,
+    /// Tallies the statuses of the builds that missed.
+    pub status_counts: Option<StatusCounts>,
+    /// Requests sent, which retries make more than `processed`.
+    pub request_counts: RequestCounts,
+    /// Also looks for a macOS build whose `.tbz` is missing as a `.dmg`,
+    /// which costs a second request for every miss.
+    pub try_dmg: bool,
+    /// Sets `pause_flag` once most recent checks got no response; whoever
+    /// resumes the search should reset it.
+    pub error_storm: ErrorStorm,
+    /// Sets `cancel_flag` when connecting fails before the server ever
+    /// answered, so an offline search ends at once; check it to tell that
+    /// from a cancel.
+    pub reachability: Reachability,
+    /// Follows how far each platform of a `search` got for a [`Checkpoint`];
+    /// builds a cancelled search skipped don't count. Ladder and hinted
+    /// searches jump around, so leave it unset for those.
+    ///
+    /// [`Checkpoint`]: crate::Checkpoint
+    pub checked_builds: Option<CheckedBuilds>,
+    /// Above 1, only every `step`th build from the start of a range is
+    /// checked, a quick sweep of a wide range to refine around its hits
+    /// later. `max_gap` still counts builds, not checks.
+    pub step: u32,
+    /// The lowest and highest build each version and platform actually got a
+    /// request for.
+    pub scanned_builds: ScannedBuilds,
+    /// A build whose `Content-Length` is smaller counts as a miss under its
+    /// status; one without a `Content-Length` is still a hit.
+    pub min_size: Option<u64>,
+    /// Caps the requests of every search on the engine. Once it runs out the
+    /// engine sets `cancel_flag`; check [`RequestBudget::exhausted`] to tell
+    /// that from a cancel.
+    pub budget: RequestBudget,
+    /// Collects the checks of a `search` that got no response or stayed
+    /// rate-limited, for [`SearchEngine::rescan_failures`].
+    pub failed_checks: Option<FailedChecks>,
+    /// Counts hits whose `Content-Type` is not one of
+    /// [`INSTALLER_CONTENT_TYPES`] as misses under their status. A hit
+    /// without a `Content-Type` still counts.
+    pub require_content_type: bool,
+    /// Tallies the hits of a `search` or `check_urls` whose `Content-Type` is
+    /// not one of [`INSTALLER_CONTENT_TYPES`].
+    pub unexpected_types: ContentTypeCounts,
+}
+
+impl SearchEngine {
+    pub fn new(client: Client, max_connections: usize) -> Self {
+        Self {
+            client,
+            base_url: BASE_URL.to_string(),
+            templates: PathTemplates::default(),
+            // A zero-permit semaphore would never let a request through.
+            semaphore: Arc::new(Semaphore::new(max_connections.max(1))),
+            pause_flag: Arc::new(AtomicBool::new(false)),
+            cancel_flag: Arc::new(AtomicBool::new(false)),
+            processed: Arc::new(AtomicU64::new(0)),
+            platform_processed: PlatformProgress::default(),
+            network_errors: Arc::new(AtomicU64::new(0)),
+            backoff: Backoff::default(),
+            rate_limiter: RateLimiter::default(),
+            jitter: Jitter::default(),
+            on_found: None,
+            max_gap: None,
+            status_counts: None,
+            request_counts: RequestCounts::default(),
+            try_dmg: false,
+            error_storm: ErrorStorm::default(),
+            reachability: Reachability::default(),
+            checked_builds: None,
+            step: 1,
+            scanned_builds: ScannedBuilds::default(),
+            min_size: None,
+            budget: RequestBudget::default(),
+            failed_checks: None,
+            require_content_type: false,
+            unexpected_types: ContentTypeCounts::default(),
+        }
+    }
+
+    pub async fn search(
+        &self,
+        version: &str,
+        start: i32,
+        end: i32,
+        platforms: &[Platform],
+    ) -> Vec<FoundInstaller> {
+        let found_urls = Arc::new(Mutex::new(Vec::new()));
+        let shared_version: Arc<str> = Arc::from(version);
+        let mut tasks = Vec::new();
+
+        // Every build gets its task up front; each one waits for its own
+        // permit, so the semaphore alone decides how many requests run.
+        'outer: for &platform in platforms {
+            let step = self.step.clamp(1, i32::MAX as u32);
+            let scan = Arc::new(PlatformScan::new(start, step as i32));
+            for number in (start..=end).step_by(step as usize) {
+                if self.cancel_flag.load(Ordering::Relaxed) {
+                    break 'outer;
+                }
+
+                let url = format!(
+                    "{}{}",
+                    self.base_url,
+                    self.templates.generate_path(platform, version, number)
+                );
+                let client = self.client.clone();
+                let semaphore = self.semaphore.clone();
+                let pause_flag = self.pause_flag.clone();
+                let processed = self.processed.clone();
+                let platform_processed = self.platform_processed.clone();
+                let network_errors = self.network_errors.clone();
+                let cancel_local = self.cancel_flag.clone();
+                let on_found = self.on_found.clone();
+                let found_urls = found_urls.clone();
+                let version = shared_version.clone();
+                let backoff = self.backoff.clone();
+                let rate_limiter = self.rate_limiter.clone();
+                let jitter = self.jitter.clone();
+                let request_counts = self.request_counts.clone();
+                let max_gap = self.max_gap;
+                let status_counts = self.status_counts.clone();
+                let error_storm = self.error_storm.clone();
+                let reachability = self.reachability.clone();
+                let checked_builds = self.checked_builds.clone();
+                let scanned_builds = self.scanned_builds.clone();
+                let min_size = self.min_size;
+                let budget = self.budget.clone();
+                let failed_checks = self.failed_checks.clone();
+                let require_content_type = self.require_content_type;
+                let unexpected_types = self.unexpected_types.clone();
+                let dmg_url = platform
+                    .generate_dmg_path(&version, number)
+                    .filter(|_| self.try_dmg)
+                    .map(|path| format!("{}{}", self.base_url, path));
+                let pending = PendingBuild::new(scan.clone(), number);
+                let scan = scan.clone();
+
+                let task = tokio::spawn(async move {
+                    let _pending = pending;
+
+                    let Ok(mut permit) = semaphore.clone().acquire_owned().await else {
+                        return;
+                    };
+
+                    while pause_flag.load(Ordering::Relaxed) {
+                        if cancel_local.load(Ordering::Relaxed) {
+                            return;
+                        }
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                    }
+
+                    if cancel_local.load(Ordering::Relaxed) {
+                        return;
+                    }
+
+                    if let Some(gap) = max_gap {
+                        if scan.past_gap(number, gap) {
+                            // A lower build still in flight may yet move the
+                            // hit up; wait for them without holding a permit.
+                            drop(permit);
+                            while scan.pending_below(number) {
+                                if cancel_local.load(Ordering::Relaxed) {
+                                    return;
+                                }
+                                tokio::time::sleep(Duration::from_millis(10)).await;
+                            }
+                            if scan.past_gap(number, gap) {
+                                // Skipped on purpose, which a resume should too.
+                                if let Some(next) = scan.mark_checked(number) {
+                                    if let Some(tracker) = &checked_builds {
+                                        tracker.set(&version, platform, next);
+                                    }
+                                }
+                                if !scan.stopped.swap(true, Ordering::Relaxed) {
+                                    debug!(
+                                        "Stopping {:?} of {} at build {}: {} misses since build {}",
+                                        platform,
+                                        version,
+                                        number,
+                                        gap,
+                                        scan.highest_hit.load(Ordering::Relaxed)
+                                    );
+                                }
+                                return;
+                            }
+                            permit = match semaphore.acquire_owned().await {
+                                Ok(p) => p,
+                                Err(_) => return,
+                            };
+                        }
+                    }
+                    let _permit = permit;
+                    if !budget.take() {
+                        cancel_local.store(true, Ordering::Relaxed);
+                        return;
+                    }
+
+                    let (status, probed_url) = match (
+                        probe_url_with_backoff(
+                            &client,
+                            &url,
+                            &backoff,
+                            &rate_limiter,
+                            &jitter,
+                            &request_counts,
+                        )
+                        .await,
+                        &dmg_url,
+                    ) {
+                        (UrlStatus::Missing(_), Some(dmg_url)) if budget.take() => {
+                            let status = probe_url_with_backoff(
+                                &client,
+                                dmg_url,
+                                &backoff,
+                                &rate_limiter,
+                                &jitter,
+                                &request_counts,
+                            )
+                            .await;
+                            (status, dmg_url.as_str())
+                        }
+                        (status, _) => (status, url.as_str()),
+                    };
+                    let failed = |failed_checks: &Option<FailedChecks>| {
+                        if let Some(failed_checks) = failed_checks {
+                            failed_checks.add(FailedCheck {
+                                version: version.to_string(),
+                                platform,
+                                build: number,
+                                url: probed_url.to_string(),
+                            });
+                        }
+                    };
+                    processed.fetch_add(1, Ordering::Relaxed);
+                    platform_processed.add(platform);
+                    scanned_builds.add(&version, platform, number);
+                    if error_storm.record(matches!(status, UrlStatus::NetworkError(_))) {
+                        warn!("Most recent requests got no response, pausing the search");
+                        pause_flag.store(true, Ordering::Relaxed);
+                    }
+                    if reachability.record(
+                        !matches!(status, UrlStatus::NetworkError(_)),
+                        matches!(status, UrlStatus::NetworkError(true)),
+                    ) {
+                        warn!("Cannot reach the server, stopping the search");
+                        cancel_local.store(true, Ordering::Relaxed);
+                    }
+
+                    let wrong_type = match &status {
+                        UrlStatus::Found(probe) => match probe.unexpected_type() {
+                            Some(content_type) => {
+                                debug!("{} is served as {}", probe.url, content_type);
+                                unexpected_types.add(content_type);
+                                true
+                            }
+                            None => false,
+                        },
+                        _ => false,
+                    };
+
+                    match status {
+                        UrlStatus::Found(Probe { status, url, .. })
+                            if wrong_type && require_content_type =>
+                        {
+                            debug!("Skipping {}: not an installer", url);
+                            if let Some(counts) = &status_counts {
+                                counts.add(status);
+                            }
+                        }
+                        UrlStatus::Found(Probe {
+                            status,
+                            content_length: Some(size),
+                            url,
+                            ..
+                        }) if min_size.is_some_and(|min_size| size < min_size) => {
+                            debug!("Skipping {}: only {} bytes", url, size);
+                            if let Some(counts) = &status_counts {
+                                counts.add(status);
+                            }
+                        }
+                        UrlStatus::Found(Probe {
+                            content_length: size,
+                            last_modified,
+                            url,
+                            content_type,
+                            ..
+                        }) => {
+                            scan.highest_hit.fetch_max(number, Ordering::Relaxed);
+                            if let Some(cb) = &on_found {
+                                cb(&FoundEvent {
+                                    version: version.clone(),
+                                    build: number,
+                                    url: url.clone(),
+                                    platform,
+                                    size,
+                                    last_modified: last_modified.clone(),
+                                });
+                            }
+                            found_urls.lock().unwrap().push(FoundInstaller {
+                                url,
+                                platform,
+                                size,
+                                last_modified,
+                                content_type,
+                            });
+                        }
+                        UrlStatus::Missing(status) => {
+                            if let Some(counts) = &status_counts {
+                                counts.add(status.as_u16());
+                            }
+                            if status == StatusCode::TOO_MANY_REQUESTS {
+                                failed(&failed_checks);
+                            }
+                        }
+                        UrlStatus::NetworkError(_) => {
+                            network_errors.fetch_add(1, Ordering::Relaxed);
+                            if let Some(counts) = &status_counts {
+                                counts.add(0);
+                            }
+                            failed(&failed_checks);
+                        }
+                        UrlStatus::RateLimited(..) => {}
+                    }
+                    if let Some(next) = scan.mark_checked(number) {
+                        if let Some(tracker) = &checked_builds {
+                            tracker.set(&version, platform, next);
+                        }
+                    }
+                });
+
+                tasks.push(task);
+            }
+        }
+
+        let watcher = self.abort_on_cancel(&tasks);
+        for task in tasks {
+            let _ = task.await;
+        }
+        watcher.abort();
+
+        let mut found = found_urls.lock().unwrap().clone();
+        dedup_found(&mut found);
+        found
+    }
+
+    /// Aborts `tasks`, queued and in flight, once the search is cancelled
+    /// instead of waiting for them to run into their timeouts. Abort the
+    /// returned watcher once the tasks are done.
+    fn abort_on_cancel<T>(&self, tasks: &[JoinHandle<T>]) -> JoinHandle<()> {
+        let handles: Vec<_> = tasks.iter().map(|task| task.abort_handle()).collect();
+        let cancel = self.cancel_flag.clone();
+        tokio::spawn(async move {
+            while !cancel.load(Ordering::Relaxed) {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+            for handle in handles {
+                handle.abort();
+            }
+        })
+    }
+
+    /// Checks arbitrary `urls`, e.g. candidates from a changelog, with the
+    /// same connection limit, budget, backoff, rate limit, pause and cancel
+    /// as a search but no URL generation. Returns each URL that was checked,
+    /// in order, with its outcome; misses keep their status for diagnostics,
+    /// as do hits `require_content_type` turned down. `on_found` isn't
+    /// called, as the URLs have no platform or build.
+    pub async fn check_urls(&self, urls: &[String]) -> Vec<(String, Probe)> {
+        let mut tasks = Vec::new();
+        for url in urls {
+            if self.cancel_flag.load(Ordering::Relaxed) {
+                break;
+            }
+
+            let url = url.clone();
+            let client = self.client.clone();
+            let semaphore = self.semaphore.clone();
+            let pause_flag = self.pause_flag.clone();
+            let cancel_local = self.cancel_flag.clone();
+            let processed = self.processed.clone();
+            let network_errors = self.network_errors.clone();
+            let backoff = self.backoff.clone();
+            let rate_limiter = self.rate_limiter.clone();
+            let jitter = self.jitter.clone();
+            let request_counts = self.request_counts.clone();
+            let status_counts = self.status_counts.clone();
+            let error_storm = self.error_storm.clone();
+            let budget = self.budget.clone();
+            let require_content_type = self.require_content_type;
+            let unexpected_types = self.unexpected_types.clone();
+
+            tasks.push(tokio::spawn(async move {
+                let Ok(_permit) = semaphore.acquire_owned().await else {
+                    return None;
+                };
+                while pause_flag.load(Ordering::Relaxed) {
+                    if cancel_local.load(Ordering::Relaxed) {
+                        return None;
+                    }
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                }
+                if cancel_local.load(Ordering::Relaxed) {
+                    return None;
+                }
+                if !budget.take() {
+                    cancel_local.store(true, Ordering::Relaxed);
+                    return None;
+                }
+
+                let status = probe_url_with_backoff(
+                    &client,
+                    &url,
+                    &backoff,
+                    &rate_limiter,
+                    &jitter,
+                    &request_counts,
+                )
+                .await;
+                processed.fetch_add(1, Ordering::Relaxed);
+                if error_storm.record(matches!(status, UrlStatus::NetworkError(_))) {
+                    warn!("Most recent requests got no response, pausing the check");
+                    pause_flag.store(true, Ordering::Relaxed);
+                }
+
+                let missing = |status: u16| missing_probe(status, &url);
+                let probe = match status {
+                    UrlStatus::Found(mut probe) => {
+                        let rejected = match probe.unexpected_type() {
+                            Some(content_type) => {
+                                debug!("{} is served as {}", probe.url, content_type);
+                                unexpected_types.add(content_type);
+                                require_content_type
+                            }
+                            None => false,
+                        };
+                        if let Some(counts) = status_counts.as_ref().filter(|_| rejected) {
+                            counts.add(probe.status);
+                        }
+                        probe.exists = !rejected;
+                        probe
+                    }
+                    UrlStatus::Missing(status) => {
+                        if let Some(counts) = &status_counts {
+                            counts.add(status.as_u16());
+                        }
+                        missing(status.as_u16())
+                    }
+                    UrlStatus::NetworkError(_) => {
+                        network_errors.fetch_add(1, Ordering::Relaxed);
+                        if let Some(counts) = &status_counts {
+                            counts.add(0);
+                        }
+                        missing(0)
+                    }
+                    UrlStatus::RateLimited(..) => missing(StatusCode::TOO_MANY_REQUESTS.as_u16()),
+                };
+                Some((url, probe))
+            }));
+        }
+
+        let watcher = self.abort_on_cancel(&tasks);
+        let mut checked = Vec::new();
+        for task in tasks {
+            if let Ok(Some(result)) = task.await {
+                checked.push(result);
+            }
+        }
+        watcher.abort();
+        checked
+    }
+
+    /// Checks each of `hits` a second time, after `delay` and with a one-byte
+    /// GET rather than the HEAD that found it, under the same connection and
+    /// rate limits as a search. Returns the hits that were confirmed and,
+    /// apart, the ones that weren't with the status their GET got, `None`
+    /// when it got no response. Both keep the order of `hits`.
+    pub async fn confirm_hits(&self, hits: Vec<FoundInstaller>, delay: Duration) -> ConfirmedHits {
+        if hits.is_empty() {
+            return (Vec::new(), Vec::new());
+        }
+        tokio::time::sleep(delay).await;
+
+        let mut tasks = Vec::new();
+        for hit in hits {
+            let client = self.client.clone();
+            let semaphore = self.semaphore.clone();
+            let backoff = self.backoff.clone();
+            let rate_limiter = self.rate_limiter.clone();
+            let jitter = self.jitter.clone();
+            let request_counts = self.request_counts.clone();
+
+            tasks.push(tokio::spawn(async move {
+                let _permit = semaphore.acquire_owned().await.ok();
+                jitter.wait().await;
+                backoff.wait().await;
+                rate_limiter.wait().await;
+                request_counts.add_request();
+                let outcome = exists_via_ranged_get(&client, &hit.url).await.map(|_| ());
+                (hit, outcome)
+            }));
+        }
+
+        let (mut confirmed, mut unconfirmed) = (Vec::new(), Vec::new());
+        for task in tasks {
+            match task.await {
+                Ok((hit, Ok(()))) => confirmed.push(hit),
+                Ok((hit, Err(status))) => {
+                    debug!("{} not confirmed: {:?}", hit.url, status);
+                    unconfirmed.push((hit, status.map(|status| status.as_u16())));
+                }
+                Err(e) => warn!("Confirming a hit failed: {}", e),
+            }
+        }
+        (confirmed, unconfirmed)
+    }
+
+    /// Checks `failed` again, e.g. the list a [`Checkpoint`](crate::Checkpoint)
+    /// kept of a search on a flaky network, with [`SearchEngine::check_urls`].
+    /// Hits are passed to `on_found` as a search's are.
+    pub async fn rescan_failures(&self, failed: &[FailedCheck]) -> Rescan {
+        let urls: Vec<String> = failed.iter().map(|check| check.url.clone()).collect();
+        let checked: HashMap<String, Probe> = self.check_urls(&urls).await.into_iter().collect();
+
+        let mut rescan = Rescan::default();
+        for check in failed {
+            match checked.get(&check.url) {
+                Some(probe) if probe.exists => {
+                    rescan.recovered += 1;
+                    if let Some(cb) = &self.on_found {
+                        cb(&FoundEvent {
+                            version: Arc::from(check.version.as_str()),
+                            build: check.build,
+                            url: probe.url.clone(),
+                            platform: check.platform,
+                            size: probe.content_length,
+                            last_modified: probe.last_modified.clone(),
+                        });
+                    }
+                    rescan.found.push(CachedInstaller {
+                        version: check.version.clone(),
+                        platform: check.platform,
+                        build: check.build,
+                        url: probe.url.clone(),
+                        size: probe.content_length,
+                        last_modified: probe.last_modified.clone(),
+                    });
+                }
+                Some(probe) if probe.status != 0 && probe.status != 429 => rescan.recovered += 1,
+                _ => rescan.still_failing.push(check.clone()),
+            }
+        }
+        rescan
+    }
+
+    /// `search` over each of `ranges` in turn, e.g. disjoint bands of build
+    /// numbers. Pass them through [`merge_build_ranges`] first so no build is
+    /// checked twice.
+    ///
+    /// [`merge_build_ranges`]: crate::merge_build_ranges
+    pub async fn search_ranges(
+        &self,
+        version: &str,
+        ranges: &[(i32, i32)],
+        platforms: &[Platform],
+    ) -> Vec<FoundInstaller> {
+        let mut found = Vec::new();
+        for &(start, end) in ranges {
+            if self.cancel_flag.load(Ordering::Relaxed) {
+                break;
+            }
+            found.extend(self.search(version, start, end, platforms).await);
+        }
+        found
+    }
+
+    /// Staircase search for the newest builds: scans `0..=initial_window`,
+    /// then keeps scanning the next `increment` builds for the platforms that
+    /// have no hit yet, at most `max_iterations` times. Much faster than a
+    /// fixed range when only the latest build is wanted.
+    pub async fn ladder_search(
+        &self,
+        version: &str,
+        platforms: &[Platform],
+        options: LadderOptions,
+    ) -> Vec<FoundInstaller> {
+        let mut found = Vec::new();
+        let mut missing = platforms.to_vec();
+        let mut start = 0;
+        let mut end = options.initial_window;
+
+        for iteration in 0..=options.max_iterations {
+            if iteration > 0 {
+                start = end + 1;
+                end += options.increment;
+            }
+
+            found.extend(self.search(version, start, end, &missing).await);
+            missing.retain(|platform| !found.iter().any(|hit| hit.platform == *platform));
+
+            if missing.is_empty() || self.cancel_flag.load(Ordering::Relaxed) {
+                break;
+            }
+        }
+
+        found
+    }
+
+    /// Finds the builds of `start..=end` without scanning all of it: probes
+    /// builds `start`, +1, +2, +4, ... and then every `max_gap` builds (or
+    /// [`AUTO_RANGE_GAP`]) until a platform gets a hit, then scans the builds
+    /// back to the previous probe and upwards a gap at a time until a whole
+    /// gap misses. Assumes a platform's builds form one run at least a gap
+    /// long; a shorter run between two probes is only found by chance. With a
+    /// `step` of 1 it never checks more builds than a `search` of the range.
+    pub async fn auto_range_search(
+        &self,
+        version: &str,
+        start: i32,
+        end: i32,
+        platforms: &[Platform],
+    ) -> Vec<FoundInstaller> {
+        let gap = self.max_gap.unwrap_or(AUTO_RANGE_GAP).max(1) as i32;
+        let mut found = Vec::new();
+        let mut missing = platforms.to_vec();
+        // Each platform's first hit and the probe before it.
+        let mut first_hits = Vec::new();
+        let mut previous = start - 1;
+        let mut offset = 0i32;
+        while let Some(probe) = start.checked_add(offset).filter(|&probe| probe <= end) {
+            if missing.is_empty() || self.cancel_flag.load(Ordering::Relaxed) {
+                break;
+            }
+            let hits = self.search(version, probe, probe, &missing).await;
+            for FoundInstaller { platform, .. } in &hits {
+                if !first_hits.iter().any(|(p, _, _)| p == platform) {
+                    first_hits.push((*platform, previous, probe));
+                }
+            }
+            missing.retain(|platform| !hits.iter().any(|hit| hit.platform == *platform));
+            found.extend(hits);
+            previous = probe;
+            offset = if offset < gap {
+                (offset * 2).clamp(1, gap)
+            } else {
+                offset.saturating_add(gap)
+            };
+        }
+
+        for (platform, previous, hit) in first_hits {
+            debug!(
+                "First {:?} build of {} at {}, scanning around it",
+                platform, version, hit
+            );
+            if previous + 1 < hit {
+                found.extend(
+                    self.search(version, previous + 1, hit - 1, &[platform])
+                        .await,
+                );
+            }
+            let mut from = hit.checked_add(1);
+            while let Some(low) = from.filter(|&low| low <= end) {
+                if self.cancel_flag.load(Ordering::Relaxed) {
+                    break;
+                }
+                let high = low.saturating_add(gap - 1).min(end);
+                let hits = self.search(version, low, high, &[platform]).await;
+                if hits.is_empty() {
+                    break;
+                }
+                found.extend(hits);
+                from = high.checked_add(1);
+            }
+        }
+        found
+    }
+
+    /// Like `search`, but scans each platform's [`KnownBuilds::window`] first
+    /// and only the rest of `start..=end` for platforms with no hit there.
+    pub async fn hinted_search(
+        &self,
+        version: &str,
+        start: i32,
+        end: i32,
+        platforms: &[Platform],
+        hints: &KnownBuilds,
+    ) -> Vec<FoundInstaller> {
+        let mut found = Vec::new();
+        // Each part stays on the builds a single search from `start` would
+        // check, so a step doesn't make the parts check more than the total.
+        let step = self.step.max(1) as i32;
+        let on_step = |build: i32| start + (build - start + step - 1) / step * step;
+        for &platform in platforms {
+            let Some((low, high)) = hints
+                .window(version, platform)
+                .map(|(low, high)| (on_step(low.max(start)), high.min(end)))
+                .filter(|(low, high)| low <= high)
+            else {
+                found.extend(self.search(version, start, end, &[platform]).await);
+                continue;
+            };
+
+            debug!(
+                "Checking builds {}-{} of {} first for {:?}",
+                low, high, version, platform
+            );
+            let hits = self.search(version, low, high, &[platform]).await;
+            if hits.is_empty() && !self.cancel_flag.load(Ordering::Relaxed) {
+                if start < low {
+                    found.extend(self.search(version, start, low - 1, &[platform]).await);
+                }
+                if on_step(high + 1) <= end {
+                    found.extend(
+                        self.search(version, on_step(high + 1), end, &[platform])
+                            .await,
+                    );
+                }
+            }
+            found.extend(hits);
+        }
+        found
+    }
+}
+
+/// Drops repeated URLs from `found`, keeping the first of each. Builds that
+/// redirect to the same file, or a version searched twice, would otherwise
+/// list it more than once.
+pub fn dedup_found(found: &mut Vec<FoundInstaller>) {
+    let mut seen = HashSet::new();
+    found.retain(|hit| seen.insert(hit.url.clone()));
+}
+
+/// Window layout for [`SearchEngine::ladder_search`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LadderOptions {
+    pub initial_window: i32,
+    pub increment: i32,
+    pub max_iterations: u32,
+}
+
+impl Default for LadderOptions {
+    fn default() -> Self {
+        Self {
+            initial_window: 1000,
+            increment: 1000,
+            max_iterations: 15,
+        }
+    }
+}
+
+impl LadderOptions {
+    /// Most build numbers a ladder search can check per platform.
+    pub fn max_builds(&self) -> u64 {
+        (self.initial_window as u64 + 1) + self.increment as u64 * self.max_iterations as u64
+    }
+
+    /// Highest build number a ladder search can reach.
+    pub fn max_build(&self) -> u64 {
+        self.max_builds() - 1
+    }
+}
@@ -0,0 +1,163 @@
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+
+/// Known hash and size of an installer.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ManifestEntry {
+    pub sha256: String,
+    pub size: Option<u64>,
+}
+
+/// Known installers keyed by file name, e.g.
+/// `{"spotify_installer-1.2.60.564.gcc6305cb-1234.exe": {"sha256": "...", "size": 94371840}}`.
+/// The file name carries the platform and build, so it identifies a build.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct Manifest(pub HashMap<String, ManifestEntry>);
+
+/// Outcome of checking one downloaded file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verification {
+    Pass {
+        sha256: String,
+    },
+    Fail {
+        sha256: String,
+        reason: String,
+    },
+    /// Not in the manifest; the computed hash can be added to it.
+    Unlisted {
+        sha256: String,
+    },
+}
+
+impl Manifest {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read manifest {}: {}", path.display(), e))?;
+        serde_json::from_str(&text)
+            .map_err(|e| format!("Invalid manifest {}: {}", path.display(), e))
+    }
+
+    /// Checks `path` against its manifest entry. `expected_size` is the
+    /// `Content-Length` seen during the search and applies to unlisted files too.
+    pub fn verify(&self, path: &Path, expected_size: Option<u64>) -> Result<Verification, String> {
+        let name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| format!("No file name in {}", path.display()))?;
+        let size = fs::metadata(path)
+            .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?
+            .len();
+        let sha256 = sha256_file(path)?;
+
+        let entry = self.0.get(name);
+        if let Some(expected) = entry.and_then(|e| e.size).or(expected_size) {
+            if expected != size {
+                return Ok(Verification::Fail {
+                    sha256,
+                    reason: format!("size {} bytes, expected {}", size, expected),
+                });
+            }
+        }
+
+        Ok(match entry {
+            None => Verification::Unlisted { sha256 },
+            Some(entry) if entry.sha256.eq_ignore_ascii_case(&sha256) => {
+                Verification::Pass { sha256 }
+            }
+            Some(entry) => Verification::Fail {
+                reason: format!("sha256 {}, expected {}", sha256, entry.sha256),
+                sha256,
+            },
+        })
+    }
+}
+
+/// Lowercase hex SHA-256 of a file.
+pub fn sha256_file(path: &Path) -> Result<String, String> {
+    let mut file =
+        File::open(path).map_err(|e| format!("Cannot open {}: {}", path.display(), e))?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let n = file
+            .read(&mut buf)
+            .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ABC_SHA256: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+    fn abc_file(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("loaderspot-verify-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        fs::write(&path, b"abc").unwrap();
+        path
+    }
+
+    fn manifest(name: &str, sha256: &str, size: Option<u64>) -> Manifest {
+        Manifest(HashMap::from([(
+            name.to_string(),
+            ManifestEntry {
+                sha256: sha256.to_string(),
+                size,
+            },
+        )]))
+    }
+
+    #[test]
+    fn hashes_file() {
+        assert_eq!(sha256_file(&abc_file("hash.exe")).unwrap(), ABC_SHA256);
+    }
+
+    #[test]
+    fn passes_matching_entry() {
+        let path = abc_file("pass.exe");
+        let result = manifest("pass.exe", ABC_SHA256, Some(3)).verify(&path, None);
+        assert_eq!(
+            result.unwrap(),
+            Verification::Pass {
+                sha256: ABC_SHA256.to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn fails_on_hash_or_size_mismatch() {
+        let path = abc_file("fail.exe");
+        let wrong_hash = manifest("fail.exe", &"0".repeat(64), None).verify(&path, None);
+        assert!(matches!(wrong_hash.unwrap(), Verification::Fail { .. }));
+
+        let wrong_size = manifest("fail.exe", ABC_SHA256, Some(4)).verify(&path, None);
+        assert!(matches!(wrong_size.unwrap(), Verification::Fail { .. }));
+    }
+
+    #[test]
+    fn reports_hash_of_unlisted_file() {
+        let path = abc_file("new.exe");
+        assert_eq!(
+            Manifest::default().verify(&path, Some(3)).unwrap(),
+            Verification::Unlisted {
+                sha256: ABC_SHA256.to_string()
+            }
+        );
+        assert!(matches!(
+            Manifest::default().verify(&path, Some(10)).unwrap(),
+            Verification::Fail { .. }
+        ));
+    }
+}
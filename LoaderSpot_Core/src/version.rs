@@ -0,0 +1,234 @@
+use regex::Regex;
+use std::sync::OnceLock;
+
+pub fn extract_base_version(version: &str) -> String {
+    let parts: Vec<&str> = version.split('.').collect();
+    if parts.len() >= 3 {
+        format!("{}.{}.{}", parts[0], parts[1], parts[2])
+    } else {
+        version.to_string()
+    }
+}
+
+/// `1.2.60.564.gcc6305cb` -> `1.2.60.564`, for labels where the hash is noise.
+pub fn short_version(version: &str) -> String {
+    if let Some(pos) = version.find(".g") {
+        return version[..pos].to_string();
+    }
+
+    let parts: Vec<&str> = version.split('.').collect();
+    let take = parts.len().min(4);
+    parts[..take].join(".")
+}
+
+/// Windows x86 installers stopped being published after 1.2.53.
+pub fn should_use_win_x86(version: &str) -> bool {
+    let mut parts = version.split('.');
+
+    if let (Some(p1), Some(p2), Some(p3)) = (parts.next(), parts.next(), parts.next()) {
+        if let (Ok(major), Ok(minor), Ok(patch)) =
+            (p1.parse::<u32>(), p2.parse::<u32>(), p3.parse::<u32>())
+        {
+            return (major, minor, patch) <= (1, 2, 53);
+        }
+    }
+    true
+}
+
+/// Spotify version, optionally followed by a 7-40 character commit hash,
+/// e.g. `1.2.60.564.gcc6305cb` or `1.2.60.564`.
+pub fn validate_version(version: &str) -> bool {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| Regex::new(r"^\d+\.\d+\.\d+\.\d+(\.g[0-9a-f]{7,40})?$").unwrap());
+    re.is_match(version)
+}
+
+/// Only the exact form Spotify has published so far: an 8 character hash.
+pub fn validate_version_strict(version: &str) -> bool {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| Regex::new(r"^\d+\.\d+\.\d+\.\d+\.g[0-9a-f]{8}$").unwrap());
+    re.is_match(version)
+}
+
+/// Non-empty lines of a version list, trimmed. Lines starting with `#` are
+/// comments. The lines are not validated.
+pub fn version_list_lines(text: &str) -> impl Iterator<Item = &str> {
+    numbered_version_lines(text).map(|(_, line)| line)
+}
+
+/// Like [`version_list_lines`], paired with each line's 1-based number in
+/// `text`, so a rejected version can be pointed out.
+pub fn numbered_version_lines(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.lines()
+        .map(str::trim)
+        .enumerate()
+        .map(|(i, line)| (i + 1, line))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+}
+
+/// Everything in `text` that looks like a version, in order, e.g. from a
+/// pasted changelog. The matches still need [`validate_version`].
+pub fn find_versions(text: &str) -> impl Iterator<Item = &str> {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| Regex::new(r"\d+\.\d+\.\d+\.\d+(\.g[0-9a-f]+)?").unwrap());
+    re.find_iter(text).map(|m| m.as_str())
+}
+
+/// Most versions one [`expand_version_range`] may yield.
+pub const MAX_VERSION_RANGE: u32 = 1000;
+
+/// Every version from `1.2.60.500..1.2.60.600`, both ends included. Only the
+/// fourth component is enumerated: it can't be derived from
+/// `major.minor.patch`, and the `.gHASH` suffix can't be enumerated at all,
+/// so both ends share `major.minor.patch` and the versions have no hash.
+pub fn expand_version_range(range: &str) -> Result<Vec<String>, String> {
+    let invalid = |reason: &str| format!("Invalid version range '{}': {}", range, reason);
+    let (from, to) = range
+        .split_once("..")
+        .ok_or_else(|| invalid("expected FROM..TO, e.g. 1.2.60.500..1.2.60.600"))?;
+
+    let parse = |version: &str| -> Result<[u32; 4], String> {
+        let parts: Vec<&str> = version.trim().split('.').collect();
+        if parts.len() == 3 {
+            return Err(invalid(
+                "give four components, e.g. 1.2.60.500..1.2.60.600; a release's fourth \
+                 component can't be derived from major.minor.patch",
+            ));
+        }
+        let numbers: Vec<u32> = parts.iter().filter_map(|part| part.parse().ok()).collect();
+        match numbers[..] {
+            [major, minor, patch, number] if parts.len() == 4 => Ok([major, minor, patch, number]),
+            _ => Err(invalid(
+                "expected versions like 1.2.60.500, without a .gHASH suffix",
+            )),
+        }
+    };
+    let (from, to) = (parse(from)?, parse(to)?);
+
+    if from[..3] != to[..3] {
+        return Err(invalid("both ends must share major.minor.patch"));
+    }
+    if from[3] > to[3] {
+        return Err(invalid("it starts after it ends"));
+    }
+    let count = to[3] - from[3] + 1;
+    if count > MAX_VERSION_RANGE {
+        return Err(invalid(&format!(
+            "{} versions, more than {}",
+            count, MAX_VERSION_RANGE
+        )));
+    }
+
+    Ok((from[3]..=to[3])
+        .map(|number| format!("{}.{}.{}.{}", from[0], from[1], from[2], number))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn win_x86_gate() {
+        assert!(should_use_win_x86("1.2.53.440.g7b2f582a"));
+        assert!(!should_use_win_x86("1.2.54.304.ge1b8d5a1"));
+    }
+
+    #[test]
+    fn version_helpers_table() {
+        // version, base version, short version, has a Windows x86 installer
+        let cases = [
+            ("1.2.53.440.g7b2f582a", "1.2.53", "1.2.53.440", true),
+            ("1.2.53.0", "1.2.53", "1.2.53.0", true),
+            ("1.2.54.304.ge1b8d5a1", "1.2.54", "1.2.54.304", false),
+            ("1.2.60.564.gcc6305cb", "1.2.60", "1.2.60.564", false),
+            ("1.2.52.999", "1.2.52", "1.2.52.999", true),
+            ("1.1.99.1.gabcdef01", "1.1.99", "1.1.99.1", true),
+            ("1.3.0.1", "1.3.0", "1.3.0.1", false),
+            ("2.0.0.1", "2.0.0", "2.0.0.1", false),
+            ("0.9.99.1", "0.9.99", "0.9.99.1", true),
+            ("1.2.53", "1.2.53", "1.2.53", true),
+            ("1.2", "1.2", "1.2", true),
+            ("1", "1", "1", true),
+            ("1.2.x.4", "1.2.x", "1.2.x.4", true),
+        ];
+        for (version, base, short, win_x86) in cases {
+            assert_eq!(extract_base_version(version), base, "{}", version);
+            assert_eq!(short_version(version), short, "{}", version);
+            assert_eq!(should_use_win_x86(version), win_x86, "{}", version);
+        }
+    }
+
+    #[test]
+    fn accepts_hashes_of_varying_length() {
+        assert!(validate_version("1.2.60.564.gcc6305c"));
+        assert!(validate_version("1.2.60.564.gcc6305cb"));
+        assert!(validate_version(&format!("1.2.60.564.g{}", "a".repeat(40))));
+        assert!(!validate_version("1.2.60.564.gcc6305"));
+        assert!(!validate_version(&format!(
+            "1.2.60.564.g{}",
+            "a".repeat(41)
+        )));
+        assert!(!validate_version("1.2.60.564.gCC6305CB"));
+    }
+
+    #[test]
+    fn accepts_versions_without_hash() {
+        assert!(validate_version("1.2.60.564"));
+        assert!(!validate_version("1.2.60"));
+        assert!(!validate_version_strict("1.2.60.564"));
+    }
+
+    #[test]
+    fn strict_validation_needs_eight_character_hash() {
+        assert!(validate_version_strict("1.2.60.564.gcc6305cb"));
+        assert!(!validate_version_strict("1.2.60.564.gcc6305c"));
+        assert!(!validate_version_strict("1.2.60.564.gcc6305cb12"));
+    }
+
+    #[test]
+    fn finds_versions_in_prose() {
+        let text = "Spotify 1.2.60.564.gcc6305cb for Windows\n  (was 1.2.53.440)\t";
+        assert_eq!(
+            find_versions(text).collect::<Vec<_>>(),
+            ["1.2.60.564.gcc6305cb", "1.2.53.440"]
+        );
+        assert_eq!(find_versions("version 1.2.60").count(), 0);
+    }
+
+    #[test]
+    fn expands_the_fourth_component() {
+        assert_eq!(
+            expand_version_range("1.2.60.562..1.2.60.564").unwrap(),
+            ["1.2.60.562", "1.2.60.563", "1.2.60.564"]
+        );
+        assert_eq!(
+            expand_version_range("1.2.60.564..1.2.60.564").unwrap(),
+            ["1.2.60.564"]
+        );
+        assert!(expand_version_range("1.2.60.564..1.2.60.562").is_err());
+        assert!(expand_version_range("1.2.60.0..1.2.60.5000").is_err());
+    }
+
+    #[test]
+    fn rejects_ranges_that_cannot_be_enumerated() {
+        let err = expand_version_range("1.2.40..1.2.50").unwrap_err();
+        assert!(err.contains("four components"), "{}", err);
+        assert!(expand_version_range("1.2.40.1..1.2.50.1").is_err());
+        assert!(expand_version_range("1.2.60.564.gcc6305cb..1.2.60.570").is_err());
+        assert!(expand_version_range("1.2.60.564").is_err());
+    }
+
+    #[test]
+    fn skips_blank_lines_and_comments() {
+        let text = "# nightly list\n 1.2.60.564.gcc6305cb \n\n  # old\n1.2.53.440.g7b2f582a\n";
+        assert_eq!(
+            version_list_lines(text).collect::<Vec<_>>(),
+            ["1.2.60.564.gcc6305cb", "1.2.53.440.g7b2f582a"]
+        );
+        assert_eq!(
+            numbered_version_lines(text).collect::<Vec<_>>(),
+            [(2, "1.2.60.564.gcc6305cb"), (5, "1.2.53.440.g7b2f582a")]
+        );
+    }
+}
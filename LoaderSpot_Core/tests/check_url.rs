@@ -0,0 +1,273 @@
+use loaderspot_core::{check_url, probe_at, ClientOptions, Probe};
+use reqwest::Client;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn falls_back_to_ranged_get_when_head_is_rejected() {
+    let server = MockServer::start().await;
+    Mock::given(method("HEAD"))
+        .and(path("/installer.tbz"))
+        .respond_with(ResponseTemplate::new(405))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/installer.tbz"))
+        .and(header("range", "bytes=0-0"))
+        .and(header("accept-encoding", "identity"))
+        .respond_with(
+            ResponseTemplate::new(206)
+                .insert_header("content-range", "bytes 0-0/104857600")
+                .set_body_bytes(vec![0u8]),
+        )
+        .mount(&server)
+        .await;
+
+    let url = format!("{}/installer.tbz", server.uri());
+    let found = check_url(&Client::new(), &url).await.unwrap();
+
+    assert_eq!(found.status, 206);
+    assert_eq!(found.content_length, Some(104857600));
+    assert_eq!(found.url, url);
+}
+
+#[tokio::test]
+async fn reads_everything_from_a_single_head() {
+    let server = MockServer::start().await;
+    Mock::given(method("HEAD"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-length", "94371840")
+                .insert_header("content-type", "application/octet-stream")
+                .insert_header("last-modified", "Fri, 01 Mar 2024 12:34:56 GMT"),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(206))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let url = format!("{}/installer.exe", server.uri());
+    let found = check_url(&Client::new(), &url).await.unwrap();
+
+    assert_eq!(
+        found,
+        Probe {
+            exists: true,
+            status: 200,
+            content_length: Some(94371840),
+            last_modified: Some("2024-03-01T12:34:56Z".to_string()),
+            url: url.clone(),
+            content_type: Some("application/octet-stream".to_string()),
+        }
+    );
+    assert_eq!(
+        serde_json::to_value(&found).unwrap()["content_type"],
+        "application/octet-stream"
+    );
+}
+
+#[tokio::test]
+async fn missing_file_stays_missing_after_fallback() {
+    let server = MockServer::start().await;
+    Mock::given(method("HEAD"))
+        .respond_with(ResponseTemplate::new(405))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+
+    let url = format!("{}/installer.tbz", server.uri());
+    assert_eq!(check_url(&Client::new(), &url).await, None);
+}
+
+#[tokio::test]
+async fn reports_content_length_from_head() {
+    let server = MockServer::start().await;
+    Mock::given(method("HEAD"))
+        .respond_with(ResponseTemplate::new(200).insert_header("content-length", "94371840"))
+        .mount(&server)
+        .await;
+
+    let url = format!("{}/installer.exe", server.uri());
+    let found = check_url(&Client::new(), &url).await.unwrap();
+
+    assert_eq!(found.content_length, Some(94371840));
+    assert_eq!(found.url, url);
+}
+
+#[tokio::test]
+async fn reports_last_modified_as_iso_8601() {
+    let server = MockServer::start().await;
+    Mock::given(method("HEAD"))
+        .and(path("/dated.exe"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("last-modified", "Fri, 01 Mar 2024 12:34:56 GMT"),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("HEAD"))
+        .and(path("/garbled.exe"))
+        .respond_with(ResponseTemplate::new(200).insert_header("last-modified", "yesterday"))
+        .mount(&server)
+        .await;
+
+    let client = Client::new();
+    let url = format!("{}/dated.exe", server.uri());
+    let found = check_url(&client, &url).await.unwrap();
+    assert_eq!(found.content_length, None);
+    assert_eq!(found.last_modified.as_deref(), Some("2024-03-01T12:34:56Z"));
+
+    let url = format!("{}/garbled.exe", server.uri());
+    let found = probe_at(&client, &url).await;
+    assert!(found.exists);
+    assert_eq!(found.last_modified, None);
+}
+
+#[tokio::test]
+async fn sends_configured_user_agent_and_headers() {
+    let server = MockServer::start().await;
+    Mock::given(method("HEAD"))
+        .and(header("user-agent", "Spotify/1.2.60"))
+        .and(header("x-client", "loaderspot"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+    Mock::given(method("HEAD"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+    let client = ClientOptions {
+        user_agent: Some("Spotify/1.2.60".to_string()),
+        headers: vec!["X-Client: loaderspot".to_string()],
+        ..Default::default()
+    }
+    .build()
+    .unwrap();
+
+    let url = format!("{}/installer.exe", server.uri());
+    assert!(check_url(&client, &url).await.is_some());
+}
+
+#[tokio::test]
+async fn probe_reports_status_and_size() {
+    let server = MockServer::start().await;
+    Mock::given(method("HEAD"))
+        .and(path("/installer.exe"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-length", "2048")
+                .insert_header("content-type", "application/octet-stream"),
+        )
+        .mount(&server)
+        .await;
+
+    let client = Client::new();
+    let url = format!("{}/installer.exe", server.uri());
+    let found = probe_at(&client, &url).await;
+    assert_eq!(
+        found,
+        Probe {
+            exists: true,
+            status: 200,
+            content_length: Some(2048),
+            last_modified: None,
+            url,
+            content_type: Some("application/octet-stream".to_string()),
+        }
+    );
+
+    let missing = probe_at(&client, &format!("{}/other.exe", server.uri())).await;
+    assert!(!missing.exists);
+    assert_eq!(missing.status, 404);
+    assert_eq!(missing.content_length, None);
+}
+
+#[tokio::test]
+async fn probe_at_reports_rate_limits_as_misses_without_retrying() {
+    let server = MockServer::start().await;
+    Mock::given(method("HEAD"))
+        .and(path("/limited.exe"))
+        .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "30"))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("HEAD"))
+        .and(path("/busy.exe"))
+        .respond_with(ResponseTemplate::new(503).insert_header("retry-after", "30"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = Client::new();
+    let url = format!("{}/limited.exe", server.uri());
+    let limited = probe_at(&client, &url).await;
+    assert!(!limited.exists);
+    assert_eq!(limited.status, 429);
+    assert_eq!(limited.url, url);
+
+    let busy = probe_at(&client, &format!("{}/busy.exe", server.uri())).await;
+    assert!(!busy.exists);
+    assert_eq!(busy.status, 503);
+}
+
+#[tokio::test]
+async fn follows_redirects_and_reports_the_final_url() {
+    let server = MockServer::start().await;
+    let mirror = format!("{}/mirror/installer.exe", server.uri());
+    Mock::given(method("HEAD"))
+        .and(path("/installer.exe"))
+        .respond_with(ResponseTemplate::new(302).insert_header("location", mirror.as_str()))
+        .mount(&server)
+        .await;
+    Mock::given(method("HEAD"))
+        .and(path("/mirror/installer.exe"))
+        .respond_with(ResponseTemplate::new(200).insert_header("content-length", "4096"))
+        .mount(&server)
+        .await;
+
+    let url = format!("{}/installer.exe", server.uri());
+    let client = ClientOptions::default().build().unwrap();
+    let found = check_url(&client, &url).await.unwrap();
+    assert_eq!(found.url, mirror);
+    assert_eq!(found.content_length, Some(4096));
+
+    // Without following, the redirect itself is the hit.
+    let client = ClientOptions {
+        follow_redirects: false,
+        ..Default::default()
+    }
+    .build()
+    .unwrap();
+    let raw = probe_at(&client, &url).await;
+    assert!(raw.exists);
+    assert_eq!(raw.status, 302);
+    assert_eq!(raw.url, url);
+}
+
+#[tokio::test]
+async fn speaks_http2_with_prior_knowledge() {
+    let server = MockServer::start().await;
+    Mock::given(method("HEAD"))
+        .respond_with(ResponseTemplate::new(200).insert_header("content-length", "1024"))
+        .mount(&server)
+        .await;
+    let client = ClientOptions {
+        http2_prior_knowledge: true,
+        pool_max_idle_per_host: Some(4),
+        ..Default::default()
+    }
+    .build()
+    .unwrap();
+
+    let url = format!("{}/installer.exe", server.uri());
+    let response = client.head(&url).send().await.unwrap();
+    assert_eq!(response.version(), reqwest::Version::HTTP_2);
+    assert!(probe_at(&client, &url).await.exists);
+}
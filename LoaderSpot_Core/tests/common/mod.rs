@@ -0,0 +1,80 @@
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// Minimal keep-alive HTTP server that answers 200 for `found` paths and 404
+/// otherwise, holding each request for `delay` while tracking how many are
+/// in flight at once. wiremock can't observe concurrency, hence this.
+pub struct CountingServer {
+    pub base_url: String,
+    pub max_in_flight: Arc<AtomicUsize>,
+    pub requests: Arc<AtomicUsize>,
+}
+
+impl CountingServer {
+    pub async fn start(found: &[&str], delay: Duration) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let found: Arc<HashSet<String>> = Arc::new(found.iter().map(|p| p.to_string()).collect());
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+        let requests = Arc::new(AtomicUsize::new(0));
+
+        let server = Self {
+            base_url: format!("http://{}/", addr),
+            max_in_flight: max_in_flight.clone(),
+            requests: requests.clone(),
+        };
+
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut socket, _)) = listener.accept().await else {
+                    return;
+                };
+                let found = found.clone();
+                let in_flight = in_flight.clone();
+                let max_in_flight = max_in_flight.clone();
+                let requests = requests.clone();
+
+                tokio::spawn(async move {
+                    let mut buf = Vec::new();
+                    let mut chunk = [0u8; 1024];
+                    loop {
+                        let Some(end) = buf.windows(4).position(|w| w == b"\r\n\r\n") else {
+                            match socket.read(&mut chunk).await {
+                                Ok(0) | Err(_) => return,
+                                Ok(n) => buf.extend_from_slice(&chunk[..n]),
+                            }
+                            continue;
+                        };
+
+                        let head = String::from_utf8_lossy(&buf[..end]).to_string();
+                        buf.drain(..end + 4);
+                        let path = head.split_whitespace().nth(1).unwrap_or("/").to_string();
+
+                        requests.fetch_add(1, Ordering::SeqCst);
+                        let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                        max_in_flight.fetch_max(now, Ordering::SeqCst);
+                        tokio::time::sleep(delay).await;
+                        in_flight.fetch_sub(1, Ordering::SeqCst);
+
+                        let status = if found.contains(path.trim_start_matches('/')) {
+                            "200 OK"
+                        } else {
+                            "404 Not Found"
+                        };
+                        let response = format!("HTTP/1.1 {}\r\ncontent-length: 0\r\n\r\n", status);
+                        if socket.write_all(response.as_bytes()).await.is_err() {
+                            return;
+                        }
+                    }
+                });
+            }
+        });
+
+        server
+    }
+}
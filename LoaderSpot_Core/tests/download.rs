@@ -0,0 +1,89 @@
+use loaderspot_core::{download_installer, ClientOptions};
+use reqwest::Client;
+use std::path::PathBuf;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("loaderspot-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[tokio::test]
+async fn downloads_to_final_name() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/spotify_installer-1.exe"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(b"installer-bytes".to_vec()))
+        .mount(&server)
+        .await;
+
+    let dir = scratch_dir("full");
+    let url = format!("{}/spotify_installer-1.exe", server.uri());
+    let saved = download_installer(&Client::new(), &url, &dir, |_, _| {})
+        .await
+        .unwrap();
+
+    assert_eq!(saved, dir.join("spotify_installer-1.exe"));
+    assert_eq!(std::fs::read(&saved).unwrap(), b"installer-bytes");
+    assert!(!dir.join("spotify_installer-1.exe.part").exists());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn resumes_from_part_file() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/spotify_installer-2.exe"))
+        .and(header("range", "bytes=10-"))
+        .and(header("accept-encoding", "identity"))
+        .respond_with(
+            ResponseTemplate::new(206)
+                .insert_header("content-range", "bytes 10-14/15")
+                .set_body_bytes(b"bytes".to_vec()),
+        )
+        .mount(&server)
+        .await;
+
+    let dir = scratch_dir("resume");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("spotify_installer-2.exe.part"), b"installer-").unwrap();
+
+    let url = format!("{}/spotify_installer-2.exe", server.uri());
+    let saved = download_installer(&Client::new(), &url, &dir, |_, _| {})
+        .await
+        .unwrap();
+
+    assert_eq!(std::fs::read(&saved).unwrap(), b"installer-bytes");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn decodes_a_compressed_body() {
+    // gzip of "installer-bytes".
+    let gzipped = vec![
+        31, 139, 8, 0, 0, 0, 0, 0, 2, 3, 203, 204, 43, 46, 73, 204, 201, 73, 45, 210, 77, 170, 44,
+        73, 45, 6, 0, 83, 51, 8, 75, 15, 0, 0, 0,
+    ];
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/spotify_installer-3.exe"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-encoding", "gzip")
+                .set_body_bytes(gzipped),
+        )
+        .mount(&server)
+        .await;
+
+    let dir = scratch_dir("gzip");
+    let url = format!("{}/spotify_installer-3.exe", server.uri());
+    let client = ClientOptions::default().build().unwrap();
+    let saved = download_installer(&client, &url, &dir, |_, _| {})
+        .await
+        .unwrap();
+
+    assert_eq!(std::fs::read(&saved).unwrap(), b"installer-bytes");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
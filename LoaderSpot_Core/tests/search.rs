@@ -0,0 +1,1014 @@
+mod common;
+
+use common::CountingServer;
+use loaderspot_core::{
+    count_builds, dedup_found, is_installer_content_type, is_suspiciously_small, CheckedBuilds,
+    ContentTypeCounts, ErrorStorm, FailedCheck, FailedChecks, FoundEvent, FoundInstaller, Jitter,
+    KnownBuilds, LadderOptions, PathTemplates, Platform, RateLimiter, Reachability, RequestBudget,
+    SearchEngine, StatusCounts, SUSPICIOUS_SIZE,
+};
+use reqwest::Client;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn concurrent_searches_share_the_connection_limit() {
+    let server = CountingServer::start(&[], Duration::from_millis(20)).await;
+    let engine = Arc::new(SearchEngine {
+        base_url: server.base_url.clone(),
+        ..SearchEngine::new(Client::new(), 4)
+    });
+
+    let mut tasks = Vec::new();
+    for version in [
+        "1.2.3.4.gaaaaaaaa",
+        "1.2.3.5.gbbbbbbbb",
+        "1.2.3.6.gcccccccc",
+    ] {
+        let engine = engine.clone();
+        tasks.push(tokio::spawn(async move {
+            engine.search(version, 0, 19, &[Platform::WinX64]).await
+        }));
+    }
+    for task in tasks {
+        task.await.unwrap();
+    }
+
+    assert_eq!(server.requests.load(Ordering::SeqCst), 60);
+    assert!(server.max_in_flight.load(Ordering::SeqCst) <= 4);
+}
+
+#[tokio::test]
+async fn concurrent_platform_searches_share_the_connection_limit() {
+    let server = CountingServer::start(&[], Duration::from_millis(20)).await;
+    let engine = Arc::new(SearchEngine {
+        base_url: server.base_url.clone(),
+        ..SearchEngine::new(Client::new(), 4)
+    });
+
+    let mut tasks = Vec::new();
+    for platform in [Platform::WinX64, Platform::WinArm64, Platform::MacOsArm64] {
+        let engine = engine.clone();
+        tasks.push(tokio::spawn(async move {
+            engine
+                .search_ranges("1.2.3.4.gaaaaaaaa", &[(0, 9), (20, 29)], &[platform])
+                .await
+        }));
+    }
+    for task in tasks {
+        task.await.unwrap();
+    }
+
+    assert_eq!(server.requests.load(Ordering::SeqCst), 60);
+    assert!(server.max_in_flight.load(Ordering::SeqCst) <= 4);
+}
+
+#[tokio::test]
+async fn finds_exactly_the_published_builds_within_the_connection_limit() {
+    let version = "1.2.3.4.gaaaaaaaa";
+    let published = [
+        (Platform::WinX64, 7),
+        (Platform::WinX64, 42),
+        (Platform::MacOsArm64, 0),
+        (Platform::MacOsArm64, 49),
+    ];
+    let paths: Vec<String> = published
+        .iter()
+        .map(|(platform, build)| platform.generate_path(version, *build))
+        .collect();
+    let paths: Vec<&str> = paths.iter().map(String::as_str).collect();
+    let server = CountingServer::start(&paths, Duration::from_millis(5)).await;
+    let engine = SearchEngine {
+        base_url: server.base_url.clone(),
+        ..SearchEngine::new(Client::new(), 3)
+    };
+
+    let found = engine
+        .search(version, 0, 49, &[Platform::WinX64, Platform::MacOsArm64])
+        .await;
+
+    let mut found: Vec<(Platform, String)> = found
+        .into_iter()
+        .map(|hit| (hit.platform, hit.url))
+        .collect();
+    found.sort();
+    let mut expected: Vec<(Platform, String)> = published
+        .iter()
+        .map(|(platform, build)| {
+            let url = format!(
+                "{}{}",
+                server.base_url,
+                platform.generate_path(version, *build)
+            );
+            (*platform, url)
+        })
+        .collect();
+    expected.sort();
+    assert_eq!(found, expected);
+    assert_eq!(server.requests.load(Ordering::SeqCst), 100);
+    let max_in_flight = server.max_in_flight.load(Ordering::SeqCst);
+    assert!((2..=3).contains(&max_in_flight), "{}", max_in_flight);
+}
+
+#[tokio::test]
+async fn on_found_reports_version_and_build() {
+    let version = "1.2.3.4.gaaaaaaaa";
+    let hit = Platform::WinX64.generate_path(version, 7);
+    let server = CountingServer::start(&[&hit], Duration::ZERO).await;
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let events_clone = events.clone();
+    let engine = SearchEngine {
+        base_url: server.base_url.clone(),
+        on_found: Some(Arc::new(move |event: &FoundEvent| {
+            events_clone.lock().unwrap().push(event.clone());
+        })),
+        ..SearchEngine::new(Client::new(), 4)
+    };
+
+    let found = engine.search(version, 0, 9, &[Platform::WinX64]).await;
+
+    assert_eq!(found.len(), 1);
+    let events = events.lock().unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(&*events[0].version, version);
+    assert_eq!(events[0].build, 7);
+    assert_eq!(events[0].url, format!("{}{}", server.base_url, hit));
+}
+
+#[tokio::test]
+async fn waits_out_retry_after_and_retries() {
+    let version = "1.2.3.4.gaaaaaaaa";
+    let hit = format!("/{}", Platform::WinX64.generate_path(version, 0));
+    let server = MockServer::start().await;
+    Mock::given(method("HEAD"))
+        .and(path(hit.as_str()))
+        .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "2"))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("HEAD"))
+        .and(path(hit.as_str()))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    let engine = SearchEngine {
+        base_url: format!("{}/", server.uri()),
+        ..SearchEngine::new(Client::new(), 4)
+    };
+
+    let started = Instant::now();
+    let found = engine.search(version, 0, 0, &[Platform::WinX64]).await;
+
+    assert_eq!(found.len(), 1);
+    assert!(started.elapsed() >= Duration::from_secs(2));
+    assert_eq!(engine.request_counts.requests(), 2);
+    assert_eq!(engine.request_counts.retries(), 1);
+}
+
+#[tokio::test]
+async fn ladder_search_stops_once_every_platform_has_a_hit() {
+    let version = "1.2.3.4.gaaaaaaaa";
+    let win = Platform::WinX64.generate_path(version, 3);
+    let mac = Platform::MacOsArm64.generate_path(version, 25);
+    let server = CountingServer::start(&[&win, &mac], Duration::ZERO).await;
+    let engine = SearchEngine {
+        base_url: server.base_url.clone(),
+        ..SearchEngine::new(Client::new(), 8)
+    };
+    let options = LadderOptions {
+        initial_window: 9,
+        increment: 10,
+        max_iterations: 5,
+    };
+
+    let mut found = engine
+        .ladder_search(version, &[Platform::WinX64, Platform::MacOsArm64], options)
+        .await;
+    found.sort_by_key(|hit| hit.platform);
+
+    assert_eq!(found.len(), 2);
+    assert_eq!(found[0].platform, Platform::WinX64);
+    assert_eq!(found[1].platform, Platform::MacOsArm64);
+    // 0..=9 for both, then 10..=19 and 20..=29 for macOS only.
+    assert_eq!(server.requests.load(Ordering::SeqCst), 10 * 2 + 10 + 10);
+}
+
+#[tokio::test]
+async fn scanned_builds_cover_what_a_ladder_search_checked() {
+    let version = "1.2.3.4.gaaaaaaaa";
+    let hit = Platform::MacOsIntel.generate_path(version, 15);
+    let server = CountingServer::start(&[&hit], Duration::ZERO).await;
+    let engine = SearchEngine {
+        base_url: server.base_url.clone(),
+        ..SearchEngine::new(Client::new(), 4)
+    };
+    let ladder = LadderOptions {
+        initial_window: 9,
+        increment: 10,
+        max_iterations: 5,
+    };
+
+    engine
+        .ladder_search(version, &[Platform::MacOsIntel], ladder)
+        .await;
+    engine.search(version, 5, 14, &[Platform::WinX64]).await;
+
+    let scanned = &engine.scanned_builds;
+    assert_eq!(scanned.get(version, Platform::MacOsIntel), Some((0, 19)));
+    assert_eq!(scanned.get(version, Platform::WinX64), Some((5, 14)));
+    assert_eq!(scanned.get(version, Platform::MacOsArm64), None);
+}
+
+#[tokio::test]
+async fn counts_checked_urls_per_platform() {
+    let server = CountingServer::start(&[], Duration::ZERO).await;
+    let engine = SearchEngine {
+        base_url: server.base_url.clone(),
+        ..SearchEngine::new(Client::new(), 4)
+    };
+
+    engine
+        .search(
+            "1.2.3.4.gaaaaaaaa",
+            0,
+            4,
+            &[Platform::WinX64, Platform::MacOsIntel],
+        )
+        .await;
+    engine
+        .search("1.2.3.4.gaaaaaaaa", 0, 2, &[Platform::WinX64])
+        .await;
+
+    assert_eq!(engine.platform_processed.get(Platform::WinX64), 8);
+    assert_eq!(engine.platform_processed.get(Platform::MacOsIntel), 5);
+    assert_eq!(engine.processed.load(Ordering::SeqCst), 13);
+}
+
+#[tokio::test]
+async fn a_full_search_processes_exactly_the_builds_counted_up_front() {
+    let version = "1.2.3.4.gaaaaaaaa";
+    let found = Platform::WinX64.generate_path(version, 21);
+    let server = CountingServer::start(&[&found], Duration::ZERO).await;
+    let engine = SearchEngine {
+        base_url: server.base_url.clone(),
+        step: 3,
+        ..SearchEngine::new(Client::new(), 4)
+    };
+
+    let ranges = [(0, 9), (20, 31)];
+    let platforms = [Platform::WinX64, Platform::MacOsArm64];
+    engine.search_ranges(version, &ranges, &platforms).await;
+
+    let total_work = count_builds(&ranges, 3) * platforms.len() as u64;
+    assert_eq!(engine.processed.load(Ordering::SeqCst), total_work);
+    assert_eq!(server.requests.load(Ordering::SeqCst) as u64, total_work);
+}
+
+#[tokio::test]
+async fn counts_requests_without_a_response_as_network_errors() {
+    // Nothing listens on a port that was just released.
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}/", listener.local_addr().unwrap());
+    drop(listener);
+    let engine = SearchEngine {
+        base_url,
+        ..SearchEngine::new(Client::new(), 4)
+    };
+
+    let found = engine
+        .search("1.2.3.4.gaaaaaaaa", 0, 2, &[Platform::WinX64])
+        .await;
+
+    assert!(found.is_empty());
+    assert_eq!(engine.network_errors.load(Ordering::SeqCst), 3);
+    assert_eq!(engine.processed.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn an_error_storm_pauses_the_search() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}/", listener.local_addr().unwrap());
+    drop(listener);
+    let engine = Arc::new(SearchEngine {
+        base_url,
+        error_storm: ErrorStorm::new(10, 50),
+        ..SearchEngine::new(Client::new(), 4)
+    });
+
+    let search = {
+        let engine = engine.clone();
+        tokio::spawn(async move {
+            engine
+                .search("1.2.3.4.gaaaaaaaa", 0, 999, &[Platform::WinX64])
+                .await
+        })
+    };
+    let started = Instant::now();
+    while !engine.error_storm.tripped() {
+        assert!(started.elapsed() < Duration::from_secs(5));
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert!(engine.pause_flag.load(Ordering::SeqCst));
+    // Only the requests already in flight finish.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(engine.processed.load(Ordering::SeqCst) < 20);
+
+    engine.cancel_flag.store(true, Ordering::Relaxed);
+    search.await.unwrap();
+}
+
+#[tokio::test]
+async fn searches_overridden_path_templates() {
+    let version = "1.2.3.4.gaaaaaaaa";
+    let mut templates = PathTemplates::default();
+    templates
+        .set(
+            Platform::MacOsArm64,
+            "osx-arm64/spotify-{version}-{number}.dmg",
+        )
+        .unwrap();
+    let hit = templates.generate_path(Platform::MacOsArm64, version, 3);
+    let server = CountingServer::start(&[&hit], Duration::ZERO).await;
+    let engine = SearchEngine {
+        base_url: server.base_url.clone(),
+        templates,
+        ..SearchEngine::new(Client::new(), 4)
+    };
+
+    let found = engine.search(version, 0, 4, &[Platform::MacOsArm64]).await;
+
+    assert_eq!(found.len(), 1);
+    assert!(found[0].url.ends_with("spotify-1.2.3.4.gaaaaaaaa-3.dmg"));
+}
+
+#[tokio::test]
+async fn hinted_search_falls_back_to_the_rest_of_the_range() {
+    let version = "1.2.85.510.gaaaaaaaa";
+    let known = KnownBuilds::parse(
+        r#"{
+            "1.2.85.507": {"links": {"win": {"x64": "https://example.com/spotify_installer-1.2.85.507.g795fff93-20.exe"}}},
+            "1.2.85.513": {"links": {"win": {"x64": "https://example.com/spotify_installer-1.2.85.513.g45f09625-29.exe"}}}
+        }"#,
+    )
+    .unwrap();
+    let inside = Platform::WinX64.generate_path(version, 25);
+    let unhinted = Platform::WinArm64.generate_path(version, 3);
+    let outside = Platform::WinX64.generate_path("1.2.85.511.gbbbbbbbb", 40);
+    let server = CountingServer::start(&[&inside, &unhinted, &outside], Duration::ZERO).await;
+    let engine = SearchEngine {
+        base_url: server.base_url.clone(),
+        ..SearchEngine::new(Client::new(), 4)
+    };
+
+    let found = engine
+        .hinted_search(version, 0, 49, &[Platform::WinX64], &known)
+        .await;
+    assert_eq!(found.len(), 1);
+    // Only builds 20-29 were needed.
+    assert_eq!(server.requests.load(Ordering::SeqCst), 10);
+
+    // No hint for arm64: the whole range is checked.
+    let found = engine
+        .hinted_search(version, 0, 49, &[Platform::WinArm64], &known)
+        .await;
+    assert_eq!(found.len(), 1);
+    assert_eq!(server.requests.load(Ordering::SeqCst), 60);
+
+    // A miss inside the window falls back to the builds around it.
+    let found = engine
+        .hinted_search("1.2.85.511.gbbbbbbbb", 0, 49, &[Platform::WinX64], &known)
+        .await;
+    assert_eq!(found.len(), 1);
+    assert_eq!(server.requests.load(Ordering::SeqCst), 110);
+}
+
+#[tokio::test]
+async fn auto_range_brackets_a_run_of_builds_and_scans_around_it() {
+    let version = "1.2.3.4.gaaaaaaaa";
+    let paths: Vec<String> = (300..=330)
+        .map(|build| Platform::WinX64.generate_path(version, build))
+        .collect();
+    let paths: Vec<&str> = paths.iter().map(String::as_str).collect();
+    let server = CountingServer::start(&paths, Duration::ZERO).await;
+    let engine = SearchEngine {
+        base_url: server.base_url.clone(),
+        ..SearchEngine::new(Client::new(), 4)
+    };
+
+    let found = engine
+        .auto_range_search(version, 0, 1000, &[Platform::WinX64])
+        .await;
+    assert_eq!(found.len(), 31);
+    // Probes 0, 1, 2, 4, 8, 16, 32, then 50-300 every 50; 251-299 below the
+    // hit; 301-350 with hits and 351-400 without above it.
+    assert_eq!(server.requests.load(Ordering::SeqCst), 13 + 49 + 100);
+}
+
+#[tokio::test]
+async fn auto_range_without_a_hit_only_probes() {
+    let server = CountingServer::start(&[], Duration::ZERO).await;
+    let engine = SearchEngine {
+        base_url: server.base_url.clone(),
+        max_gap: Some(100),
+        ..SearchEngine::new(Client::new(), 4)
+    };
+
+    let found = engine
+        .auto_range_search("1.2.3.4.gaaaaaaaa", 0, 1000, &[Platform::WinX64])
+        .await;
+    assert!(found.is_empty());
+    // 0, 1, 2, 4, ..., 64, then 100-1000 every 100.
+    assert_eq!(server.requests.load(Ordering::SeqCst), 8 + 10);
+}
+
+#[tokio::test]
+async fn progress_never_passes_the_builds_counted_up_front() {
+    let version = "1.2.85.511.gbbbbbbbb";
+    let known = KnownBuilds::parse(
+        r#"{
+            "1.2.85.507": {"links": {"win": {"x64": "https://example.com/spotify_installer-1.2.85.507.g795fff93-20.exe"}}},
+            "1.2.85.513": {"links": {"win": {"x64": "https://example.com/spotify_installer-1.2.85.513.g45f09625-29.exe"}}}
+        }"#,
+    )
+    .unwrap();
+    let server = CountingServer::start(&[], Duration::ZERO).await;
+    let engine = SearchEngine {
+        base_url: server.base_url.clone(),
+        step: 3,
+        ..SearchEngine::new(Client::new(), 4)
+    };
+
+    // The window 20-29 is checked first, then the builds on either side.
+    engine
+        .hinted_search(version, 0, 49, &[Platform::WinX64], &known)
+        .await;
+    let total_work = count_builds(&[(0, 49)], 3);
+    assert_eq!(engine.processed.load(Ordering::SeqCst), total_work);
+    assert_eq!(server.requests.load(Ordering::SeqCst) as u64, total_work);
+}
+
+#[tokio::test]
+async fn retries_count_once_towards_progress() {
+    let version = "1.2.3.4.gaaaaaaaa";
+    let server = MockServer::start().await;
+    Mock::given(method("HEAD"))
+        .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "1"))
+        .up_to_n_times(3)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("HEAD"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+
+    let engine = SearchEngine {
+        base_url: format!("{}/", server.uri()),
+        ..SearchEngine::new(Client::new(), 4)
+    };
+    engine.search(version, 0, 9, &[Platform::WinX64]).await;
+
+    assert_eq!(engine.processed.load(Ordering::SeqCst), 10);
+    assert_eq!(engine.request_counts.requests(), 13);
+    assert_eq!(engine.request_counts.retries(), 3);
+}
+
+#[tokio::test]
+async fn max_gap_stops_a_platform_after_its_last_hit() {
+    let version = "1.2.3.4.gaaaaaaaa";
+    let first = Platform::WinX64.generate_path(version, 5);
+    let second = Platform::WinX64.generate_path(version, 6);
+    let server = CountingServer::start(&[&first, &second], Duration::ZERO).await;
+    let engine = SearchEngine {
+        base_url: server.base_url.clone(),
+        max_gap: Some(10),
+        // One connection at a time keeps the request count exact.
+        ..SearchEngine::new(Client::new(), 1)
+    };
+
+    let found = engine.search(version, 0, 999, &[Platform::WinX64]).await;
+    assert_eq!(found.len(), 2);
+    // Builds 0-16: the hits, then 10 misses after build 6.
+    assert_eq!(server.requests.load(Ordering::SeqCst), 17);
+
+    // Without a hit the whole range is still checked.
+    let found = engine.search(version, 0, 99, &[Platform::WinArm64]).await;
+    assert!(found.is_empty());
+    assert_eq!(server.requests.load(Ordering::SeqCst), 117);
+}
+
+#[tokio::test]
+async fn a_version_searched_twice_yields_each_installer_once() {
+    let version = "1.2.3.4.gaaaaaaaa";
+    let win = Platform::WinX64.generate_path(version, 3);
+    let mac = Platform::MacOsIntel.generate_path(version, 8);
+    let server = CountingServer::start(&[&win, &mac], Duration::ZERO).await;
+    let engine = SearchEngine {
+        base_url: server.base_url.clone(),
+        ..SearchEngine::new(Client::new(), 4)
+    };
+
+    let platforms = [Platform::WinX64, Platform::MacOsIntel];
+    let mut found = engine.search(version, 0, 9, &platforms).await;
+    found.extend(engine.search(version, 0, 9, &platforms).await);
+    assert_eq!(found.len(), 4);
+
+    dedup_found(&mut found);
+    assert_eq!(found.len(), 2);
+    assert_ne!(found[0].url, found[1].url);
+}
+
+#[tokio::test]
+async fn cancel_ends_a_search_without_draining_the_queue() {
+    let server = CountingServer::start(&[], Duration::from_millis(20)).await;
+    let engine = Arc::new(SearchEngine {
+        base_url: server.base_url.clone(),
+        ..SearchEngine::new(Client::new(), 4)
+    });
+
+    let search = {
+        let engine = engine.clone();
+        tokio::spawn(async move {
+            engine
+                .search("1.2.3.4.gaaaaaaaa", 0, 9999, &[Platform::WinX64])
+                .await
+        })
+    };
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let cancelled_at = Instant::now();
+    engine.cancel_flag.store(true, Ordering::Relaxed);
+    search.await.unwrap();
+
+    assert!(cancelled_at.elapsed() < Duration::from_secs(2));
+    assert!(server.requests.load(Ordering::SeqCst) < 100);
+    assert!(server.max_in_flight.load(Ordering::SeqCst) <= 4);
+}
+
+#[tokio::test]
+async fn checked_builds_follow_each_platform() {
+    let version = "1.2.3.4.gaaaaaaaa";
+    let server = CountingServer::start(&[], Duration::ZERO).await;
+    let checked = CheckedBuilds::default();
+    let engine = SearchEngine {
+        base_url: server.base_url.clone(),
+        checked_builds: Some(checked.clone()),
+        ..SearchEngine::new(Client::new(), 4)
+    };
+
+    engine
+        .search(version, 5, 14, &[Platform::WinX64, Platform::MacOsArm64])
+        .await;
+
+    assert_eq!(checked.get(version, Platform::WinX64), Some(15));
+    assert_eq!(checked.get(version, Platform::MacOsArm64), Some(15));
+    assert_eq!(checked.get(version, Platform::WinArm64), None);
+}
+
+#[tokio::test]
+async fn checked_builds_stop_at_the_first_build_a_cancel_skipped() {
+    let version = "1.2.3.4.gaaaaaaaa";
+    let server = CountingServer::start(&[], Duration::from_millis(20)).await;
+    let checked = CheckedBuilds::default();
+    let engine = Arc::new(SearchEngine {
+        base_url: server.base_url.clone(),
+        checked_builds: Some(checked.clone()),
+        ..SearchEngine::new(Client::new(), 4)
+    });
+
+    let search = {
+        let engine = engine.clone();
+        tokio::spawn(async move { engine.search(version, 0, 9999, &[Platform::WinX64]).await })
+    };
+    // Spawning ten thousand builds can outlast a fixed sleep in debug builds.
+    while checked.get(version, Platform::WinX64).is_none() {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    engine.cancel_flag.store(true, Ordering::Relaxed);
+    search.await.unwrap();
+
+    let next = checked.get(version, Platform::WinX64).unwrap();
+    assert!(next > 0 && next < 9999);
+    // Every build below it got a request.
+    assert!(server.requests.load(Ordering::SeqCst) >= next as usize);
+}
+
+#[tokio::test]
+async fn try_dmg_checks_macos_builds_as_dmg_too() {
+    let version = "1.2.3.4.gaaaaaaaa";
+    let dmg = Platform::MacOsArm64.generate_dmg_path(version, 4).unwrap();
+    let server = CountingServer::start(&[&dmg], Duration::ZERO).await;
+    let mut engine = SearchEngine {
+        base_url: server.base_url.clone(),
+        ..SearchEngine::new(Client::new(), 4)
+    };
+
+    let platforms = [Platform::WinX64, Platform::MacOsArm64];
+    assert!(engine.search(version, 0, 9, &platforms).await.is_empty());
+    assert_eq!(server.requests.load(Ordering::SeqCst), 20);
+
+    engine.try_dmg = true;
+    let found = engine.search(version, 0, 9, &platforms).await;
+    assert_eq!(found.len(), 1);
+    assert!(found[0].url.ends_with(".gaaaaaaaa-4.dmg"));
+    // Windows once per build, macOS twice.
+    assert_eq!(server.requests.load(Ordering::SeqCst), 20 + 10 + 2 * 10);
+    assert_eq!(engine.processed.load(Ordering::SeqCst), 40);
+}
+
+#[tokio::test]
+async fn rate_limit_applies_on_top_of_the_connection_limit() {
+    let server = CountingServer::start(&[], Duration::ZERO).await;
+    let engine = SearchEngine {
+        base_url: server.base_url.clone(),
+        rate_limiter: RateLimiter::new(50),
+        ..SearchEngine::new(Client::new(), 100)
+    };
+
+    let started = Instant::now();
+    engine
+        .search("1.2.3.4.gaaaaaaaa", 0, 9, &[Platform::WinX64])
+        .await;
+    // Ten requests 20ms apart, however many connections are free.
+    assert!(started.elapsed() >= Duration::from_millis(180));
+    assert_eq!(server.requests.load(Ordering::SeqCst), 10);
+}
+
+#[tokio::test]
+async fn jitter_waits_before_each_request() {
+    let server = CountingServer::start(&[], Duration::ZERO).await;
+    let engine = SearchEngine {
+        base_url: server.base_url.clone(),
+        jitter: Jitter::seeded(Duration::from_millis(400), 1),
+        ..SearchEngine::new(Client::new(), 1)
+    };
+
+    let started = Instant::now();
+    engine
+        .search("1.2.3.4.gaaaaaaaa", 0, 9, &[Platform::WinX64])
+        .await;
+    // Ten waits of 0-400ms in a row add up to well over a single one.
+    assert!(started.elapsed() >= Duration::from_millis(400));
+    assert_eq!(server.requests.load(Ordering::SeqCst), 10);
+}
+
+#[tokio::test]
+async fn cancel_aborts_requests_in_flight() {
+    let server = CountingServer::start(&[], Duration::from_secs(30)).await;
+    let engine = Arc::new(SearchEngine {
+        base_url: server.base_url.clone(),
+        ..SearchEngine::new(Client::new(), 4)
+    });
+
+    let search = {
+        let engine = engine.clone();
+        tokio::spawn(async move {
+            engine
+                .search("1.2.3.4.gaaaaaaaa", 0, 3, &[Platform::WinX64])
+                .await
+        })
+    };
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let cancelled_at = Instant::now();
+    engine.cancel_flag.store(true, Ordering::Relaxed);
+    search.await.unwrap();
+
+    assert!(cancelled_at.elapsed() < Duration::from_secs(2));
+}
+
+#[tokio::test]
+async fn searches_only_the_given_ranges() {
+    let version = "1.2.3.4.gaaaaaaaa";
+    let low = Platform::WinX64.generate_path(version, 50);
+    let high = Platform::WinX64.generate_path(version, 3050);
+    let server = CountingServer::start(&[&low, &high], Duration::ZERO).await;
+    let engine = SearchEngine {
+        base_url: server.base_url.clone(),
+        ..SearchEngine::new(Client::new(), 4)
+    };
+
+    let found = engine
+        .search_ranges(version, &[(0, 100), (3000, 3100)], &[Platform::WinX64])
+        .await;
+    assert_eq!(found.len(), 2);
+    assert_eq!(server.requests.load(Ordering::SeqCst), 202);
+}
+
+#[tokio::test]
+async fn a_step_checks_every_nth_build_only() {
+    let version = "1.2.3.4.gaaaaaaaa";
+    let on_step = Platform::WinX64.generate_path(version, 30);
+    let off_step = Platform::WinX64.generate_path(version, 35);
+    let server = CountingServer::start(&[&on_step, &off_step], Duration::ZERO).await;
+    let checked = CheckedBuilds::default();
+    let engine = SearchEngine {
+        base_url: server.base_url.clone(),
+        step: 10,
+        checked_builds: Some(checked.clone()),
+        ..SearchEngine::new(Client::new(), 4)
+    };
+
+    let found = engine.search(version, 0, 99, &[Platform::WinX64]).await;
+    assert_eq!(found.len(), 1);
+    assert!(found[0].url.ends_with(&on_step));
+    assert_eq!(server.requests.load(Ordering::SeqCst), 10);
+    assert_eq!(checked.get(version, Platform::WinX64), Some(100));
+}
+
+#[tokio::test]
+async fn tallies_the_statuses_of_missed_builds() {
+    let version = "1.2.3.4.gaaaaaaaa";
+    let hit = Platform::WinX64.generate_path(version, 3);
+    let server = CountingServer::start(&[&hit], Duration::ZERO).await;
+    let counts = StatusCounts::default();
+    let engine = SearchEngine {
+        base_url: server.base_url.clone(),
+        status_counts: Some(counts.clone()),
+        ..SearchEngine::new(Client::new(), 4)
+    };
+
+    let found = engine.search(version, 0, 9, &[Platform::WinX64]).await;
+    assert_eq!(found.len(), 1);
+    assert_eq!(counts.counts(), vec![(404, 9)]);
+}
+
+#[tokio::test]
+async fn min_size_skips_placeholder_files() {
+    let version = "1.2.3.4.gaaaaaaaa";
+    let server = MockServer::start().await;
+    Mock::given(path(format!(
+        "/{}",
+        Platform::WinX64.generate_path(version, 3)
+    )))
+    .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![0u8; 100]))
+    .mount(&server)
+    .await;
+    let base_url = format!("{}/", server.uri());
+
+    let engine = SearchEngine {
+        base_url: base_url.clone(),
+        ..SearchEngine::new(Client::new(), 4)
+    };
+    let found = engine.search(version, 0, 4, &[Platform::WinX64]).await;
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].size, Some(100));
+    assert!(is_suspiciously_small(found[0].size));
+
+    let counts = StatusCounts::default();
+    let engine = SearchEngine {
+        base_url,
+        min_size: Some(SUSPICIOUS_SIZE),
+        status_counts: Some(counts.clone()),
+        ..SearchEngine::new(Client::new(), 4)
+    };
+    let found = engine.search(version, 0, 4, &[Platform::WinX64]).await;
+    assert!(found.is_empty());
+    assert_eq!(counts.counts(), vec![(404, 4), (200, 1)]);
+}
+
+#[tokio::test]
+async fn require_content_type_skips_html_error_pages() {
+    let version = "1.2.3.4.gaaaaaaaa";
+    let server = MockServer::start().await;
+    for (build, content_type) in [
+        (1, "text/html; charset=utf-8"),
+        (3, "application/octet-stream"),
+    ] {
+        Mock::given(path(format!(
+            "/{}",
+            Platform::WinX64.generate_path(version, build)
+        )))
+        .respond_with(ResponseTemplate::new(200).insert_header("content-type", content_type))
+        .mount(&server)
+        .await;
+    }
+    let base_url = format!("{}/", server.uri());
+
+    let engine = SearchEngine {
+        base_url: base_url.clone(),
+        ..SearchEngine::new(Client::new(), 4)
+    };
+    let found = engine.search(version, 0, 4, &[Platform::WinX64]).await;
+    assert_eq!(found.len(), 2);
+    assert_eq!(
+        engine.unexpected_types.summary(),
+        "text/html; charset=utf-8 x1"
+    );
+
+    let counts = StatusCounts::default();
+    let unexpected_types = ContentTypeCounts::default();
+    let engine = SearchEngine {
+        base_url,
+        require_content_type: true,
+        status_counts: Some(counts.clone()),
+        unexpected_types: unexpected_types.clone(),
+        ..SearchEngine::new(Client::new(), 4)
+    };
+    let found = engine.search(version, 0, 4, &[Platform::WinX64]).await;
+    assert_eq!(found.len(), 1);
+    assert!(found[0]
+        .url
+        .ends_with(&Platform::WinX64.generate_path(version, 3)));
+    assert_eq!(counts.counts(), vec![(404, 3), (200, 1)]);
+    assert_eq!(
+        found[0].content_type.as_deref(),
+        Some("application/octet-stream")
+    );
+    assert_eq!(unexpected_types.total(), 1);
+
+    let checked = engine
+        .check_urls(&[format!(
+            "{}/{}",
+            server.uri(),
+            Platform::WinX64.generate_path(version, 1)
+        )])
+        .await;
+    assert!(!checked[0].1.exists);
+    assert_eq!(
+        checked[0].1.content_type.as_deref(),
+        Some("text/html; charset=utf-8")
+    );
+}
+
+#[test]
+fn installer_content_types_ignore_case_and_parameters() {
+    assert!(is_installer_content_type("application/octet-stream"));
+    assert!(is_installer_content_type(
+        "Application/X-Apple-Diskimage; name=x.dmg"
+    ));
+    assert!(!is_installer_content_type("text/html"));
+    assert!(!is_installer_content_type(""));
+}
+
+#[tokio::test]
+async fn checks_a_list_of_urls_in_order() {
+    let server = MockServer::start().await;
+    Mock::given(method("HEAD"))
+        .and(path("/a.exe"))
+        .respond_with(ResponseTemplate::new(200).insert_header("content-length", "1234"))
+        .mount(&server)
+        .await;
+    Mock::given(method("HEAD"))
+        .and(path("/b.exe"))
+        .respond_with(ResponseTemplate::new(403))
+        .mount(&server)
+        .await;
+    let engine = SearchEngine {
+        status_counts: Some(StatusCounts::default()),
+        ..SearchEngine::new(Client::new(), 2)
+    };
+
+    let urls: Vec<String> = ["a.exe", "b.exe", "c.exe"]
+        .iter()
+        .map(|name| format!("{}/{}", server.uri(), name))
+        .collect();
+    let checked = engine.check_urls(&urls).await;
+
+    let summary: Vec<(&str, bool, u16, Option<u64>)> = checked
+        .iter()
+        .map(|(url, probe)| {
+            (
+                url.as_str(),
+                probe.exists,
+                probe.status,
+                probe.content_length,
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            (urls[0].as_str(), true, 200, Some(1234)),
+            (urls[1].as_str(), false, 403, None),
+            (urls[2].as_str(), false, 404, None),
+        ]
+    );
+    assert_eq!(engine.processed.load(Ordering::SeqCst), 3);
+    let summary = engine.status_counts.unwrap().summary();
+    assert!(
+        summary.contains("403 x1") && summary.contains("404 x1"),
+        "{}",
+        summary
+    );
+}
+
+#[tokio::test]
+async fn confirming_hits_drops_those_a_second_get_misses() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/a.exe"))
+        .respond_with(ResponseTemplate::new(206).insert_header("content-range", "bytes 0-0/1234"))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/b.exe"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+    let engine = SearchEngine::new(Client::new(), 2);
+
+    let hit = |name: &str| FoundInstaller {
+        url: format!("{}/{}", server.uri(), name),
+        platform: Platform::WinX64,
+        size: Some(1234),
+        last_modified: None,
+        content_type: None,
+    };
+    let (confirmed, unconfirmed) = engine
+        .confirm_hits(vec![hit("a.exe"), hit("b.exe")], Duration::ZERO)
+        .await;
+
+    assert_eq!(confirmed, vec![hit("a.exe")]);
+    assert_eq!(unconfirmed, vec![(hit("b.exe"), Some(404))]);
+    assert_eq!(engine.request_counts.requests(), 2);
+}
+
+#[tokio::test]
+async fn an_unresolvable_host_stops_the_search_at_once() {
+    let engine = SearchEngine {
+        base_url: "http://loaderspot.invalid/".to_string(),
+        reachability: Reachability::watching(),
+        ..SearchEngine::new(Client::new(), 4)
+    };
+
+    let found = engine
+        .search("1.2.3.4.gaaaaaaaa", 0, 999, &[Platform::WinX64])
+        .await;
+
+    assert!(found.is_empty());
+    assert!(engine.reachability.unreachable());
+    assert!(engine.cancel_flag.load(Ordering::SeqCst));
+    assert!(engine.processed.load(Ordering::SeqCst) < 1000);
+}
+
+#[tokio::test]
+async fn a_spent_budget_cancels_the_search() {
+    let version = "1.2.3.4.gaaaaaaaa";
+    let server = CountingServer::start(&[], Duration::ZERO).await;
+    let engine = SearchEngine {
+        base_url: server.base_url.clone(),
+        budget: RequestBudget::new(10),
+        ..SearchEngine::new(Client::new(), 4)
+    };
+
+    engine.search(version, 0, 99, &[Platform::WinX64]).await;
+
+    assert_eq!(server.requests.load(Ordering::SeqCst), 10);
+    assert_eq!(engine.budget.used(), 10);
+    assert!(engine.budget.exhausted());
+    assert!(engine.cancel_flag.load(Ordering::Relaxed));
+}
+
+#[tokio::test]
+async fn collects_the_checks_that_got_no_response() {
+    let version = "1.2.3.4.gaaaaaaaa";
+    let failed = FailedChecks::default();
+    let engine = SearchEngine {
+        // Nothing listens on port 1.
+        base_url: "http://127.0.0.1:1/".to_string(),
+        failed_checks: Some(failed.clone()),
+        ..SearchEngine::new(Client::new(), 4)
+    };
+
+    engine.search(version, 0, 2, &[Platform::WinX64]).await;
+
+    let mut builds: Vec<i32> = failed.snapshot().iter().map(|check| check.build).collect();
+    builds.sort();
+    assert_eq!(builds, [0, 1, 2]);
+    assert!(failed.snapshot()[0].url.starts_with("http://127.0.0.1:1/"));
+}
+
+#[tokio::test]
+async fn rescanning_failures_keeps_only_the_ones_failing_again() {
+    let server = MockServer::start().await;
+    Mock::given(method("HEAD"))
+        .and(path("/hit.exe"))
+        .respond_with(ResponseTemplate::new(200).insert_header("content-length", "1234"))
+        .mount(&server)
+        .await;
+    let check = |base: &str, build: i32, name: &str| FailedCheck {
+        version: "1.2.3.4.gaaaaaaaa".to_string(),
+        platform: Platform::WinX64,
+        build,
+        url: format!("{}/{}", base, name),
+    };
+    let failed = [
+        check(&server.uri(), 1, "hit.exe"),
+        check(&server.uri(), 2, "miss.exe"),
+        check("http://127.0.0.1:1", 3, "down.exe"),
+    ];
+    let engine = SearchEngine::new(Client::new(), 2);
+
+    let rescan = engine.rescan_failures(&failed).await;
+
+    assert_eq!(rescan.recovered, 2);
+    assert_eq!(rescan.found.len(), 1);
+    assert_eq!(rescan.found[0].build, 1);
+    assert_eq!(rescan.found[0].size, Some(1234));
+    assert_eq!(rescan.still_failing, [failed[2].clone()]);
+}
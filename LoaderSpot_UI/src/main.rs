@@ -3,151 +3,359 @@
 
 use crossbeam_channel::{unbounded, Receiver, Sender};
 use eframe::egui;
+use eframe::egui::text::{LayoutJob, TextFormat};
+use loaderspot_core::{
+    count_builds, download_command, download_installer, estimate_search_time, fetch_versions_json,
+    file_name_from_url, find_versions, format_age, generate_urls, is_suspiciously_small,
+    merge_build_ranges, numbered_version_lines, short_version, should_use_win_x86,
+    validate_version, validate_version_strict, version_list_lines, Backoff, CachedInstaller,
+    CheckedBuilds, Checkpoint, ClientOptions, ContentTypeCounts, Downloader, ErrorStorm,
+    FailedChecks, FoundEvent, IpFamily, Jitter, KnownBuilds, KnownVersions, LadderOptions,
+    PathTemplates, Platform, PlatformProgress, Probe, Ramp, RateLimiter, Reachability,
+    RequestCounts, Rescan, ResultsCache, RunReport, RunStats, ScannedBuilds, SearchEngine,
+    SearchParams, SearchedVersion, StatusCounts, DEFAULT_MAX_REQUESTS, DEFAULT_POOL_IDLE_TIMEOUT,
+    SUSPICIOUS_SIZE,
+};
+#[cfg(feature = "telemetry")]
 use reqwest::Client;
-use serde::Deserialize;
-use std::collections::{HashMap, VecDeque};
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
-use std::sync::{Arc, OnceLock};
-use std::time::Instant;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::runtime::Runtime;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum Platform {
-    WinX86,
-    WinX64,
-    WinArm64,
-    MacOsIntel,
-    MacOsArm64,
+#[derive(Deserialize)]
+struct VersionData {
+    fullversion: Option<String>,
 }
 
-impl Platform {
-    fn name(&self) -> &str {
-        match self {
-            Platform::WinX86 => "Windows x86",
-            Platform::WinX64 => "Windows x64",
-            Platform::WinArm64 => "Windows arm64",
-            Platform::MacOsIntel => "macOS intel",
-            Platform::MacOsArm64 => "macOS arm64",
-        }
+/// "42s", "3m 05s" or "1h 02m".
+fn format_eta(secs: u64) -> String {
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m {:02}s", secs / 60, secs % 60),
+        _ => format!("{}h {:02}m", secs / 3600, secs % 3600 / 60),
     }
+}
 
-    fn generate_path(&self, version: &str, number: i32) -> String {
-        match self {
-            Platform::WinX86 => format!("win32-x86/spotify_installer-{version}-{number}.exe"),
-            Platform::WinX64 => format!("win32-x86_64/spotify_installer-{version}-{number}.exe"),
-            Platform::WinArm64 => format!("win32-arm64/spotify_installer-{version}-{number}.exe"),
-            Platform::MacOsIntel => format!("osx-x86_64/spotify-autoupdate-{version}-{number}.tbz"),
-            Platform::MacOsArm64 => format!("osx-arm64/spotify-autoupdate-{version}-{number}.tbz"),
-        }
+/// "94.0 MB, 2024-03-01T12:34:56Z" after a found URL; the date is left out
+/// when the server sent no `Last-Modified`. A file too small to be a real
+/// installer is flagged.
+fn installer_details(size: Option<u64>, last_modified: Option<&str>) -> String {
+    let mut details = format_size(size);
+    if is_suspiciously_small(size) {
+        details.push_str(", suspiciously small");
     }
-
-    fn all() -> Vec<Platform> {
-        vec![
-            Platform::WinX86,
-            Platform::WinX64,
-            Platform::WinArm64,
-            Platform::MacOsIntel,
-            Platform::MacOsArm64,
-        ]
+    if let Some(date) = last_modified {
+        details.push_str(", ");
+        details.push_str(date);
     }
+    details
 }
 
-#[derive(Deserialize)]
-struct VersionData {
-    fullversion: Option<String>,
+/// Colors of the results panel, readable on its gray-20 background.
+const HEADER_COLOR: egui::Color32 = egui::Color32::from_rgb(190, 150, 255);
+const URL_COLOR: egui::Color32 = egui::Color32::from_rgb(110, 190, 255);
+const WARNING_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 200, 0);
+const ERROR_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 80, 80);
+
+/// Colors each line of the results text by what it is: platform headers,
+/// URLs with their details in gray, warnings and errors. Works on a
+/// partly revealed line too, so the colors appear as it types.
+fn results_layout_job(text: &str, font_id: egui::FontId, text_color: egui::Color32) -> LayoutJob {
+    let mut job = LayoutJob::default();
+    let mut append = |text: &str, color: egui::Color32| {
+        job.append(text, 0.0, TextFormat::simple(font_id.clone(), color));
+    };
+    for line in text.split_inclusive('\n') {
+        let content = line.trim_end();
+        if content.starts_with("http") {
+            match line.find(" (") {
+                Some(details) => {
+                    append(&line[..details], URL_COLOR);
+                    append(&line[details..], egui::Color32::GRAY);
+                }
+                None => append(line, URL_COLOR),
+            }
+            continue;
+        }
+        let color = if content.starts_with("Error") {
+            ERROR_COLOR
+        } else if content.starts_with("Warning")
+            || content.starts_with("Nothing")
+            || content.starts_with("Missed builds")
+        {
+            WARNING_COLOR
+        } else if content == "Searched:"
+            || Platform::all()
+                .iter()
+                .any(|platform| content.strip_suffix(':') == Some(platform.name()))
+        {
+            HEADER_COLOR
+        } else {
+            text_color
+        };
+        append(line, color);
+    }
+    job
 }
 
-struct UrlGenerator;
-
-impl UrlGenerator {
-    const BASE_URL: &'static str = "https://upgrade.scdn.co/upgrade/client/";
+fn format_size(size: Option<u64>) -> String {
+    let Some(bytes) = size else {
+        return "unknown".to_string();
+    };
 
-    fn generate_url(platform: Platform, version: &str, number: i32) -> String {
-        format!(
-            "{}{}",
-            Self::BASE_URL,
-            platform.generate_path(version, number)
-        )
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
     }
-}
 
-#[allow(dead_code)]
-fn extract_base_version(version: &str) -> String {
-    let parts: Vec<&str> = version.split('.').collect();
-    if parts.len() >= 3 {
-        format!("{}.{}.{}", parts[0], parts[1], parts[2])
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
     } else {
-        version.to_string()
+        format!("{:.1} {}", value, UNITS[unit])
     }
 }
 
-#[allow(dead_code)]
-fn should_use_win_x86(version: &str) -> bool {
-    let mut parts = version.split('.');
+const MAX_CONNECTION_OPTIONS: [usize; 6] = [50, 100, 150, 200, 250, 300];
 
-    if let (Some(p1), Some(p2), Some(p3)) = (parts.next(), parts.next(), parts.next()) {
-        if let (Ok(major), Ok(minor), Ok(patch)) =
-            (p1.parse::<u32>(), p2.parse::<u32>(), p3.parse::<u32>())
-        {
-            return (major, minor, patch) <= (1, 2, 53);
+const SETTINGS_KEY: &str = "settings";
+const HISTORY_KEY: &str = "history";
+/// Older searches are dropped from the history beyond this many.
+const MAX_HISTORY: usize = 20;
+const FAVORITES_KEY: &str = "favorites";
+const APP_ID: &str = "LoaderSpot";
+const RESULTS_CACHE_FILE: &str = "last_results.json";
+const CHECKPOINT_FILE: &str = "checkpoint.json";
+/// `schema_version` of a results JSON file, the same as the CLI's output.
+const RESULTS_SCHEMA_VERSION: u32 = 1;
+/// How often a running search saves its checkpoint.
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Longest a report of an unknown version may take.
+#[cfg(feature = "telemetry")]
+const FORM_TIMEOUT: Duration = Duration::from_secs(10);
+/// Installers are large; the 10s scan timeout would cut downloads short.
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+/// The request rate shown while searching is averaged over this much time.
+const RATE_WINDOW: Duration = Duration::from_secs(5);
+/// Combined width of the two input columns at the minimum window size.
+const MIN_COLUMNS_WIDTH: f32 = 700.0;
+/// Frame margins, strokes and spacing around the two input columns.
+const COLUMN_CHROME: f32 = 33.0;
+
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+struct Settings {
+    range_from: String,
+    range_to: String,
+    /// From/To rows added below the first one.
+    extra_ranges: Vec<(String, String)>,
+    smart_search: bool,
+    build_hints: bool,
+    max_gap_enabled: bool,
+    max_gap: u32,
+    step: u32,
+    max_connections_index: usize,
+    platform_win_x86: bool,
+    platform_win_x64: bool,
+    platform_win_arm64: bool,
+    platform_macos_intel: bool,
+    platform_macos_arm64: bool,
+    report_unknown: bool,
+    only_new: bool,
+    reveal_speed_ms: u64,
+    instant_results: bool,
+    strict_version: bool,
+    diagnostics: bool,
+    notify_when_done: bool,
+    latest_only: bool,
+    counts_only: bool,
+    unique_builds: bool,
+    remember_versions: bool,
+    skip_small: bool,
+    require_content_type: bool,
+    versions_input: String,
+    proxy: String,
+    local_address: String,
+    ip_family: IpFamily,
+    user_agent: String,
+    headers: String,
+    ca_cert: String,
+    http2_prior_knowledge: bool,
+    compression: bool,
+    limit_idle_connections: bool,
+    max_idle_connections: usize,
+    idle_timeout_secs: u64,
+    custom_connections: String,
+    rps_limit_enabled: bool,
+    rps_limit: u32,
+    jitter_ms: u64,
+    ramp_connections: bool,
+    pause_on_network_errors: bool,
+    network_error_percent: u8,
+    max_requests: u64,
+    download_dir: String,
+    downloader: Downloader,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            range_from: "0".to_string(),
+            range_to: "5000".to_string(),
+            extra_ranges: Vec::new(),
+            smart_search: false,
+            build_hints: false,
+            max_gap_enabled: false,
+            max_gap: 500,
+            step: 1,
+            max_connections_index: 1,
+            platform_win_x86: false,
+            platform_win_x64: false,
+            platform_win_arm64: false,
+            platform_macos_intel: false,
+            platform_macos_arm64: false,
+            report_unknown: false,
+            only_new: false,
+            reveal_speed_ms: 8,
+            instant_results: false,
+            strict_version: false,
+            diagnostics: false,
+            notify_when_done: false,
+            latest_only: false,
+            counts_only: false,
+            unique_builds: false,
+            remember_versions: false,
+            skip_small: false,
+            require_content_type: false,
+            versions_input: String::new(),
+            proxy: String::new(),
+            local_address: String::new(),
+            ip_family: IpFamily::Any,
+            user_agent: String::new(),
+            headers: String::new(),
+            ca_cert: String::new(),
+            http2_prior_knowledge: false,
+            compression: true,
+            limit_idle_connections: false,
+            max_idle_connections: 32,
+            idle_timeout_secs: DEFAULT_POOL_IDLE_TIMEOUT.as_secs(),
+            custom_connections: String::new(),
+            rps_limit_enabled: false,
+            rps_limit: 50,
+            jitter_ms: 0,
+            ramp_connections: false,
+            pause_on_network_errors: true,
+            network_error_percent: ErrorStorm::DEFAULT_THRESHOLD_PERCENT,
+            max_requests: DEFAULT_MAX_REQUESTS,
+            download_dir: String::new(),
+            downloader: Downloader::Curl,
         }
     }
-    true
 }
 
-fn validate_version(version: &str) -> bool {
-    static RE: OnceLock<regex::Regex> = OnceLock::new();
-    let re = RE.get_or_init(|| regex::Regex::new(r"^\d+\.\d+\.\d+\.\d+\.g[0-9a-f]{8}$").unwrap());
-    re.is_match(version)
+/// A past search, kept so its inputs can be filled back into the form.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+struct HistoryEntry {
+    versions: Vec<String>,
+    start: i32,
+    end: i32,
+    /// Ranges after `start..=end`, for searches of several bands.
+    #[serde(default)]
+    extra_ranges: Vec<(i32, i32)>,
+    smart_search: bool,
+    platforms: Vec<Platform>,
+    /// Seconds since the Unix epoch.
+    searched_at: u64,
 }
 
-fn short_version(version: &str) -> String {
-    if let Some(pos) = version.find(".g") {
-        return version[..pos].to_string();
+impl HistoryEntry {
+    /// Same inputs, ignoring when the search ran.
+    fn same_search(&self, other: &HistoryEntry) -> bool {
+        self.versions == other.versions
+            && self.start == other.start
+            && self.end == other.end
+            && self.extra_ranges == other.extra_ranges
+            && self.smart_search == other.smart_search
+            && self.platforms == other.platforms
     }
 
-    let parts: Vec<&str> = version.split('.').collect();
-    let take = parts.len().min(4);
-    parts[..take].join(".")
+    fn label(&self) -> String {
+        let versions = match self.versions.len() {
+            1 => short_version(&self.versions[0]),
+            n => format!("{} +{} more", short_version(&self.versions[0]), n - 1),
+        };
+        let range = if self.smart_search {
+            "smart".to_string()
+        } else {
+            std::iter::once(&(self.start, self.end))
+                .chain(&self.extra_ranges)
+                .map(|(start, end)| format!("{}-{}", start, end))
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        let platforms = self
+            .platforms
+            .iter()
+            .map(|p| p.name())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let age = Duration::from_secs(unix_now().saturating_sub(self.searched_at));
+        format!(
+            "{}  [{}]  {} ({})",
+            versions,
+            range,
+            platforms,
+            format_age(age)
+        )
+    }
 }
 
-const MAX_CONNECTION_OPTIONS: [usize; 6] = [50, 100, 150, 200, 250, 300];
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
 
-async fn check_url(client: &Client, url: String, platform: Platform) -> Option<(String, Platform)> {
-    match client.head(&url).send().await {
-        Ok(response) => {
-            if response.status().is_success() {
-                Some((url, platform))
-            } else if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
-                eprintln!(
-                    "RATE LIMITED (429)! Server blocked the request for: {}",
-                    url
-                );
-                None
-            } else {
-                None
-            }
-        }
-        Err(e) => {
-            eprintln!("Network error: {}", e);
-            None
-        }
-    }
+/// The results cache lives next to eframe's settings file.
+fn results_cache_path() -> Option<PathBuf> {
+    eframe::storage_dir(APP_ID).map(|dir| dir.join(RESULTS_CACHE_FILE))
 }
 
-async fn fetch_versions_json(client: &Client) -> HashMap<String, VersionData> {
-    let url =
-        "https://raw.githubusercontent.com/LoaderSpot/LoaderSpot/refs/heads/main/versions.json";
+/// Progress of the running search, so it can be resumed after a crash or
+/// after closing the window mid-search.
+fn checkpoint_path() -> Option<PathBuf> {
+    eframe::storage_dir(APP_ID).map(|dir| dir.join(CHECKPOINT_FILE))
+}
 
-    match client.get(url).send().await {
-        Ok(response) if response.status().is_success() => response
-            .json::<HashMap<String, VersionData>>()
-            .await
-            .unwrap_or_default(),
-        _ => HashMap::new(),
-    }
+/// Shows a desktop notification from a background thread, as the backends
+/// may block. Failures, e.g. no notification daemon running, are only logged.
+fn notify_desktop(body: String) {
+    std::thread::spawn(move || {
+        let shown = notify_rust::Notification::new()
+            .appname(APP_ID)
+            .summary("Search finished")
+            .body(&body)
+            .show();
+        if let Err(e) = shown {
+            log::debug!("No desktop notification: {}", e);
+        }
+    });
+}
+
+/// Whether eframe has saved settings, and with them the window geometry.
+fn has_saved_settings() -> bool {
+    eframe::storage_dir(APP_ID).is_some_and(|dir| dir.join("app.ron").is_file())
 }
 
+/// Sends `version`, and nothing else, to the project's Google Form.
+#[cfg(feature = "telemetry")]
 async fn submit_to_google_form(client: &Client, version: &str) {
     let form_url = "https://docs.google.com/forms/u/0/d/e/1FAIpQLSdqIxSjqt2PcjBlQzhvwqc4QckfWuq5qqWsrdpoTidQHsPGpw/formResponse";
 
@@ -156,120 +364,218 @@ async fn submit_to_google_form(client: &Client, version: &str) {
         ("entry.1319854718", "from LoaderSpot"),
     ];
 
-    let _ = client.post(form_url).form(&params).send().await;
+    let sent = client
+        .post(form_url)
+        .form(&params)
+        .timeout(FORM_TIMEOUT)
+        .send()
+        .await;
+    if let Err(e) = sent {
+        log::debug!("Cannot report {}: {}", version, e);
+    }
 }
 
-async fn check_version_and_submit(client: &Client, version: &str) {
-    let versions_json = fetch_versions_json(client).await;
-
-    let version_exists = versions_json
-        .values()
-        .any(|v| v.fullversion.as_ref().is_some_and(|fv| fv == version));
-
-    if !version_exists {
-        submit_to_google_form(client, version).await;
+/// Reports `version` when versions.json doesn't list it, on a task of its
+/// own so a slow form never holds up the search. `pending` counts the
+/// reports still being sent.
+#[cfg(feature = "telemetry")]
+fn check_version_and_submit(
+    client: &Client,
+    known: &KnownVersions,
+    version: &str,
+    pending: &Arc<AtomicUsize>,
+) {
+    if known.contains(version) {
+        return;
     }
+    let client = client.clone();
+    let version = version.to_string();
+    let pending = pending.clone();
+    pending.fetch_add(1, Ordering::Relaxed);
+    tokio::spawn(async move {
+        submit_to_google_form(&client, &version).await;
+        pending.fetch_sub(1, Ordering::Relaxed);
+    });
 }
 
-enum SearchMessage {
-    Result(String, Platform),
-    Complete(String),
-    VersionStart(String, usize, usize),
-    CompleteAll,
+/// Version lines of a dropped file: the `fullversion` fields of a
+/// versions.json-shaped file, otherwise one version per line.
+fn versions_from_file(name: &str, text: &str) -> Result<Vec<String>, String> {
+    if name.to_ascii_lowercase().ends_with(".json") {
+        let known: HashMap<String, VersionData> = serde_json::from_str(text)
+            .map_err(|e| format!("{} is not versions.json: {}", name, e))?;
+        let mut versions: Vec<String> = known.into_values().filter_map(|v| v.fullversion).collect();
+        versions.sort();
+        return Ok(versions);
+    }
+    Ok(version_list_lines(text).map(str::to_string).collect())
 }
 
-struct SearchOptions {
-    client: Client,
-    version: String,
-    start: i32,
-    end: i32,
+/// Validated inputs of a search, see `SpotifyFinderApp::search_plan`.
+struct SearchPlan {
+    versions: Vec<String>,
+    /// Merged, so no build is listed twice.
+    ranges: Vec<(i32, i32)>,
     platforms: Vec<Platform>,
-    max_connections: usize,
-    tx: Sender<SearchMessage>,
-    pause_flag: Arc<AtomicBool>,
-    cancel_flag: Arc<AtomicBool>,
-    processed: Arc<AtomicU64>,
 }
 
-async fn search_installers(opts: SearchOptions) {
-    use tokio::sync::Semaphore;
-
-    let semaphore = Arc::new(Semaphore::new(opts.max_connections));
-    let mut tasks = Vec::new();
-
-    'outer: for platform in opts.platforms {
-        for number in opts.start..=opts.end {
-            if opts.cancel_flag.load(Ordering::Relaxed) {
-                break 'outer;
-            }
+/// A build number box of the range rows.
+fn range_field(ui: &mut egui::Ui, text: &mut String) {
+    let desired = egui::Vec2::new(110.0, ui.spacing().interact_size.y);
+    let (rect, _resp) = ui.allocate_exact_size(desired, egui::Sense::click());
+    ui.painter()
+        .rect_filled(rect, 4.0, egui::Color32::from_gray(20));
+    ui.painter().rect_stroke(
+        rect,
+        4.0,
+        egui::Stroke::new(1.0, egui::Color32::from_gray(80)),
+    );
+    ui.put(rect, egui::TextEdit::singleline(text).frame(false));
+}
 
-            while opts.pause_flag.load(Ordering::Relaxed) {
-                if opts.cancel_flag.load(Ordering::Relaxed) {
-                    break 'outer;
-                }
-                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
-            }
+/// Windows x86 is skipped for versions that no longer ship it, unless it is
+/// the only platform selected.
+fn platforms_for_version(base_platforms: &[Platform], version: &str) -> Vec<Platform> {
+    let mut platforms = base_platforms.to_vec();
+    if platforms.contains(&Platform::WinX86) && platforms.len() > 1 && !should_use_win_x86(version)
+    {
+        platforms.retain(|p| *p != Platform::WinX86);
+    }
+    platforms
+}
 
-            let permit = match semaphore.clone().acquire_owned().await {
-                Ok(p) => p,
-                Err(_) => break 'outer,
-            };
+/// Builds checked per platform by a search of `params`: the ladder's most,
+/// or every `step`th build of the ranges.
+fn builds_per_platform(params: &SearchParams) -> u64 {
+    match &params.ladder {
+        Some(ladder) => ladder.max_builds().div_ceil(params.step as u64),
+        None => count_builds(&params.ranges, params.step),
+    }
+}
 
-            while opts.pause_flag.load(Ordering::Relaxed) {
-                if opts.cancel_flag.load(Ordering::Relaxed) {
-                    break 'outer;
-                }
-                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
-            }
+/// The builds a search of `versions` checks, counted up front for the
+/// progress bar.
+#[derive(Debug)]
+struct SearchWork {
+    per_platform: HashMap<Platform, u64>,
+    /// Ladder, hinted and gap-bounded searches may stop early, and
+    /// --only-new and a resume skip builds, so only a plain fresh search
+    /// checks exactly `total()`.
+    exact: bool,
+}
 
-            if opts.cancel_flag.load(Ordering::Relaxed) {
-                break 'outer;
+impl SearchWork {
+    fn new(params: &SearchParams, versions: &[String], only_new: bool, resuming: bool) -> Self {
+        let builds = builds_per_platform(params);
+        let mut per_platform: HashMap<Platform, u64> = HashMap::new();
+        for version in versions {
+            for platform in platforms_for_version(&params.platforms, version) {
+                let total = per_platform.entry(platform).or_default();
+                *total = total.saturating_add(builds);
             }
-
-            let url = UrlGenerator::generate_url(platform, &opts.version, number);
-            let client = opts.client.clone();
-            let tx_clone = opts.tx.clone();
-            let processed_clone = opts.processed.clone();
-            let cancel_local = opts.cancel_flag.clone();
-
-            let task = tokio::spawn(async move {
-                let _permit = permit;
-
-                if cancel_local.load(Ordering::Relaxed) {
-                    return;
-                }
-
-                let result = check_url(&client, url, platform).await;
-                processed_clone.fetch_add(1, Ordering::Relaxed);
-
-                if let Some((url, platform)) = result {
-                    let _ = tx_clone.send(SearchMessage::Result(url, platform));
-                }
-            });
-
-            tasks.push(task);
+        }
+        Self {
+            per_platform,
+            exact: params.ladder.is_none()
+                && !params.build_hints
+                && params.max_gap.is_none()
+                && !only_new
+                && !resuming,
         }
     }
 
-    if opts.cancel_flag.load(Ordering::Relaxed) {
-        for task in &tasks {
-            task.abort();
-        }
+    fn total(&self) -> u64 {
+        self.per_platform.values().sum()
     }
+}
 
-    for task in tasks {
-        let _ = task.await;
+/// Whether a finished, uncancelled search's `processed` count accounts for
+/// its `total_work`; one that is only an upper bound may fall short.
+fn processed_all(total_work: u64, total_work_is_exact: bool, processed: u64) -> bool {
+    if total_work_is_exact {
+        processed == total_work
+    } else {
+        processed <= total_work
     }
+}
+
+/// A search over `max_requests`, held back until it is confirmed.
+struct LargeSearch {
+    requests: u64,
+    estimate: Duration,
+    resumed: Option<Checkpoint>,
+}
+
+enum SearchMessage {
+    Result(FoundEvent),
+    Complete(String),
+    VersionStart(String, usize, usize),
+    /// What the search of a version covered, sent before its `Complete`.
+    Searched(SearchedVersion),
+    /// Skipped, as versions.json already lists it.
+    AlreadyKnown(String),
+    /// The run could not start; nothing was searched.
+    Failed(String),
+    CompleteAll,
+    /// A "Check URLs" run ended, with every URL it got to.
+    UrlsChecked(Vec<(String, Probe)>),
+    /// A "Rescan failures" run of that many checks ended; its hits came
+    /// as `Result`s.
+    Rescanned(usize, Rescan),
+}
+
+enum DownloadMessage {
+    Progress(String, u64, Option<u64>),
+    Finished(String, Result<PathBuf, String>),
+}
 
-    let _ = opts.tx.send(SearchMessage::Complete(opts.version));
+enum DownloadState {
+    Running(u64, Option<u64>),
+    Done(PathBuf),
+    Failed(String),
 }
 
 struct SpotifyFinderApp {
     runtime: Runtime,
     versions_input: String,
+    /// Candidate installer URLs for "Check URLs", one per line.
+    url_list: String,
     range_from: String,
     range_to: String,
+    /// From/To rows added below the first one.
+    extra_ranges: Vec<(String, String)>,
+    smart_search: bool,
+    /// Scan near the builds of known neighbouring versions first.
+    build_hints: bool,
+    /// Stop a platform after `max_gap` misses past its last hit.
+    max_gap_enabled: bool,
+    max_gap: u32,
+    /// Check only every `step`th build, for a quick sweep of a wide range.
+    step: u32,
     max_connections_index: usize,
+    custom_connections: String,
+    /// Cap on requests per second, on top of the connection limit.
+    rps_limit_enabled: bool,
+    rps_limit: u32,
+    /// Random wait of up to this many milliseconds before each request.
+    jitter_ms: u64,
+    /// Open the connections gradually at the start of a search.
+    ramp_connections: bool,
+    /// Pause when more than `network_error_percent` of the recent checks got
+    /// no response.
+    pause_on_network_errors: bool,
+    network_error_percent: u8,
+    /// The current run's, shared by all its engines.
+    error_storm: ErrorStorm,
+    /// The current run's; stops it when the server can't be reached at all.
+    reachability: Reachability,
+    /// Searches sending more requests need confirming first.
+    max_requests: u64,
+    /// Waiting for "Run anyway" in the confirmation dialog.
+    large_search: Option<LargeSearch>,
+    /// Set by "Run anyway" for the one `search` call that follows.
+    large_search_confirmed: bool,
+    settings_open: bool,
 
     platform_win_x86: bool,
     platform_win_x64: bool,
@@ -278,6 +584,36 @@ struct SpotifyFinderApp {
     platform_macos_arm64: bool,
 
     report_unknown: bool,
+    /// Skip versions already in versions.json.
+    only_new: bool,
+    remember_versions: bool,
+    /// Treat found files under `SUSPICIOUS_SIZE` as missing.
+    skip_small: bool,
+    /// Treat found files served as something other than an installer, such
+    /// as an HTML error page, as missing.
+    require_content_type: bool,
+    proxy: String,
+    local_address: String,
+    ip_family: IpFamily,
+    user_agent: String,
+    /// Extra request headers, one `Name: Value` per line.
+    headers: String,
+    /// Extra root certificate to trust, PEM or DER.
+    ca_cert: String,
+    /// Deliberately not part of [`Settings`], so it is off again after a restart.
+    accept_invalid_certs: bool,
+    /// Only for plain-HTTP mirrors that speak HTTP/2 (h2c).
+    http2_prior_knowledge: bool,
+    /// Accept compressed bodies for versions.json and downloads.
+    compression: bool,
+    /// Idle connections kept per host; every one when unchecked.
+    limit_idle_connections: bool,
+    max_idle_connections: usize,
+    /// 0 keeps idle connections until the server closes them.
+    idle_timeout_secs: u64,
+    download_dir: String,
+    /// Tool used by "Copy as commands".
+    downloader: Downloader,
 
     is_searching: bool,
     is_paused: bool,
@@ -290,32 +626,131 @@ struct SpotifyFinderApp {
     reveal_pos: usize,
     last_reveal: Instant,
     reveal_speed_ms: u64,
+    instant_results: bool,
+    strict_version: bool,
+    diagnostics: bool,
+    /// Show a desktop notification once a search completes.
+    notify_when_done: bool,
+    latest_only: bool,
+    /// List how many builds each platform has instead of their URLs.
+    counts_only: bool,
+    /// List the distinct build numbers each platform has across all
+    /// versions instead of their URLs.
+    unique_builds: bool,
     progress: f32,
     progress_text: String,
     total_work: u64,
+    /// Ladder searches stop early, so `total_work` is only an upper bound.
+    total_work_is_exact: bool,
 
     processed_global: Arc<AtomicU64>,
+    search_started: Instant,
+    /// (time, processed_global) samples from the last `RATE_WINDOW`.
+    rate_samples: VecDeque<(Instant, u64)>,
+    platform_processed: PlatformProgress,
+    /// Statuses of the missed builds, tallied when `diagnostics` is on.
+    status_counts: StatusCounts,
+    /// Unexpected `Content-Type`s of the hits, shown with `diagnostics`.
+    unexpected_types: ContentTypeCounts,
+    /// Shared by the engines of every version, for the run report.
+    request_counts: RequestCounts,
+    network_errors: Arc<AtomicU64>,
+    /// Reports of unknown versions still being sent.
+    reports_pending: Arc<AtomicUsize>,
+    /// Summary of the last run, filled in once it completes or is stopped.
+    run_report: Option<RunReport>,
+    /// (processed, total) for every platform of the current search.
+    platform_progress: HashMap<Platform, (u64, u64)>,
+    backoff: Backoff,
+    /// Pass in which the clipboard was requested; its text arrives as a
+    /// paste event in the next one.
+    clipboard_requested: Option<u64>,
+    /// Versions of the current search skipped by `only_new`.
+    known_skipped: usize,
+    /// Progress of the current search, saved every `CHECKPOINT_INTERVAL`.
+    checkpoint: Option<Checkpoint>,
+    /// Next build per platform; only range searches check builds in order.
+    checked_builds: Option<CheckedBuilds>,
+    /// Checks of the last search that got no response, for "Rescan failures".
+    failed_checks: FailedChecks,
+    last_checkpoint_save: Instant,
+    /// Left behind by a search that was interrupted, offered on startup.
+    resumable: Option<Checkpoint>,
 
     rx: Option<Receiver<SearchMessage>>,
     found_urls: HashMap<Platform, Vec<String>>,
+    /// Recent searches, newest first.
+    history: Vec<HistoryEntry>,
+    /// Pinned versions, shown as chips above the versions box. Unlike the
+    /// history they only change when pinned or unpinned.
+    favorites: Vec<String>,
+    searched_versions: Vec<String>,
+    found: Vec<CachedInstaller>,
+    /// Builds and platforms each version of the current search covered.
+    coverage: Vec<SearchedVersion>,
+    /// Line number and text of each version the last search plan rejected.
+    rejected_versions: Vec<(usize, String)>,
     pause_flag: Arc<AtomicBool>,
     cancel_flag: Arc<AtomicBool>,
+
+    download_tx: Sender<DownloadMessage>,
+    download_rx: Receiver<DownloadMessage>,
+    downloads: HashMap<String, DownloadState>,
 }
 
 impl Default for SpotifyFinderApp {
     fn default() -> Self {
+        let (download_tx, download_rx) = unbounded();
         Self {
             runtime: Runtime::new().unwrap(),
             versions_input: String::new(),
+            url_list: String::new(),
             range_from: "0".to_string(),
             range_to: "5000".to_string(),
+            extra_ranges: Vec::new(),
+            smart_search: false,
+            build_hints: false,
+            max_gap_enabled: false,
+            max_gap: 500,
+            step: 1,
             max_connections_index: 1,
+            custom_connections: String::new(),
+            rps_limit_enabled: false,
+            rps_limit: 50,
+            jitter_ms: 0,
+            ramp_connections: false,
+            pause_on_network_errors: true,
+            network_error_percent: ErrorStorm::DEFAULT_THRESHOLD_PERCENT,
+            error_storm: ErrorStorm::default(),
+            reachability: Reachability::default(),
+            max_requests: DEFAULT_MAX_REQUESTS,
+            large_search: None,
+            large_search_confirmed: false,
+            settings_open: false,
             platform_win_x86: false,
             platform_win_x64: false,
             platform_win_arm64: false,
             platform_macos_intel: false,
             platform_macos_arm64: false,
             report_unknown: false,
+            only_new: false,
+            remember_versions: false,
+            skip_small: false,
+            require_content_type: false,
+            proxy: String::new(),
+            local_address: String::new(),
+            ip_family: IpFamily::Any,
+            user_agent: String::new(),
+            headers: String::new(),
+            ca_cert: String::new(),
+            accept_invalid_certs: false,
+            http2_prior_knowledge: false,
+            compression: true,
+            limit_idle_connections: false,
+            max_idle_connections: 32,
+            idle_timeout_secs: DEFAULT_POOL_IDLE_TIMEOUT.as_secs(),
+            download_dir: String::new(),
+            downloader: Downloader::Curl,
             is_searching: false,
             is_paused: false,
             displayed_results: String::new(),
@@ -324,22 +759,422 @@ impl Default for SpotifyFinderApp {
             reveal_pos: 0,
             last_reveal: Instant::now(),
             reveal_speed_ms: 8,
+            instant_results: false,
+            strict_version: false,
+            diagnostics: false,
+            notify_when_done: false,
+            latest_only: false,
+            counts_only: false,
+            unique_builds: false,
             progress: 0.0,
             progress_text: String::new(),
             total_work: 0,
+            total_work_is_exact: true,
             processed_global: Arc::new(AtomicU64::new(0)),
+            search_started: Instant::now(),
+            rate_samples: VecDeque::new(),
+            platform_processed: PlatformProgress::default(),
+            status_counts: StatusCounts::default(),
+            unexpected_types: ContentTypeCounts::default(),
+            request_counts: RequestCounts::default(),
+            network_errors: Arc::new(AtomicU64::new(0)),
+            reports_pending: Arc::new(AtomicUsize::new(0)),
+            run_report: None,
+            platform_progress: HashMap::new(),
+            backoff: Backoff::default(),
+            clipboard_requested: None,
+            known_skipped: 0,
+            checkpoint: None,
+            checked_builds: None,
+            failed_checks: FailedChecks::default(),
+            last_checkpoint_save: Instant::now(),
+            resumable: None,
             rx: None,
             found_urls: HashMap::new(),
+            history: Vec::new(),
+            favorites: Vec::new(),
+            searched_versions: Vec::new(),
+            found: Vec::new(),
+            coverage: Vec::new(),
+            rejected_versions: Vec::new(),
             pause_flag: Arc::new(AtomicBool::new(false)),
             cancel_flag: Arc::new(AtomicBool::new(false)),
             current_version: None,
             current_version_index: 0,
             total_versions: 0,
+            download_tx,
+            download_rx,
+            downloads: HashMap::new(),
         }
     }
 }
 
 impl SpotifyFinderApp {
+    fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        let settings: Settings = cc
+            .storage
+            .and_then(|storage| eframe::get_value(storage, SETTINGS_KEY))
+            .unwrap_or_default();
+
+        let mut app = Self::default();
+        app.apply_settings(settings);
+        app.history = cc
+            .storage
+            .and_then(|storage| eframe::get_value(storage, HISTORY_KEY))
+            .unwrap_or_default();
+        app.history.truncate(MAX_HISTORY);
+        app.favorites = cc
+            .storage
+            .and_then(|storage| eframe::get_value(storage, FAVORITES_KEY))
+            .unwrap_or_default();
+        if let Some(path) = checkpoint_path().filter(|path| path.is_file()) {
+            match Checkpoint::load(&path) {
+                Ok(checkpoint) => app.resumable = Some(checkpoint),
+                Err(e) => eprintln!("{}", e),
+            }
+        }
+        app
+    }
+
+    /// Puts a search at the top of the history, replacing an identical older one.
+    fn record_history(&mut self, entry: HistoryEntry) {
+        self.history.retain(|old| !old.same_search(&entry));
+        self.history.insert(0, entry);
+        self.history.truncate(MAX_HISTORY);
+    }
+
+    /// Fills the form with a past search without starting it.
+    fn apply_history(&mut self, entry: &HistoryEntry) {
+        self.versions_input = entry.versions.join("\n");
+        self.range_from = entry.start.to_string();
+        self.range_to = entry.end.to_string();
+        self.extra_ranges = entry
+            .extra_ranges
+            .iter()
+            .map(|(start, end)| (start.to_string(), end.to_string()))
+            .collect();
+        self.smart_search = entry.smart_search;
+        self.platform_win_x86 = entry.platforms.contains(&Platform::WinX86);
+        self.platform_win_x64 = entry.platforms.contains(&Platform::WinX64);
+        self.platform_win_arm64 = entry.platforms.contains(&Platform::WinArm64);
+        self.platform_macos_intel = entry.platforms.contains(&Platform::MacOsIntel);
+        self.platform_macos_arm64 = entry.platforms.contains(&Platform::MacOsArm64);
+    }
+
+    /// Appends the valid `versions` to the versions box, skipping ones
+    /// already there. Returns how many were added and how many were invalid.
+    fn append_versions(&mut self, versions: Vec<String>) -> (usize, usize) {
+        let validate = if self.strict_version {
+            validate_version_strict
+        } else {
+            validate_version
+        };
+
+        let mut existing: Vec<String> = version_list_lines(&self.versions_input)
+            .map(str::to_string)
+            .collect();
+        let (mut added, mut invalid) = (0, 0);
+        for version in versions {
+            if !validate(&version) {
+                invalid += 1;
+            } else if !existing.contains(&version) {
+                if !self.versions_input.is_empty() && !self.versions_input.ends_with('\n') {
+                    self.versions_input.push('\n');
+                }
+                self.versions_input.push_str(&version);
+                self.versions_input.push('\n');
+                existing.push(version);
+                added += 1;
+            }
+        }
+        (added, invalid)
+    }
+
+    /// Appends the valid versions of dropped `.txt`/`.json` files to the
+    /// versions box, skipping ones already there.
+    fn load_dropped_versions(&mut self, files: &[egui::DroppedFile]) {
+        let mut report = Vec::new();
+        for file in files {
+            let name = file
+                .path
+                .as_deref()
+                .and_then(|p| p.file_name())
+                .map_or(file.name.clone(), |n| n.to_string_lossy().into_owned());
+            let text = match (&file.bytes, &file.path) {
+                (Some(bytes), _) => Ok(String::from_utf8_lossy(bytes).into_owned()),
+                (None, Some(path)) => std::fs::read_to_string(path).map_err(|e| e.to_string()),
+                (None, None) => Err("no contents".to_string()),
+            };
+            let versions = match text.and_then(|text| versions_from_file(&name, &text)) {
+                Ok(versions) => versions,
+                Err(e) => {
+                    report.push(format!("Error: cannot load {}: {}", name, e));
+                    continue;
+                }
+            };
+
+            let (added, invalid) = self.append_versions(versions);
+            report.push(format!(
+                "Loaded {} valid version(s) from {}{}",
+                added,
+                name,
+                if invalid > 0 {
+                    format!(", skipped {} invalid", invalid)
+                } else {
+                    String::new()
+                }
+            ));
+        }
+        self.displayed_results = report.join("\n");
+    }
+
+    /// Appends the versions found anywhere in pasted text, e.g. a line of a
+    /// changelog, to the versions box.
+    fn load_pasted_versions(&mut self, text: &str) {
+        let versions: Vec<String> = find_versions(text).map(str::to_string).collect();
+        if versions.is_empty() {
+            self.displayed_results = "No version found in the clipboard".to_string();
+            return;
+        }
+        let (added, invalid) = self.append_versions(versions);
+        self.displayed_results = format!(
+            "Added {} version(s) from the clipboard{}",
+            added,
+            if invalid > 0 {
+                format!(", skipped {} invalid", invalid)
+            } else {
+                String::new()
+            }
+        );
+    }
+
+    /// Pins the valid versions in the versions box that aren't pinned yet.
+    fn pin_versions(&mut self) {
+        let validate = if self.strict_version {
+            validate_version_strict
+        } else {
+            validate_version
+        };
+
+        let mut added = 0;
+        for version in version_list_lines(&self.versions_input) {
+            if validate(version) && !self.favorites.iter().any(|pinned| pinned == version) {
+                self.favorites.push(version.to_string());
+                added += 1;
+            }
+        }
+        self.displayed_results = if added == 0 {
+            "No new valid version to pin".to_string()
+        } else {
+            format!("Pinned {} version(s)", added)
+        };
+    }
+
+    /// One chip per pinned version: clicking it adds the version to the
+    /// versions box, its ✖ unpins it.
+    fn favorites_row(&mut self, ui: &mut egui::Ui) {
+        if self.favorites.is_empty() {
+            return;
+        }
+
+        let (mut picked, mut unpinned) = (None, None);
+        ui.add_enabled_ui(!self.is_searching, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                ui.spacing_mut().item_spacing.x = 2.0;
+                for (i, version) in self.favorites.iter().enumerate() {
+                    let chip = egui::Button::new(egui::RichText::new(version).monospace())
+                        .small()
+                        .rounding(8.0);
+                    if ui.add(chip).on_hover_text("Add to the versions").clicked() {
+                        picked = Some(version.clone());
+                    }
+                    if ui.small_button("✖").on_hover_text("Unpin").clicked() {
+                        unpinned = Some(i);
+                    }
+                    ui.add_space(6.0);
+                }
+            });
+        });
+
+        if let Some(version) = picked {
+            self.append_versions(vec![version]);
+        }
+        if let Some(i) = unpinned {
+            self.favorites.remove(i);
+        }
+    }
+
+    fn history_menu(&mut self, ui: &mut egui::Ui) {
+        ui.add_enabled_ui(!self.is_searching, |ui| {
+            ui.menu_button("🕘 History", |ui| {
+                if self.history.is_empty() {
+                    ui.label(egui::RichText::new("No searches yet").color(egui::Color32::GRAY));
+                    return;
+                }
+
+                let mut picked = None;
+                egui::ScrollArea::vertical()
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        for (i, entry) in self.history.iter().enumerate() {
+                            if ui.button(entry.label()).clicked() {
+                                picked = Some(i);
+                            }
+                        }
+                    });
+                ui.separator();
+                if ui.button("Clear history").clicked() {
+                    self.history.clear();
+                    ui.close_menu();
+                }
+
+                if let Some(i) = picked {
+                    let entry = self.history[i].clone();
+                    self.apply_history(&entry);
+                    ui.close_menu();
+                }
+            });
+        });
+    }
+
+    fn apply_settings(&mut self, settings: Settings) {
+        self.range_from = settings.range_from;
+        self.range_to = settings.range_to;
+        self.extra_ranges = settings.extra_ranges;
+        self.smart_search = settings.smart_search;
+        self.build_hints = settings.build_hints;
+        self.max_gap_enabled = settings.max_gap_enabled;
+        self.max_gap = settings.max_gap.max(1);
+        self.step = settings.step.max(1);
+        self.max_connections_index = settings
+            .max_connections_index
+            .min(MAX_CONNECTION_OPTIONS.len() - 1);
+        self.platform_win_x86 = settings.platform_win_x86;
+        self.platform_win_x64 = settings.platform_win_x64;
+        self.platform_win_arm64 = settings.platform_win_arm64;
+        self.platform_macos_intel = settings.platform_macos_intel;
+        self.platform_macos_arm64 = settings.platform_macos_arm64;
+        self.report_unknown = settings.report_unknown;
+        self.only_new = settings.only_new;
+        self.reveal_speed_ms = settings.reveal_speed_ms;
+        self.instant_results = settings.instant_results;
+        self.strict_version = settings.strict_version;
+        self.diagnostics = settings.diagnostics;
+        self.notify_when_done = settings.notify_when_done;
+        self.latest_only = settings.latest_only;
+        self.counts_only = settings.counts_only;
+        self.unique_builds = settings.unique_builds;
+        self.remember_versions = settings.remember_versions;
+        self.skip_small = settings.skip_small;
+        self.require_content_type = settings.require_content_type;
+        self.proxy = settings.proxy;
+        self.local_address = settings.local_address;
+        self.ip_family = settings.ip_family;
+        self.user_agent = settings.user_agent;
+        self.headers = settings.headers;
+        self.ca_cert = settings.ca_cert;
+        self.http2_prior_knowledge = settings.http2_prior_knowledge;
+        self.compression = settings.compression;
+        self.limit_idle_connections = settings.limit_idle_connections;
+        self.max_idle_connections = settings.max_idle_connections;
+        self.idle_timeout_secs = settings.idle_timeout_secs;
+        self.custom_connections = settings.custom_connections;
+        self.rps_limit_enabled = settings.rps_limit_enabled;
+        self.rps_limit = settings.rps_limit.max(1);
+        self.jitter_ms = settings.jitter_ms;
+        self.ramp_connections = settings.ramp_connections;
+        self.pause_on_network_errors = settings.pause_on_network_errors;
+        self.network_error_percent = settings.network_error_percent.clamp(1, 100);
+        self.max_requests = settings.max_requests.max(1);
+        self.download_dir = settings.download_dir;
+        self.downloader = settings.downloader;
+        if settings.remember_versions {
+            self.versions_input = settings.versions_input;
+        }
+    }
+
+    fn settings(&self) -> Settings {
+        Settings {
+            range_from: self.range_from.clone(),
+            range_to: self.range_to.clone(),
+            extra_ranges: self.extra_ranges.clone(),
+            smart_search: self.smart_search,
+            build_hints: self.build_hints,
+            max_gap_enabled: self.max_gap_enabled,
+            max_gap: self.max_gap,
+            step: self.step,
+            max_connections_index: self.max_connections_index,
+            platform_win_x86: self.platform_win_x86,
+            platform_win_x64: self.platform_win_x64,
+            platform_win_arm64: self.platform_win_arm64,
+            platform_macos_intel: self.platform_macos_intel,
+            platform_macos_arm64: self.platform_macos_arm64,
+            report_unknown: self.report_unknown,
+            only_new: self.only_new,
+            reveal_speed_ms: self.reveal_speed_ms,
+            instant_results: self.instant_results,
+            strict_version: self.strict_version,
+            diagnostics: self.diagnostics,
+            notify_when_done: self.notify_when_done,
+            latest_only: self.latest_only,
+            counts_only: self.counts_only,
+            unique_builds: self.unique_builds,
+            remember_versions: self.remember_versions,
+            skip_small: self.skip_small,
+            require_content_type: self.require_content_type,
+            versions_input: if self.remember_versions {
+                self.versions_input.clone()
+            } else {
+                String::new()
+            },
+            proxy: self.proxy.clone(),
+            local_address: self.local_address.clone(),
+            ip_family: self.ip_family,
+            user_agent: self.user_agent.clone(),
+            headers: self.headers.clone(),
+            ca_cert: self.ca_cert.clone(),
+            http2_prior_knowledge: self.http2_prior_knowledge,
+            compression: self.compression,
+            limit_idle_connections: self.limit_idle_connections,
+            max_idle_connections: self.max_idle_connections,
+            idle_timeout_secs: self.idle_timeout_secs,
+            custom_connections: self.custom_connections.clone(),
+            rps_limit_enabled: self.rps_limit_enabled,
+            rps_limit: self.rps_limit,
+            jitter_ms: self.jitter_ms,
+            ramp_connections: self.ramp_connections,
+            pause_on_network_errors: self.pause_on_network_errors,
+            network_error_percent: self.network_error_percent,
+            max_requests: self.max_requests,
+            download_dir: self.download_dir.clone(),
+            downloader: self.downloader,
+        }
+    }
+
+    /// A reveal speed of 0 means the same as the "Instant results" checkbox.
+    fn reveal_instantly(&self) -> bool {
+        self.instant_results || self.reveal_speed_ms == 0
+    }
+
+    fn push_result_text(&mut self, text: String) {
+        if self.reveal_instantly() {
+            self.displayed_results.push_str(&text);
+        } else {
+            self.reveal_queue.push_back(text);
+        }
+    }
+
+    /// Shows everything still waiting in the reveal animation at once.
+    fn flush_reveal(&mut self) {
+        if let Some(cur) = self.current_reveal.take() {
+            self.displayed_results.push_str(&cur[self.reveal_pos..]);
+        }
+        self.reveal_pos = 0;
+        while let Some(line) = self.reveal_queue.pop_front() {
+            self.displayed_results.push_str(&line);
+        }
+    }
+
     fn advance_reveal(&mut self) {
         if self.current_reveal.is_some() && !self.reveal_queue.is_empty() {
             if let Some(cur) = self.current_reveal.take() {
@@ -408,85 +1243,637 @@ impl SpotifyFinderApp {
     }
 
     fn select_all_platforms(&mut self) {
-        self.platform_win_x86 = true;
-        self.platform_win_x64 = true;
-        self.platform_win_arm64 = true;
-        self.platform_macos_intel = true;
-        self.platform_macos_arm64 = true;
+        self.select_os(true, true);
     }
 
     fn select_no_platforms(&mut self) {
-        self.platform_win_x86 = false;
-        self.platform_win_x64 = false;
-        self.platform_win_arm64 = false;
-        self.platform_macos_intel = false;
-        self.platform_macos_arm64 = false;
+        self.select_os(false, false);
     }
 
-    fn start_search(&mut self) {
-        let versions: Vec<String> = self
-            .versions_input
-            .lines()
-            .map(|s| s.trim().to_string())
-            .filter(|s| !s.is_empty() && validate_version(s))
-            .collect();
+    /// Every Windows and/or every macOS platform, and nothing else, like the
+    /// CLI's `--os`.
+    fn select_os(&mut self, windows: bool, macos: bool) {
+        self.platform_win_x86 = windows;
+        self.platform_win_x64 = windows;
+        self.platform_win_arm64 = windows;
+        self.platform_macos_intel = macos;
+        self.platform_macos_arm64 = macos;
+    }
+
+    /// Whether exactly the platforms of [`Self::select_os`] are selected.
+    fn os_selected(&self, windows: bool, macos: bool) -> bool {
+        [
+            self.platform_win_x86 == windows,
+            self.platform_win_x64 == windows,
+            self.platform_win_arm64 == windows,
+            self.platform_macos_intel == macos,
+            self.platform_macos_arm64 == macos,
+        ]
+        .iter()
+        .all(|&same| same)
+    }
+
+    /// Validated versions, range and platforms, or `None` after showing
+    /// what is wrong in the results panel.
+    fn search_plan(&mut self) -> Option<SearchPlan> {
+        let validate = if self.strict_version {
+            validate_version_strict
+        } else {
+            validate_version
+        };
+        let mut versions: Vec<String> = Vec::new();
+        self.rejected_versions.clear();
+        for (line, version) in numbered_version_lines(&self.versions_input) {
+            if !validate(version) {
+                self.rejected_versions.push((line, version.to_string()));
+            } else if !versions.iter().any(|v| v == version) {
+                versions.push(version.to_string());
+            }
+        }
 
         if versions.is_empty() {
             self.displayed_results = "Error: No valid versions provided".to_string();
-            return;
+            return None;
         }
 
         let start = self.range_from.parse::<i32>().unwrap_or(0);
         let end = self.range_to.parse::<i32>().unwrap_or(5000);
+        let mut ranges = vec![(start, end)];
+        for (from, to) in &self.extra_ranges {
+            match (from.trim().parse::<i32>(), to.trim().parse::<i32>()) {
+                (Ok(start), Ok(end)) if start >= 0 => ranges.push((start, end)),
+                _ if self.smart_search => {}
+                _ => {
+                    self.displayed_results = format!("Error: Invalid range '{}' to '{}'", from, to);
+                    return None;
+                }
+            }
+        }
 
-        if end < start {
+        if !self.smart_search && ranges.iter().any(|(start, end)| end < start) {
             self.displayed_results = "Error: End range must be >= start range".to_string();
-            return;
+            return None;
         }
+        let ranges = merge_build_ranges(ranges);
 
         let base_platforms = self.get_selected_platforms();
         if base_platforms.is_empty() {
             self.displayed_results = "Error: No platforms selected".to_string();
-            return;
+            return None;
         }
 
-        let max_conn = MAX_CONNECTION_OPTIONS[self
-            .max_connections_index
-            .min(MAX_CONNECTION_OPTIONS.len() - 1)];
-
         if base_platforms.len() == 1 && base_platforms[0] == Platform::WinX86 && versions.len() == 1
         {
             let user_version = &versions[0];
             if !should_use_win_x86(user_version) {
                 self.displayed_results = "Warning: x86 architecture for Windows is no longer supported for versions newer than 1.2.53".to_string();
-                return;
+                return None;
             }
         }
 
-        self.is_searching = true;
-        self.progress = 0.0;
-        self.progress_text = "Starting...".to_string();
-        self.displayed_results.clear();
-        self.reveal_queue.clear();
-        self.current_reveal = None;
-        self.found_urls.clear();
+        Some(SearchPlan {
+            versions,
+            ranges,
+            platforms: base_platforms,
+        })
+    }
+
+    fn preview_urls(&mut self) {
+        let Some(plan) = self.search_plan() else {
+            return;
+        };
+        // A smart search only commits to its first window up front.
+        let ranges = if self.smart_search {
+            vec![(0, LadderOptions::default().initial_window)]
+        } else {
+            plan.ranges
+        };
 
-        let mut total_work_calc: u64 = 0;
-        for v in &versions {
-            let mut cnt = base_platforms.len();
-            if base_platforms.contains(&Platform::WinX86) && cnt > 1 && !should_use_win_x86(v) {
-                cnt -= 1;
+        let mut urls = String::new();
+        let mut total = 0;
+        for version in &plan.versions {
+            for platform in platforms_for_version(&plan.platforms, version) {
+                for &(start, end) in &ranges {
+                    let templates = PathTemplates::default();
+                    for url in
+                        generate_urls(version, start, end, self.step, &[platform], &templates)
+                    {
+                        urls.push_str(&url);
+                        urls.push('\n');
+                        total += 1;
+                    }
+                }
             }
-            let per_version = ((end - start + 1) * cnt as i32) as u64;
-            total_work_calc = total_work_calc.saturating_add(per_version);
         }
 
-        self.total_work = total_work_calc;
-        self.processed_global.store(0, Ordering::Relaxed);
+        self.clear_results();
+        self.displayed_results = format!("Total: {} requests\n\n{}", total, urls);
+    }
 
-        self.current_version = None;
-        self.current_version_index = 0;
-        self.total_versions = versions.len();
+    fn start_search(&mut self) {
+        self.search(None);
+    }
+
+    /// Fills the form from the interrupted search and continues it.
+    fn resume_search(&mut self) {
+        let Some(checkpoint) = self.resumable.take() else {
+            return;
+        };
+        let params = &checkpoint.params;
+        self.versions_input = params.versions.join("\n");
+        if let Some((&(start, end), extra)) = params.ranges.split_first() {
+            self.range_from = start.to_string();
+            self.range_to = end.to_string();
+            self.extra_ranges = extra
+                .iter()
+                .map(|(start, end)| (start.to_string(), end.to_string()))
+                .collect();
+        }
+        self.smart_search = params.ladder.is_some();
+        // A smart search never uses hints, whatever the checkbox says.
+        if !self.smart_search {
+            self.build_hints = params.build_hints;
+        }
+        self.max_gap_enabled = params.max_gap.is_some();
+        if let Some(max_gap) = params.max_gap {
+            self.max_gap = max_gap;
+        }
+        self.step = params.step;
+        self.platform_win_x86 = params.platforms.contains(&Platform::WinX86);
+        self.platform_win_x64 = params.platforms.contains(&Platform::WinX64);
+        self.platform_win_arm64 = params.platforms.contains(&Platform::WinArm64);
+        self.platform_macos_intel = params.platforms.contains(&Platform::MacOsIntel);
+        self.platform_macos_arm64 = params.platforms.contains(&Platform::MacOsArm64);
+        self.search(Some(checkpoint));
+    }
+
+    /// Everything but the connection limit: networking, output and
+    /// behaviour. Changes apply to the next search and are saved with the
+    /// rest of the settings.
+    fn settings_dialog(&mut self, ctx: &egui::Context) {
+        let mut open = self.settings_open;
+        egui::Window::new("Settings")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .default_width(380.0)
+            .vscroll(true)
+            .show(ctx, |ui| {
+                ui.label(egui::RichText::new("Network:").strong());
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.rps_limit_enabled, "Limit to")
+                        .on_hover_text(
+                            "Cap the request rate regardless of the connection \
+                             count, to scan politely from shared networks",
+                        );
+                    ui.add_enabled(
+                        self.rps_limit_enabled,
+                        egui::DragValue::new(&mut self.rps_limit).range(1..=10000),
+                    );
+                    ui.label("requests/s");
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("Random wait of up to");
+                    ui.add(egui::DragValue::new(&mut self.jitter_ms).range(0..=10000))
+                        .on_hover_text(
+                            "Wait a random moment before each request so they don't \
+                             arrive in lockstep. A waiting request still holds its \
+                             connection",
+                        );
+                    ui.label("ms before each request");
+                });
+
+                ui.checkbox(&mut self.ramp_connections, "Ramp up connections")
+                    .on_hover_text(
+                        "Start with a few connections and double them every 500ms \
+                         up to the limit, so a cold connection pool has time to \
+                         set up before every request competes for one",
+                    );
+
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.pause_on_network_errors, "Pause when over")
+                        .on_hover_text(
+                            "Pause the search when most recent requests got no \
+                             response, e.g. because the network went down, instead \
+                             of counting the rest of the range as misses",
+                        );
+                    ui.add_enabled(
+                        self.pause_on_network_errors,
+                        egui::DragValue::new(&mut self.network_error_percent)
+                            .range(1..=100)
+                            .suffix("%"),
+                    );
+                    ui.label("of requests fail");
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("Proxy:");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.proxy)
+                            .hint_text("http://host:port")
+                            .desired_width(f32::INFINITY),
+                    );
+                });
+
+                egui::CollapsingHeader::new("Network interface")
+                    .id_salt("network_interface")
+                    .show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label("Local address:");
+                            ui.add(
+                                egui::TextEdit::singleline(&mut self.local_address)
+                                    .hint_text("chosen by the OS")
+                                    .desired_width(f32::INFINITY),
+                            );
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("IP version:");
+                            for family in [IpFamily::Any, IpFamily::V4, IpFamily::V6] {
+                                ui.radio_value(&mut self.ip_family, family, family.to_string());
+                            }
+                        });
+                    });
+
+                egui::CollapsingHeader::new("Request headers")
+                    .id_salt("request_headers")
+                    .show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label("User-Agent:");
+                            ui.add(
+                                egui::TextEdit::singleline(&mut self.user_agent)
+                                    .hint_text("reqwest default")
+                                    .desired_width(f32::INFINITY),
+                            );
+                        });
+                        ui.label("Extra headers, one \"Name: Value\" per line:");
+                        ui.add(
+                            egui::TextEdit::multiline(&mut self.headers)
+                                .font(egui::TextStyle::Monospace)
+                                .desired_rows(3)
+                                .desired_width(f32::INFINITY),
+                        );
+                    });
+
+                egui::CollapsingHeader::new("Expert: connections")
+                    .id_salt("expert_connections")
+                    .show(ui, |ui| {
+                        ui.checkbox(&mut self.http2_prior_knowledge, "HTTP/2 prior knowledge")
+                            .on_hover_text(
+                                "Speak HTTP/2 without negotiating it first. The Spotify \
+                                 CDN negotiates HTTP/2 on its own; this is only for \
+                                 plain-HTTP mirrors that speak h2c and breaks any other",
+                            );
+                        ui.checkbox(&mut self.compression, "Compressed transfers")
+                            .on_hover_text(
+                                "Accept gzip, deflate and brotli bodies. Only versions.json \
+                                 and downloads read a body; probes are unaffected",
+                            );
+                        ui.horizontal(|ui| {
+                            ui.checkbox(&mut self.limit_idle_connections, "Keep at most")
+                                .on_hover_text(
+                                    "By default every connection a search opens is kept \
+                                     for reuse, which avoids new TLS handshakes",
+                                );
+                            ui.add_enabled(
+                                self.limit_idle_connections,
+                                egui::DragValue::new(&mut self.max_idle_connections)
+                                    .range(0..=10_000),
+                            );
+                            ui.label("idle connections");
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("Close idle connections after");
+                            ui.add(
+                                egui::DragValue::new(&mut self.idle_timeout_secs)
+                                    .range(0..=3600)
+                                    .suffix(" s"),
+                            )
+                            .on_hover_text(
+                                "0 keeps them until the server closes them. Raise it \
+                                 when a requests-per-second limit spaces requests out \
+                                 further than this",
+                            );
+                        });
+                    });
+
+                egui::CollapsingHeader::new(
+                    egui::RichText::new("⚠ Danger: TLS").color(egui::Color32::LIGHT_RED),
+                )
+                .id_salt("danger_tls")
+                .show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("CA certificate:");
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.ca_cert)
+                                .hint_text("path to a PEM or DER file")
+                                .desired_width(f32::INFINITY),
+                        );
+                    });
+                    ui.checkbox(
+                        &mut self.accept_invalid_certs,
+                        "Accept invalid certificates",
+                    )
+                    .on_hover_text(
+                        "Skips TLS certificate checks, so anyone on the network path \
+                         can forge responses. Only for a mirror you control; \
+                         not remembered after a restart",
+                    );
+                    if self.accept_invalid_certs {
+                        ui.label(
+                            egui::RichText::new("Certificates are NOT verified.")
+                                .color(egui::Color32::LIGHT_RED)
+                                .strong(),
+                        );
+                    }
+                });
+
+                ui.separator();
+                ui.label(egui::RichText::new("Output:").strong());
+                ui.horizontal(|ui| {
+                    ui.label("Download to:");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.download_dir)
+                            .hint_text("current directory")
+                            .desired_width(f32::INFINITY),
+                    );
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("Reveal speed:");
+                    ui.add_enabled(
+                        !self.instant_results,
+                        egui::Slider::new(&mut self.reveal_speed_ms, 0..=50).custom_formatter(
+                            |ms, _| {
+                                if ms == 0.0 {
+                                    "instant".to_string()
+                                } else {
+                                    format!("{} ms/char", ms)
+                                }
+                            },
+                        ),
+                    );
+                });
+                ui.checkbox(&mut self.instant_results, "Instant results");
+
+                ui.checkbox(&mut self.diagnostics, "Diagnostics")
+                    .on_hover_text(
+                        "Count the HTTP statuses of missed builds: all 404 means the \
+                         range is off, 403s point at blocking or geo restrictions",
+                    );
+                ui.checkbox(&mut self.notify_when_done, "Notify when done")
+                    .on_hover_text("Show a desktop notification when a search finishes");
+
+                ui.separator();
+                ui.label(egui::RichText::new("Behavior:").strong());
+                ui.horizontal(|ui| {
+                    ui.label("Confirm searches over");
+                    ui.add(
+                        egui::DragValue::new(&mut self.max_requests)
+                            .range(1..=u64::MAX)
+                            .speed(1000),
+                    );
+                    ui.label("requests");
+                });
+
+                #[cfg(feature = "telemetry")]
+                ui.checkbox(
+                    &mut self.report_unknown,
+                    "Report unknown versions (sends data)",
+                )
+                .on_hover_text(
+                    "Off by default. When on, each searched version missing from the \
+                     repository's versions.json is posted to the LoaderSpot Google Form. \
+                     Only the version string is sent, with no builds, URLs or settings.",
+                );
+                ui.checkbox(&mut self.only_new, "Only new versions")
+                    .on_hover_text("Skip versions already in the repository's versions.json");
+                ui.checkbox(&mut self.remember_versions, "Remember versions list");
+                ui.checkbox(&mut self.strict_version, "Strict version check")
+                    .on_hover_text("Only accept versions with an 8 character hash");
+                ui.checkbox(&mut self.skip_small, "Skip placeholder files")
+                    .on_hover_text(
+                        "Treat found files under 1 MB as missing. Real installers are \
+                         tens of megabytes; smaller ones are flagged either way",
+                    );
+                ui.checkbox(&mut self.require_content_type, "Skip error pages")
+                    .on_hover_text(
+                        "Treat found files served with a type installers don't have, \
+                         such as an HTML page, as missing. Diagnostics list such types \
+                         either way",
+                    );
+            });
+        self.settings_open = open;
+    }
+
+    /// Asks before starting a search over `max_requests`.
+    fn large_search_dialog(&mut self, ctx: &egui::Context) {
+        let Some(pending) = &self.large_search else {
+            return;
+        };
+        let text = format!(
+            "This search sends up to {} requests, about {} at the current settings.",
+            pending.requests,
+            format_eta(pending.estimate.as_secs())
+        );
+        let (mut run, mut cancel) = (false, false);
+        egui::Window::new("Large search")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(text);
+                ui.label(
+                    egui::RichText::new(format!(
+                        "Searches over {} requests need confirming; see Settings.",
+                        self.max_requests
+                    ))
+                    .size(12.0)
+                    .color(egui::Color32::GRAY),
+                );
+                ui.add_space(5.0);
+                ui.horizontal(|ui| {
+                    run = ui.button("Run anyway").clicked();
+                    cancel = ui.button("Cancel").clicked();
+                });
+            });
+        if run || cancel {
+            let pending = self.large_search.take().unwrap();
+            if run {
+                self.large_search_confirmed = true;
+                self.search(pending.resumed);
+            } else if pending.resumed.is_some() {
+                // Offer it again.
+                self.resumable = pending.resumed;
+            }
+        }
+    }
+
+    fn discard_checkpoint(&mut self) {
+        self.resumable = None;
+        self.checkpoint = None;
+        self.checked_builds = None;
+        if let Some(path) = checkpoint_path().filter(|path| path.exists()) {
+            if let Err(e) = std::fs::remove_file(&path) {
+                eprintln!("Cannot remove {}: {}", path.display(), e);
+            }
+        }
+    }
+
+    fn save_checkpoint(&mut self) {
+        self.last_checkpoint_save = Instant::now();
+        let (Some(checkpoint), Some(path)) = (&mut self.checkpoint, checkpoint_path()) else {
+            return;
+        };
+        if let Some(checked) = &self.checked_builds {
+            checkpoint.record(checked);
+        }
+        checkpoint.record_failures(&self.failed_checks);
+        checkpoint.found = self.found.clone();
+        if let Err(e) = checkpoint.save(&path) {
+            eprintln!("{}", e);
+        }
+    }
+
+    /// Starts a new search, or continues `resumed` when its parameters
+    /// still match the form.
+    fn search(&mut self, resumed: Option<Checkpoint>) {
+        // The confirmation dialog decides about the one already waiting.
+        if self.large_search.is_some() {
+            return;
+        }
+        let confirmed = std::mem::take(&mut self.large_search_confirmed);
+        let Some(SearchPlan {
+            versions,
+            ranges,
+            platforms: base_platforms,
+        }) = self.search_plan()
+        else {
+            return;
+        };
+
+        let Some(max_conn) = self.connection_limit() else {
+            return;
+        };
+
+        let client = match self.client_options(Duration::from_secs(10)).build() {
+            Ok(client) => client,
+            Err(e) => {
+                self.displayed_results = format!("Error: {}", e);
+                return;
+            }
+        };
+
+        let smart_search = self.smart_search;
+        let ladder = LadderOptions::default();
+        // A hinted search stops as soon as the likely window has a hit.
+        let build_hints = self.build_hints && !smart_search;
+        let max_gap = self.max_gap_enabled.then_some(self.max_gap.max(1));
+        let step = self.step.max(1);
+        let min_size = self.skip_small.then_some(SUSPICIOUS_SIZE);
+        let params = SearchParams {
+            versions: versions.clone(),
+            platforms: base_platforms.clone(),
+            ranges: ranges.clone(),
+            ladder: smart_search.then_some(ladder),
+            build_hints,
+            max_gap,
+            try_dmg: false,
+            step,
+        };
+        if let Some(resumed) = &resumed {
+            if let Err(e) = resumed.ensure_matches(&params) {
+                self.displayed_results = format!("Error: {}", e);
+                return;
+            }
+        }
+
+        let builds = builds_per_platform(&params);
+        let requests: u64 = versions
+            .iter()
+            .filter(|version| !resumed.as_ref().is_some_and(|r| r.is_completed(version)))
+            .map(|version| platforms_for_version(&base_platforms, version).len() as u64 * builds)
+            .sum();
+        if requests > self.max_requests && !confirmed {
+            let rps = self.rps_limit_enabled.then_some(self.rps_limit.max(1));
+            self.large_search = Some(LargeSearch {
+                requests,
+                estimate: estimate_search_time(requests, max_conn, rps),
+                resumed,
+            });
+            return;
+        }
+
+        self.record_history(HistoryEntry {
+            versions: versions.clone(),
+            start: ranges[0].0,
+            end: ranges[0].1,
+            extra_ranges: ranges[1..].to_vec(),
+            smart_search: self.smart_search,
+            platforms: base_platforms.clone(),
+            searched_at: unix_now(),
+        });
+
+        self.is_searching = true;
+        self.progress = 0.0;
+        self.progress_text = "Starting...".to_string();
+        self.displayed_results.clear();
+        self.reveal_queue.clear();
+        self.current_reveal = None;
+        self.found_urls.clear();
+        self.found.clear();
+        self.coverage.clear();
+        self.known_skipped = 0;
+        self.searched_versions = versions.clone();
+
+        // Ladder and hinted searches jump around, so they resume whole versions.
+        self.checked_builds = (!smart_search && !build_hints).then(CheckedBuilds::default);
+        let checkpoint = resumed.unwrap_or_else(|| Checkpoint::new(params));
+        for installer in &checkpoint.found {
+            self.found_urls
+                .entry(installer.platform)
+                .or_default()
+                .push(installer.url.clone());
+        }
+        self.found = checkpoint.found.clone();
+        self.failed_checks = FailedChecks::from(checkpoint.failed.clone());
+        if !self.found.is_empty() {
+            self.displayed_results = self.render_found();
+        }
+        let resuming = !checkpoint.completed.is_empty() || !checkpoint.next_builds.is_empty();
+        let versions_to_search: Vec<String> = versions
+            .iter()
+            .filter(|version| !checkpoint.is_completed(version))
+            .cloned()
+            .collect();
+        let work = SearchWork::new(
+            &checkpoint.params,
+            &versions_to_search,
+            self.only_new,
+            resuming,
+        );
+        // The task only reads it; the UI thread keeps the one it saves.
+        let resumed = resuming.then(|| Arc::new(checkpoint.clone()));
+        // A new search takes over the checkpoint file.
+        self.resumable = None;
+        self.checkpoint = Some(checkpoint);
+        self.last_checkpoint_save = Instant::now();
+
+        self.platform_progress = work
+            .per_platform
+            .iter()
+            .map(|(&platform, &total)| (platform, (0, total)))
+            .collect();
+        self.total_work = work.total();
+        self.total_work_is_exact = work.exact;
+        self.processed_global.store(0, Ordering::Relaxed);
+        self.platform_processed.reset();
+        self.search_started = Instant::now();
+        self.rate_samples.clear();
+
+        self.current_version = None;
+        self.current_version_index = 0;
+        self.total_versions = versions_to_search.len();
 
         let (tx, rx): (Sender<SearchMessage>, Receiver<SearchMessage>) = unbounded();
         self.rx = Some(rx);
@@ -495,19 +1882,88 @@ impl SpotifyFinderApp {
         self.cancel_flag.store(false, Ordering::Relaxed);
         self.is_paused = false;
 
-        let versions_to_search = versions.clone();
-        let report_unknown = self.report_unknown;
+        // Builds without the telemetry feature ignore a saved setting.
+        let report_unknown = cfg!(feature = "telemetry") && self.report_unknown;
+        #[cfg(feature = "telemetry")]
+        let reports_pending = self.reports_pending.clone();
         let pause = self.pause_flag.clone();
         let cancel = self.cancel_flag.clone();
         let base_platforms_for_spawn = base_platforms.clone();
         let processed_for_spawn = self.processed_global.clone();
+        let platform_processed = self.platform_processed.clone();
+        self.status_counts.reset();
+        let status_counts = self.diagnostics.then(|| self.status_counts.clone());
+        self.unexpected_types.reset();
+        let unexpected_types = self.unexpected_types.clone();
+        let require_content_type = self.require_content_type;
+        let only_new = self.only_new;
+        self.request_counts.reset();
+        self.network_errors.store(0, Ordering::Relaxed);
+        let request_counts = self.request_counts.clone();
+        let network_errors = self.network_errors.clone();
+        self.run_report = Some(RunReport {
+            platforms: base_platforms.clone(),
+            connections: max_conn,
+            requests_per_second: self.rps_limit_enabled.then_some(self.rps_limit.max(1)),
+            ..RunReport::start(
+                concat!(env!("CARGO_PKG_NAME"), " ", env!("CARGO_PKG_VERSION")),
+                versions.clone(),
+                RunReport::describe_scope(smart_search.then_some(&ladder), &ranges),
+            )
+        });
+        let backoff = self.backoff.clone();
+        // One limiter for the whole run, so it spans every version.
+        let rate_limiter = if self.rps_limit_enabled {
+            RateLimiter::new(self.rps_limit.max(1))
+        } else {
+            RateLimiter::default()
+        };
+        let jitter = Jitter::new(Duration::from_millis(self.jitter_ms));
+        let mut ramp = self.ramp_connections;
+        self.error_storm = if self.pause_on_network_errors {
+            ErrorStorm::new(
+                ErrorStorm::DEFAULT_WINDOW,
+                self.network_error_percent.clamp(1, 100),
+            )
+        } else {
+            ErrorStorm::default()
+        };
+        let error_storm = self.error_storm.clone();
+        self.reachability = Reachability::watching();
+        let reachability = self.reachability.clone();
+        let checked_builds = self.checked_builds.clone();
+        let failed_checks = self.failed_checks.clone();
 
         self.runtime.spawn(async move {
-            let client = Client::builder()
-                .timeout(std::time::Duration::from_secs(10))
-                .build()
-                .unwrap();
-
+            // Fetched once for the whole run.
+            let versions_json = if build_hints || report_unknown || only_new {
+                fetch_versions_json(&client).await
+            } else {
+                Err(String::new())
+            };
+            // Without versions.json the search falls back to the plain range.
+            let hints = if build_hints {
+                match versions_json
+                    .as_ref()
+                    .map_err(String::clone)
+                    .and_then(|json| KnownBuilds::parse(json))
+                {
+                    Ok(hints) => hints,
+                    Err(e) => {
+                        log::warn!("No build hints: {}", e);
+                        KnownBuilds::default()
+                    }
+                }
+            } else {
+                KnownBuilds::default()
+            };
+            let known = versions_json.and_then(|json| KnownVersions::parse(&json));
+            if only_new {
+                if let Err(e) = &known {
+                    let _ = tx.send(SearchMessage::Failed(e.clone()));
+                    return;
+                }
+            }
             let total_versions = versions_to_search.len();
             for (i, version) in versions_to_search.into_iter().enumerate() {
                 let _ = tx.clone().send(SearchMessage::VersionStart(
@@ -515,55 +1971,799 @@ impl SpotifyFinderApp {
                     i + 1,
                     total_versions,
                 ));
-                if report_unknown {
-                    check_version_and_submit(&client, &version).await;
+                if let Ok(known) = &known {
+                    if only_new && known.contains(&version) {
+                        let _ = tx.send(SearchMessage::AlreadyKnown(version));
+                        continue;
+                    }
+                    // Unknown without versions.json means nothing.
+                    #[cfg(feature = "telemetry")]
+                    if report_unknown {
+                        check_version_and_submit(&client, known, &version, &reports_pending);
+                    }
                 }
 
-                let mut platforms_for_version = base_platforms_for_spawn.clone();
-                if platforms_for_version.contains(&Platform::WinX86)
-                    && platforms_for_version.len() > 1
-                    && !should_use_win_x86(&version)
-                {
-                    platforms_for_version.retain(|p| *p != Platform::WinX86);
-                }
+                let platforms_for_version =
+                    platforms_for_version(&base_platforms_for_spawn, &version);
+                let skipped: Vec<Platform> = base_platforms_for_spawn
+                    .iter()
+                    .copied()
+                    .filter(|platform| !platforms_for_version.contains(platform))
+                    .collect();
 
                 if platforms_for_version.is_empty() {
+                    let _ = tx.send(SearchMessage::Searched(SearchedVersion::new(
+                        &version,
+                        &[],
+                        skipped,
+                        &ScannedBuilds::default(),
+                    )));
                     let _ = tx
                         .clone()
                         .send(SearchMessage::Complete(version.to_string()));
                     continue;
                 }
 
-                search_installers(SearchOptions {
-                    client: client.clone(),
-                    version,
-                    start,
-                    end,
-                    platforms: platforms_for_version,
-                    max_connections: max_conn,
-                    tx: tx.clone(),
+                let tx_found = tx.clone();
+                let engine = SearchEngine {
                     pause_flag: pause.clone(),
                     cancel_flag: cancel.clone(),
                     processed: processed_for_spawn.clone(),
-                })
-                .await;
+                    platform_processed: platform_processed.clone(),
+                    status_counts: status_counts.clone(),
+                    request_counts: request_counts.clone(),
+                    network_errors: network_errors.clone(),
+                    backoff: backoff.clone(),
+                    rate_limiter: rate_limiter.clone(),
+                    jitter: jitter.clone(),
+                    error_storm: error_storm.clone(),
+                    reachability: reachability.clone(),
+                    max_gap,
+                    step,
+                    min_size,
+                    require_content_type,
+                    unexpected_types: unexpected_types.clone(),
+                    checked_builds: checked_builds.clone(),
+                    failed_checks: Some(failed_checks.clone()),
+                    on_found: Some(Arc::new(move |event: &FoundEvent| {
+                        let _ = tx_found.send(SearchMessage::Result(event.clone()));
+                    })),
+                    ..SearchEngine::new(client.clone(), max_conn)
+                };
+                // Later versions reuse the connections the first one opened.
+                if ramp {
+                    Ramp::default().apply(&engine.semaphore);
+                    ramp = false;
+                }
+                if smart_search {
+                    engine
+                        .ladder_search(&version, &platforms_for_version, ladder)
+                        .await;
+                } else if !hints.is_empty() {
+                    for &(start, end) in &ranges {
+                        engine
+                            .hinted_search(&version, start, end, &platforms_for_version, &hints)
+                            .await;
+                    }
+                } else if let Some(resumed) = &resumed {
+                    // Each platform picks up at its own next build.
+                    for &platform in &platforms_for_version {
+                        let remaining = resumed.remaining_ranges(&version, platform, &ranges);
+                        if !remaining.is_empty() {
+                            engine
+                                .search_ranges(&version, &remaining, &[platform])
+                                .await;
+                        }
+                    }
+                } else {
+                    engine
+                        .search_ranges(&version, &ranges, &platforms_for_version)
+                        .await;
+                }
+                let _ = tx.send(SearchMessage::Searched(SearchedVersion::new(
+                    &version,
+                    &platforms_for_version,
+                    skipped,
+                    &engine.scanned_builds,
+                )));
+                let _ = tx.send(SearchMessage::Complete(version));
+
+                if cancel.load(Ordering::Relaxed) {
+                    break;
+                }
+            }
+
+            let _ = tx.send(SearchMessage::CompleteAll);
+        });
+    }
+
+    /// The chosen or custom connection limit; shows an error for a bad custom one.
+    fn connection_limit(&mut self) -> Option<usize> {
+        match self.custom_connections.trim() {
+            "" => Some(
+                MAX_CONNECTION_OPTIONS[self
+                    .max_connections_index
+                    .min(MAX_CONNECTION_OPTIONS.len() - 1)],
+            ),
+            custom => match custom.parse::<usize>() {
+                Ok(n) if n > 0 => Some(n),
+                _ => {
+                    self.displayed_results =
+                        "Error: Max connections must be a positive number".to_string();
+                    None
+                }
+            },
+        }
+    }
+
+    /// Checks the URLs in the "Check URLs" box as they are, with the search's
+    /// connection and rate limits but no versions, platforms or ranges.
+    fn check_url_list(&mut self) {
+        let mut urls: Vec<String> = Vec::new();
+        for line in version_list_lines(&self.url_list) {
+            if (line.starts_with("http://") || line.starts_with("https://"))
+                && !urls.iter().any(|url| url == line)
+            {
+                urls.push(line.to_string());
+            }
+        }
+        if urls.is_empty() {
+            self.displayed_results = "Error: No http(s) URLs to check".to_string();
+            return;
+        }
+        let Some(max_conn) = self.connection_limit() else {
+            return;
+        };
+        let client = match self.client_options(Duration::from_secs(10)).build() {
+            Ok(client) => client,
+            Err(e) => {
+                self.displayed_results = format!("Error: {}", e);
+                return;
+            }
+        };
+
+        self.clear_results();
+        self.is_searching = true;
+        self.progress_text = "Starting...".to_string();
+        self.total_work = urls.len() as u64;
+        self.total_work_is_exact = true;
+        self.search_started = Instant::now();
+        self.rate_samples.clear();
+        self.current_version = None;
+        self.run_report = None;
+        self.pause_flag.store(false, Ordering::Relaxed);
+        self.cancel_flag.store(false, Ordering::Relaxed);
+        self.is_paused = false;
+        self.status_counts.reset();
+        self.unexpected_types.reset();
+        self.request_counts.reset();
+        self.network_errors.store(0, Ordering::Relaxed);
+        self.error_storm = if self.pause_on_network_errors {
+            ErrorStorm::new(
+                ErrorStorm::DEFAULT_WINDOW,
+                self.network_error_percent.clamp(1, 100),
+            )
+        } else {
+            ErrorStorm::default()
+        };
+
+        let engine = SearchEngine {
+            pause_flag: self.pause_flag.clone(),
+            cancel_flag: self.cancel_flag.clone(),
+            processed: self.processed_global.clone(),
+            status_counts: self.diagnostics.then(|| self.status_counts.clone()),
+            require_content_type: self.require_content_type,
+            unexpected_types: self.unexpected_types.clone(),
+            request_counts: self.request_counts.clone(),
+            network_errors: self.network_errors.clone(),
+            backoff: self.backoff.clone(),
+            rate_limiter: if self.rps_limit_enabled {
+                RateLimiter::new(self.rps_limit.max(1))
+            } else {
+                RateLimiter::default()
+            },
+            jitter: Jitter::new(Duration::from_millis(self.jitter_ms)),
+            error_storm: self.error_storm.clone(),
+            ..SearchEngine::new(client, max_conn)
+        };
+        let (tx, rx): (Sender<SearchMessage>, Receiver<SearchMessage>) = unbounded();
+        self.rx = Some(rx);
+        self.runtime.spawn(async move {
+            let checked = engine.check_urls(&urls).await;
+            let _ = tx.send(SearchMessage::UrlsChecked(checked));
+        });
+    }
+
+    /// Checks the builds of the last search that got no response again, with
+    /// the search's connection and rate limits. Hits join the results.
+    fn rescan_failures(&mut self) {
+        let failed = self.failed_checks.snapshot();
+        if failed.is_empty() {
+            return;
+        }
+        let Some(max_conn) = self.connection_limit() else {
+            return;
+        };
+        let client = match self.client_options(Duration::from_secs(10)).build() {
+            Ok(client) => client,
+            Err(e) => {
+                self.displayed_results = format!("Error: {}", e);
+                return;
+            }
+        };
+
+        self.is_searching = true;
+        self.progress = 0.0;
+        self.progress_text = "Rescanning failures...".to_string();
+        self.total_work = failed.len() as u64;
+        self.total_work_is_exact = true;
+        self.processed_global.store(0, Ordering::Relaxed);
+        self.search_started = Instant::now();
+        self.rate_samples.clear();
+        self.current_version = None;
+        self.pause_flag.store(false, Ordering::Relaxed);
+        self.cancel_flag.store(false, Ordering::Relaxed);
+        self.is_paused = false;
+        self.network_errors.store(0, Ordering::Relaxed);
+
+        let (tx, rx): (Sender<SearchMessage>, Receiver<SearchMessage>) = unbounded();
+        let tx_found = tx.clone();
+        let engine = SearchEngine {
+            pause_flag: self.pause_flag.clone(),
+            cancel_flag: self.cancel_flag.clone(),
+            processed: self.processed_global.clone(),
+            request_counts: self.request_counts.clone(),
+            network_errors: self.network_errors.clone(),
+            backoff: self.backoff.clone(),
+            rate_limiter: if self.rps_limit_enabled {
+                RateLimiter::new(self.rps_limit.max(1))
+            } else {
+                RateLimiter::default()
+            },
+            jitter: Jitter::new(Duration::from_millis(self.jitter_ms)),
+            require_content_type: self.require_content_type,
+            unexpected_types: self.unexpected_types.clone(),
+            on_found: Some(Arc::new(move |event: &FoundEvent| {
+                let _ = tx_found.send(SearchMessage::Result(event.clone()));
+            })),
+            ..SearchEngine::new(client, max_conn)
+        };
+        self.rx = Some(rx);
+        self.runtime.spawn(async move {
+            let rescan = engine.rescan_failures(&failed).await;
+            let _ = tx.send(SearchMessage::Rescanned(failed.len(), rescan));
+        });
+    }
+
+    /// The live URLs with their details; with diagnostics on, the misses
+    /// and their status too.
+    fn render_url_checks(&self, checked: &[(String, Probe)]) -> String {
+        let mut text = String::new();
+        for (url, probe) in checked.iter().filter(|(_, probe)| probe.exists) {
+            text.push_str(&format!(
+                "{} ({})\n",
+                probe.url,
+                installer_details(probe.content_length, probe.last_modified.as_deref())
+            ));
+            if probe.url != *url {
+                text.push_str(&format!("  redirected from {}\n", url));
+            }
+        }
+        let live = checked.iter().filter(|(_, probe)| probe.exists).count();
+        if live == 0 {
+            text.push_str(&format!(
+                "Nothing live among the {} URL(s)\n",
+                checked.len()
+            ));
+        }
+        if self.diagnostics {
+            for (url, probe) in checked.iter().filter(|(_, probe)| !probe.exists) {
+                match (probe.status, &probe.content_type) {
+                    (0, _) => text.push_str(&format!("Missing (no response): {}\n", url)),
+                    (status, Some(content_type)) => text.push_str(&format!(
+                        "Missing ({}, served as {}): {}\n",
+                        status, content_type, url
+                    )),
+                    (status, None) => text.push_str(&format!("Missing ({}): {}\n", status, url)),
+                }
+            }
+        }
+        text
+    }
+
+    /// Network settings from the settings dialog, shared by searches and downloads.
+    fn client_options(&self, timeout: Duration) -> ClientOptions {
+        ClientOptions {
+            timeout,
+            proxy: Some(self.proxy.clone()),
+            local_address: Some(self.local_address.clone()),
+            ip_family: self.ip_family,
+            user_agent: Some(self.user_agent.clone()),
+            headers: self.headers.lines().map(str::to_string).collect(),
+            follow_redirects: true,
+            ca_cert: Some(PathBuf::from(self.ca_cert.trim())),
+            accept_invalid_certs: self.accept_invalid_certs,
+            http2_prior_knowledge: self.http2_prior_knowledge,
+            compression: self.compression,
+            pool_max_idle_per_host: self
+                .limit_idle_connections
+                .then_some(self.max_idle_connections),
+            pool_idle_timeout: Some(Duration::from_secs(self.idle_timeout_secs))
+                .filter(|timeout| !timeout.is_zero()),
+        }
+    }
+
+    /// A shell command per found URL, in the order the Downloads list shows them.
+    fn download_commands(&self) -> String {
+        let dir = self.download_dir.trim();
+        let dir = (!dir.is_empty()).then(|| Path::new(dir));
+        Platform::all()
+            .iter()
+            .filter_map(|platform| self.found_urls.get(platform))
+            .flatten()
+            .map(|url| download_command(self.downloader, url, dir))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn start_download(&mut self, url: String) {
+        let client = match self.client_options(DOWNLOAD_TIMEOUT).build() {
+            Ok(client) => client,
+            Err(e) => {
+                self.downloads.insert(url, DownloadState::Failed(e));
+                return;
+            }
+        };
+
+        let dir = if self.download_dir.trim().is_empty() {
+            PathBuf::from(".")
+        } else {
+            PathBuf::from(self.download_dir.trim())
+        };
+
+        self.downloads
+            .insert(url.clone(), DownloadState::Running(0, None));
+        let tx = self.download_tx.clone();
+
+        self.runtime.spawn(async move {
+            let result = download_installer(&client, &url, &dir, |downloaded, total| {
+                let _ = tx.send(DownloadMessage::Progress(url.clone(), downloaded, total));
+            })
+            .await;
+            let _ = tx.send(DownloadMessage::Finished(url, result));
+        });
+    }
+
+    fn update_downloads(&mut self) {
+        while let Ok(msg) = self.download_rx.try_recv() {
+            match msg {
+                DownloadMessage::Progress(url, downloaded, total) => {
+                    self.downloads
+                        .insert(url, DownloadState::Running(downloaded, total));
+                }
+                DownloadMessage::Finished(url, Ok(path)) => {
+                    self.downloads.insert(url, DownloadState::Done(path));
+                }
+                DownloadMessage::Finished(url, Err(e)) => {
+                    self.downloads.insert(url, DownloadState::Failed(e));
+                }
+            }
+        }
+    }
+
+    fn save_last_results(&self) {
+        let Some(path) = results_cache_path() else {
+            return;
+        };
+        let cache = ResultsCache::new(self.searched_versions.clone(), self.found.clone());
+        if let Err(e) = cache.save(&path) {
+            eprintln!("{}", e);
+        }
+    }
+
+    fn load_last_results(&mut self) {
+        let cache = match results_cache_path()
+            .ok_or_else(|| "No storage directory for the results cache".to_string())
+            .and_then(|path| ResultsCache::load(&path))
+        {
+            Ok(cache) => cache,
+            Err(e) => {
+                self.displayed_results = format!("Error: {}", e);
+                return;
+            }
+        };
+
+        self.clear_results();
+        for installer in &cache.found {
+            self.found_urls
+                .entry(installer.platform)
+                .or_default()
+                .push(installer.url.clone());
+        }
+
+        self.progress = 1.0;
+        self.progress_text = format!(
+            "Loaded results for {} version(s) from {}",
+            cache.versions.len(),
+            format_age(cache.age())
+        );
+        self.searched_versions = cache.versions;
+        self.found = cache.found;
+
+        self.displayed_results = if self.found.is_empty() {
+            "Nothing found in the last search".to_string()
+        } else {
+            self.render_found()
+        };
+    }
+
+    /// Found installers grouped by platform, newest build first. With
+    /// "Latest only" each platform shows just its highest build per version.
+    /// The installers of `platform` as listed, newest first, and only the
+    /// newest of each version with "Latest only".
+    fn shown_installers(&self, platform: Platform) -> Vec<&CachedInstaller> {
+        let mut installers: Vec<&CachedInstaller> = self
+            .found
+            .iter()
+            .filter(|installer| installer.platform == platform)
+            .collect();
+        installers.sort_by_key(|installer| std::cmp::Reverse(installer.build));
+        if self.latest_only {
+            let mut seen_versions = Vec::new();
+            installers.retain(|installer| {
+                let first = !seen_versions.contains(&&installer.version);
+                seen_versions.push(&installer.version);
+                first
+            });
+        }
+        installers
+    }
+
+    fn render_found(&self) -> String {
+        if self.counts_only {
+            return self.render_counts();
+        }
+        if self.unique_builds {
+            return self.render_unique_builds();
+        }
+        let mut text = String::new();
+        for platform in Platform::all() {
+            let installers = self.shown_installers(platform);
+            if installers.is_empty() {
+                continue;
+            }
+
+            text.push_str(&format!("\n{}:\n", platform.name()));
+            for installer in installers {
+                text.push_str(&format!(
+                    "{} ({})\n",
+                    installer.url,
+                    installer_details(installer.size, installer.last_modified.as_deref())
+                ));
+            }
+        }
+        text.push_str(&self.render_coverage());
+        text
+    }
+
+    /// "Counts only": the builds listed per platform and in total.
+    fn render_counts(&self) -> String {
+        let mut text = String::new();
+        let mut total = 0;
+        for platform in Platform::all() {
+            let count = self.shown_installers(platform).len();
+            if count > 0 {
+                text.push_str(&format!("{}: {} builds\n", platform.name(), count));
+                total += count;
+            }
+        }
+        text.push_str(&format!("Total: {} builds\n", total));
+        text.push_str(&self.render_coverage());
+        text
+    }
+
+    /// "Unique builds": the distinct build numbers of each platform across
+    /// all versions, lowest first.
+    fn render_unique_builds(&self) -> String {
+        let mut text = String::new();
+        let mut total = 0;
+        for platform in Platform::all() {
+            let builds: BTreeSet<i32> = self
+                .found
+                .iter()
+                .filter(|installer| installer.platform == platform)
+                .map(|installer| installer.build)
+                .collect();
+            if builds.is_empty() {
+                continue;
+            }
+            let numbers: Vec<String> = builds.iter().map(i32::to_string).collect();
+            text.push_str(&format!(
+                "{}: {} builds\n  {}\n",
+                platform.name(),
+                builds.len(),
+                numbers.join(", ")
+            ));
+            total += builds.len();
+        }
+        text.push_str(&format!("Total: {} unique builds\n", total));
+        text.push_str(&self.render_coverage());
+        text
+    }
+
+    /// What each version's search covered, so a platform without results
+    /// shows whether it was skipped or how far it was checked.
+    fn render_coverage(&self) -> String {
+        let mut text = String::new();
+        if !self.coverage.is_empty() {
+            text.push_str("\nSearched:\n");
+        }
+        for searched in &self.coverage {
+            text.push_str(&format!("{}: {}\n", searched.version, searched.describe()));
+        }
+        text
+    }
+
+    /// Replaces the streamed results with the sorted (or collapsed) view.
+    fn rerender_found(&mut self) {
+        if !self.found.is_empty() {
+            self.flush_reveal();
+            self.displayed_results = self.render_found();
+        }
+    }
+
+    fn toggle_pause(&mut self) {
+        self.is_paused = !self.is_paused;
+        self.pause_flag.store(self.is_paused, Ordering::Relaxed);
+        if self.is_paused {
+            self.progress_text = "Paused".to_string();
+        } else {
+            self.error_storm.reset();
+        }
+    }
+
+    /// Enter starts a search, Space pauses or resumes it and Esc stops it.
+    /// Ignored while a text field has focus, so Enter in the versions box
+    /// still adds a line.
+    fn handle_shortcuts(&mut self, ctx: &egui::Context) {
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+        let (enter, space, escape) = ctx.input_mut(|i| {
+            (
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Space),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Escape),
+            )
+        });
+        if !self.is_searching {
+            if enter {
+                self.start_search();
+            }
+        } else if escape {
+            self.stop_search();
+        } else if space {
+            self.toggle_pause();
+        }
+    }
+
+    fn stop_search(&mut self) {
+        self.cancel_flag.store(true, Ordering::Relaxed);
+        self.pause_flag.store(false, Ordering::Relaxed);
+        self.is_paused = false;
+        self.is_searching = false;
+        self.rx = None;
+        self.progress_text = "Search stopped".to_string();
+        self.finish_run_report(true);
+        // A URL check has no checkpoint, and must not drop a resumable one.
+        if self.checkpoint.is_some() {
+            self.discard_checkpoint();
+        }
+    }
+
+    /// Fills in the counters and results of the run that just ended.
+    fn finish_run_report(&mut self, cancelled: bool) {
+        let Some(report) = &mut self.run_report else {
+            return;
+        };
+        let processed = self.processed_global.load(Ordering::Relaxed);
+        let network_errors = self.network_errors.load(Ordering::Relaxed);
+        let hits = self.found.len() as u64;
+        report.elapsed_secs = self.search_started.elapsed().as_secs_f64();
+        report.cancelled = cancelled;
+        report.stats = RunStats {
+            builds_checked: processed,
+            requests: self.request_counts.requests(),
+            retries: self.request_counts.retries(),
+            hits,
+            misses: processed.saturating_sub(hits + network_errors),
+            network_errors,
+        };
+        report.searched = self.coverage.clone();
+        report.found = self.found.clone();
+    }
+
+    /// Writes the last run's report as Markdown into the download folder,
+    /// or next to the settings when none is set.
+    fn save_run_report(&mut self) {
+        let Some(report) = &self.run_report else {
+            return;
+        };
+        let dir = match self.download_dir.trim() {
+            "" => eframe::storage_dir(APP_ID),
+            dir => Some(PathBuf::from(dir)),
+        };
+        let Some(dir) = dir else {
+            self.displayed_results = "Error: No folder to save the report in".to_string();
+            return;
+        };
+        let path = dir.join(format!("loaderspot-report-{}.md", report.started_at));
+        match report.save(&path) {
+            Ok(()) => self.progress_text = format!("Report saved to {}", path.display()),
+            Err(e) => self.displayed_results = format!("Error: {}", e),
+        }
+    }
+
+    /// Writes the listed URLs, one per line, into the download folder, or
+    /// next to the settings when none is set, for other download tools.
+    fn export_urls(&mut self) {
+        let dir = match self.download_dir.trim() {
+            "" => eframe::storage_dir(APP_ID),
+            dir => Some(PathBuf::from(dir)),
+        };
+        let Some(dir) = dir else {
+            self.displayed_results = "Error: No folder to export the URLs to".to_string();
+            return;
+        };
+        let mut urls = String::new();
+        for platform in Platform::all() {
+            for installer in self.shown_installers(platform) {
+                urls.push_str(&installer.url);
+                urls.push('\n');
+            }
+        }
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+        let path = dir.join(format!("loaderspot-urls-{}.txt", secs));
+        match std::fs::write(&path, urls) {
+            Ok(()) => self.progress_text = format!("URLs exported to {}", path.display()),
+            Err(e) => {
+                self.displayed_results = format!("Error: Cannot write {}: {}", path.display(), e)
+            }
+        }
+    }
+
+    /// Everything found, in the CLI's `--output-shape grouped` layout:
+    /// version -> platform -> builds, newest first.
+    fn results_json(&self) -> serde_json::Value {
+        // A resumed search can have found versions it didn't search this time.
+        let mut listed = self.searched_versions.clone();
+        for installer in &self.found {
+            if !listed.contains(&installer.version) {
+                listed.push(installer.version.clone());
+            }
+        }
 
-                if cancel.load(Ordering::Relaxed) {
-                    break;
+        let mut versions = serde_json::Map::new();
+        for version in &listed {
+            let mut platforms = serde_json::Map::new();
+            for platform in Platform::all() {
+                let mut installers: Vec<&CachedInstaller> = self
+                    .found
+                    .iter()
+                    .filter(|installer| installer.version == *version)
+                    .filter(|installer| installer.platform == platform)
+                    .collect();
+                if installers.is_empty() {
+                    continue;
                 }
+                installers.sort_by(|a, b| b.build.cmp(&a.build).then_with(|| a.url.cmp(&b.url)));
+                let builds: Vec<serde_json::Value> = installers
+                    .iter()
+                    .map(|installer| {
+                        let extension = file_name_from_url(&installer.url)
+                            .and_then(|name| name.rsplit_once('.'))
+                            .map(|(_, extension)| extension);
+                        serde_json::json!({
+                            "build": installer.build,
+                            "url": installer.url,
+                            "extension": extension,
+                            "size": installer.size,
+                            "last_modified": installer.last_modified,
+                        })
+                    })
+                    .collect();
+                platforms.insert(platform.code().to_string(), builds.into());
             }
+            versions.insert(version.clone(), platforms.into());
+        }
+        serde_json::json!({
+            "schema_version": RESULTS_SCHEMA_VERSION,
+            "versions": listed,
+            "results": versions,
+        })
+    }
 
-            let _ = tx.send(SearchMessage::CompleteAll);
-        });
+    /// Asks where to save the results as JSON and writes them there. A
+    /// cancelled dialog writes nothing.
+    fn save_results_json(&mut self) {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+        let mut dialog = rfd::FileDialog::new()
+            .set_title("Save results as JSON")
+            .set_file_name(format!("loaderspot-results-{}.json", secs))
+            .add_filter("JSON", &["json"]);
+        if !self.download_dir.trim().is_empty() {
+            dialog = dialog.set_directory(self.download_dir.trim());
+        }
+        // The Linux portal backend talks D-Bus through Tokio.
+        let picked = {
+            let _runtime = self.runtime.enter();
+            dialog.save_file()
+        };
+        let Some(path) = picked else {
+            return;
+        };
+
+        let json = serde_json::to_string_pretty(&self.results_json())
+            .expect("JSON values always serialize");
+        match std::fs::write(&path, json) {
+            Ok(()) => self.progress_text = format!("Results saved to {}", path.display()),
+            Err(e) => {
+                self.displayed_results = format!("Error: Cannot write {}: {}", path.display(), e)
+            }
+        }
     }
 
-    fn stop_search(&mut self) {
-        self.cancel_flag.store(true, Ordering::Relaxed);
-        self.pause_flag.store(false, Ordering::Relaxed);
-        self.is_paused = false;
-        self.is_searching = false;
-        self.rx = None;
-        self.progress_text = "Search stopped".to_string();
+    /// Installers found so far, across all platforms.
+    fn found_count(&self) -> usize {
+        self.found_urls.values().map(Vec::len).sum()
+    }
+
+    /// E.g. "Found 7 installers across 3 platforms in 42s".
+    fn completion_summary(&self) -> String {
+        let count = self.found_count();
+        let platforms = self
+            .found_urls
+            .values()
+            .filter(|urls| !urls.is_empty())
+            .count();
+        format!(
+            "Found {} installer{} across {} platform{} in {}",
+            count,
+            if count == 1 { "" } else { "s" },
+            platforms,
+            if platforms == 1 { "" } else { "s" },
+            format_eta(self.search_started.elapsed().as_secs())
+        )
+    }
+
+    /// Which version lines the last search skipped, so a typo in one of many
+    /// can be found and fixed.
+    fn rejected_versions_summary(&self, ui: &mut egui::Ui) {
+        const SHOWN: usize = 10;
+        let mut text = self
+            .rejected_versions
+            .iter()
+            .take(SHOWN)
+            .map(|(line, version)| format!("line {} '{}'", line, version))
+            .collect::<Vec<_>>()
+            .join(", ");
+        if self.rejected_versions.len() > SHOWN {
+            text.push_str(&format!(
+                " and {} more",
+                self.rejected_versions.len() - SHOWN
+            ));
+        }
+        let format = if self.strict_version {
+            "Expected e.g. 1.2.60.564.gcc6305cb; strict checking needs the 8 character hash"
+        } else {
+            "Expected e.g. 1.2.60.564 or 1.2.60.564.gcc6305cb"
+        };
+        ui.label(egui::RichText::new(format!("Invalid: {}", text)).color(WARNING_COLOR))
+            .on_hover_text(format);
     }
 
     fn clear_results(&mut self) {
@@ -571,40 +2771,113 @@ impl SpotifyFinderApp {
         self.reveal_queue.clear();
         self.current_reveal = None;
         self.found_urls.clear();
+        self.found.clear();
+        self.coverage.clear();
+        self.failed_checks = FailedChecks::default();
         self.progress = 0.0;
         self.progress_text.clear();
         self.total_work = 0;
         self.processed_global.store(0, Ordering::Relaxed);
+        self.platform_processed.reset();
+        self.platform_progress.clear();
+    }
+
+    /// Requests per second over the last `RATE_WINDOW`, or since the search
+    /// started while the window is still filling.
+    fn request_rate(&mut self, processed: u64) -> f64 {
+        let now = Instant::now();
+        if self.is_paused {
+            // Restart the window on resume instead of averaging in the pause.
+            self.rate_samples.clear();
+        }
+        self.rate_samples.push_back((now, processed));
+        while self
+            .rate_samples
+            .get(1)
+            .is_some_and(|(t, _)| now.duration_since(*t) >= RATE_WINDOW)
+        {
+            self.rate_samples.pop_front();
+        }
+
+        let (oldest, oldest_processed) = self.rate_samples[0];
+        let window = now.duration_since(oldest).as_secs_f64();
+        if window >= 1.0 {
+            processed.saturating_sub(oldest_processed) as f64 / window
+        } else {
+            processed as f64 / self.search_started.elapsed().as_secs_f64().max(0.001)
+        }
+    }
+
+    /// ", ~420 req/s, ETA 3m 05s", or nothing until the first requests finish.
+    fn throughput_text(&mut self, processed: u64) -> String {
+        let rate = self.request_rate(processed);
+        if rate < 0.5 {
+            return String::new();
+        }
+        let eta = (self.total_work.saturating_sub(processed) as f64 / rate).ceil() as u64;
+        // Ladder searches usually finish well before `total_work`.
+        let bound = if self.total_work_is_exact { "" } else { "≤ " };
+        format!(", ~{:.0} req/s, ETA {}{}", rate, bound, format_eta(eta))
     }
 
     fn update_search_progress(&mut self) {
         let current_processed = self.processed_global.load(Ordering::Relaxed);
+        let throughput = if self.is_searching {
+            self.throughput_text(current_processed)
+        } else {
+            String::new()
+        };
+        for (platform, (processed, _)) in self.platform_progress.iter_mut() {
+            *processed = self.platform_processed.get(*platform);
+        }
         let denom = if self.total_work == 0 {
             1
         } else {
             self.total_work
         };
-        self.progress = current_processed as f32 / denom as f32;
+        // The engine counts each build once, however many retries it took.
+        debug_assert!(
+            self.total_work == 0 || current_processed <= self.total_work,
+            "{} builds checked of {}",
+            current_processed,
+            self.total_work
+        );
+        self.progress = (current_processed as f32 / denom as f32).min(1.0);
 
         if let Some(v) = &self.current_version {
             let short = short_version(v);
             if self.total_versions > 1 {
                 self.progress_text = format!(
-                    "Checking: {}/{}, Version: {}, No. {}/{}",
+                    "Checking: {}/{}, Version: {}, No. {}/{}{}",
                     current_processed,
                     denom,
                     short,
                     self.current_version_index,
-                    self.total_versions
+                    self.total_versions,
+                    throughput
                 );
             } else {
                 self.progress_text = format!(
-                    "Checking: {}/{}, Version: {}",
-                    current_processed, denom, short
+                    "Checking: {}/{}, Version: {}{}",
+                    current_processed, denom, short, throughput
                 );
             }
         } else if self.is_searching {
-            self.progress_text = format!("Checking: {}/{}", current_processed, denom);
+            self.progress_text = format!("Checking: {}/{}{}", current_processed, denom, throughput);
+        }
+
+        if self.is_searching {
+            if let Some(remaining) = self.backoff.remaining() {
+                self.progress_text = format!(
+                    "Rate limited, backing off {}s",
+                    remaining.as_secs_f32().ceil() as u64
+                );
+            }
+            // The engine paused itself; Resume resets the storm.
+            if self.error_storm.tripped() {
+                self.is_paused = true;
+                self.progress_text = "Network appears down, paused".to_string();
+            }
         }
 
         if let Some(rx_owned) = self.rx.take() {
@@ -615,46 +2888,176 @@ impl SpotifyFinderApp {
                 processed_this_frame += 1;
 
                 match msg {
-                    SearchMessage::Result(url, platform) => {
-                        let entry = self.found_urls.entry(platform).or_default();
+                    SearchMessage::Result(event) => {
+                        let entry = self.found_urls.entry(event.platform).or_default();
+                        if entry.contains(&event.url) {
+                            continue;
+                        }
                         let first = entry.is_empty();
-                        entry.push(url.clone());
+                        entry.push(event.url.clone());
 
-                        if first {
-                            self.reveal_queue
-                                .push_back(format!("\n{}:\n", platform.name()));
+                        if !self.latest_only && !self.counts_only && !self.unique_builds {
+                            if first {
+                                self.push_result_text(format!("\n{}:\n", event.platform.name()));
+                            }
+                            self.push_result_text(format!(
+                                "{} ({})\n",
+                                event.url,
+                                installer_details(event.size, event.last_modified.as_deref())
+                            ));
+                        }
+                        self.found.push(CachedInstaller {
+                            version: event.version.to_string(),
+                            platform: event.platform,
+                            build: event.build,
+                            url: event.url,
+                            size: event.size,
+                            last_modified: event.last_modified,
+                        });
+                        if self.latest_only || self.counts_only || self.unique_builds {
+                            self.rerender_found();
                         }
-                        self.reveal_queue.push_back(format!("{}\n", url));
                     }
                     SearchMessage::VersionStart(version, idx, total) => {
                         self.current_version = Some(version);
                         self.current_version_index = idx;
                         self.total_versions = total;
                     }
-                    SearchMessage::Complete(_version) => {}
+                    SearchMessage::Complete(version) => {
+                        if let Some(checkpoint) = &mut self.checkpoint {
+                            if !self.cancel_flag.load(Ordering::Relaxed) {
+                                checkpoint.complete_version(&version);
+                            }
+                        }
+                    }
+                    SearchMessage::Searched(searched) => {
+                        self.coverage.push(searched);
+                    }
+                    SearchMessage::AlreadyKnown(version) => {
+                        self.known_skipped += 1;
+                        if let Some(checkpoint) = &mut self.checkpoint {
+                            checkpoint.complete_version(&version);
+                        }
+                    }
+                    SearchMessage::Failed(e) => {
+                        self.is_searching = false;
+                        self.progress = 0.0;
+                        self.progress_text = "Search failed".to_string();
+                        self.displayed_results = format!("Error: {}", e);
+                        self.run_report = None;
+                        self.discard_checkpoint();
+                        completed = true;
+                    }
+                    // The engine cancelled the run, so it is not complete.
+                    SearchMessage::CompleteAll if self.reachability.unreachable() => {
+                        self.is_searching = false;
+                        self.progress = 0.0;
+                        self.progress_text = "Cannot reach server".to_string();
+                        self.displayed_results =
+                            "Error: Cannot reach server, check your connection".to_string();
+                        self.pause_flag.store(false, Ordering::Relaxed);
+                        self.cancel_flag.store(false, Ordering::Relaxed);
+                        self.is_paused = false;
+                        self.run_report = None;
+                        self.discard_checkpoint();
+                        completed = true;
+                    }
                     SearchMessage::CompleteAll => {
+                        // The worker's shared counter is the only source of
+                        // progress; an uncancelled run must account for every URL.
+                        debug_assert!(processed_all(
+                            self.total_work,
+                            self.total_work_is_exact,
+                            self.processed_global.load(Ordering::Relaxed)
+                        ));
                         self.is_searching = false;
                         self.progress = 1.0;
-                        self.progress_text = "Search complete".to_string();
+                        self.progress_text = self.completion_summary();
+                        if self.notify_when_done {
+                            notify_desktop(self.completion_summary());
+                        }
 
-                        let mut found_any = false;
+                        let found_any = self.found_count() > 0;
 
-                        for platform in Platform::all() {
-                            if let Some(urls) = self.found_urls.get(&platform) {
-                                if !urls.is_empty() {
-                                    found_any = true;
+                        self.pause_flag.store(false, Ordering::Relaxed);
+                        self.cancel_flag.store(false, Ordering::Relaxed);
+                        self.is_paused = false;
+                        if found_any {
+                            self.rerender_found();
+                        } else if self.known_skipped == self.total_versions {
+                            self.displayed_results =
+                                "Nothing new: every version is already in versions.json"
+                                    .to_string();
+                        } else {
+                            self.displayed_results =
+                                "Nothing found, consider increasing the search range".to_string();
+                            self.displayed_results.push_str(&self.render_coverage());
+                        }
+                        if self.diagnostics {
+                            let summary = self.status_counts.summary();
+                            if !summary.is_empty() {
+                                self.progress_text =
+                                    format!("{}, misses: {}", self.progress_text, summary);
+                                if !found_any {
+                                    self.displayed_results.push_str(&format!(
+                                        "\nMissed builds answered: {}",
+                                        summary
+                                    ));
                                 }
                             }
+                            if self.unexpected_types.total() > 0 {
+                                self.progress_text = format!(
+                                    "{}, not installers: {}",
+                                    self.progress_text,
+                                    self.unexpected_types.summary()
+                                );
+                            }
                         }
+                        self.save_last_results();
+                        self.finish_run_report(false);
+                        self.discard_checkpoint();
 
+                        completed = true;
+                    }
+                    SearchMessage::UrlsChecked(checked) => {
+                        let live = checked.iter().filter(|(_, probe)| probe.exists).count();
+                        self.is_searching = false;
+                        self.progress = 1.0;
+                        self.progress_text = format!(
+                            "{} of {} URL(s) live in {}",
+                            live,
+                            checked.len(),
+                            format_eta(self.search_started.elapsed().as_secs())
+                        );
                         self.pause_flag.store(false, Ordering::Relaxed);
                         self.cancel_flag.store(false, Ordering::Relaxed);
                         self.is_paused = false;
-                        if !found_any {
-                            self.displayed_results =
-                                "Nothing found, consider increasing the search range".to_string();
+                        self.displayed_results = self.render_url_checks(&checked);
+                        if self.notify_when_done {
+                            notify_desktop(self.progress_text.clone());
+                        }
+                        completed = true;
+                    }
+                    SearchMessage::Rescanned(total, rescan) => {
+                        self.is_searching = false;
+                        self.progress = 1.0;
+                        self.progress_text = format!(
+                            "Recovered {} of {} failed check(s), {} still failing",
+                            rescan.recovered,
+                            total,
+                            rescan.still_failing.len()
+                        );
+                        self.pause_flag.store(false, Ordering::Relaxed);
+                        self.cancel_flag.store(false, Ordering::Relaxed);
+                        self.is_paused = false;
+                        self.failed_checks = FailedChecks::from(rescan.still_failing);
+                        if !rescan.found.is_empty() {
+                            self.rerender_found();
+                            self.save_last_results();
+                        }
+                        if self.notify_when_done {
+                            notify_desktop(self.progress_text.clone());
                         }
-
                         completed = true;
                     }
                 }
@@ -672,11 +3075,76 @@ impl SpotifyFinderApp {
 }
 
 impl eframe::App for SpotifyFinderApp {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, SETTINGS_KEY, &self.settings());
+        eframe::set_value(storage, HISTORY_KEY, &self.history);
+        eframe::set_value(storage, FAVORITES_KEY, &self.favorites);
+        // Also runs on exit, so closing mid-search leaves it resumable.
+        if self.is_searching {
+            self.save_checkpoint();
+        }
+    }
+
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        let dropped = ctx.input(|i| i.raw.dropped_files.clone());
+        if !dropped.is_empty() && !self.is_searching {
+            self.load_dropped_versions(&dropped);
+        }
+        if let Some(requested) = self.clipboard_requested {
+            let pasted = ctx.input(|i| {
+                i.events.iter().find_map(|event| match event {
+                    egui::Event::Paste(text) => Some(text.clone()),
+                    _ => None,
+                })
+            });
+            if let Some(text) = pasted {
+                self.clipboard_requested = None;
+                self.load_pasted_versions(&text);
+            } else if ctx.cumulative_pass_nr() > requested + 1 {
+                // No paste event: the clipboard held no text.
+                self.clipboard_requested = None;
+                self.displayed_results = "The clipboard is empty".to_string();
+            } else {
+                ctx.request_repaint();
+            }
+        }
+        if ctx.input(|i| !i.raw.hovered_files.is_empty()) {
+            let painter = ctx.layer_painter(egui::LayerId::new(
+                egui::Order::Foreground,
+                egui::Id::new("drop_overlay"),
+            ));
+            let rect = ctx.screen_rect();
+            painter.rect_filled(rect, 0.0, egui::Color32::from_black_alpha(180));
+            painter.text(
+                rect.center(),
+                egui::Align2::CENTER_CENTER,
+                "Drop a version list (.txt) or versions.json",
+                egui::FontId::proportional(20.0),
+                egui::Color32::WHITE,
+            );
+        }
+        self.handle_shortcuts(ctx);
+        self.large_search_dialog(ctx);
+        self.settings_dialog(ctx);
         self.update_search_progress();
+        if self.is_searching && self.last_checkpoint_save.elapsed() >= CHECKPOINT_INTERVAL {
+            self.save_checkpoint();
+        }
+        self.update_downloads();
+        if self.reveal_instantly() {
+            self.flush_reveal();
+        }
         self.advance_reveal();
 
-        if self.is_searching || !self.reveal_queue.is_empty() || self.current_reveal.is_some() {
+        let downloading = self
+            .downloads
+            .values()
+            .any(|state| matches!(state, DownloadState::Running(..)));
+        if self.is_searching
+            || downloading
+            || !self.reveal_queue.is_empty()
+            || self.current_reveal.is_some()
+        {
             ctx.request_repaint();
         }
 
@@ -685,22 +3153,64 @@ impl eframe::App for SpotifyFinderApp {
             ui.add_space(5.0);
 
             ui.horizontal(|ui| {
+                // Split the row between the two columns in the 360:340 ratio
+                // of the default window, minus frame margins and spacing.
+                let content = (ui.available_width() - COLUMN_CHROME).max(MIN_COLUMNS_WIDTH);
+                let versions_width = content * 360.0 / MIN_COLUMNS_WIDTH;
+                let settings_width = content - versions_width;
+
                 egui::Frame::group(ui.style())
                     .fill(egui::Color32::from_gray(30))
                     .show(ui, |ui| {
-                        ui.set_width(360.0);
+                        ui.set_width(versions_width);
                         ui.vertical(|ui| {
-                            ui.label(egui::RichText::new("Spotify Versions:").strong());
+                            ui.horizontal(|ui| {
+                                ui.label(egui::RichText::new("Spotify Versions:").strong());
+                                ui.with_layout(
+                                    egui::Layout::right_to_left(egui::Align::Center),
+                                    |ui| {
+                                        self.history_menu(ui);
+                                        let pin = ui
+                                            .add_enabled(
+                                                !self.is_searching,
+                                                egui::Button::new("📌 Pin"),
+                                            )
+                                            .on_hover_text(
+                                                "Pin the versions in the box as favorites",
+                                            );
+                                        if pin.clicked() {
+                                            self.pin_versions();
+                                        }
+                                        let paste = ui
+                                            .add_enabled(
+                                                !self.is_searching,
+                                                egui::Button::new("📋 From clipboard"),
+                                            )
+                                            .on_hover_text(
+                                                "Add every version found in the copied text",
+                                            );
+                                        if paste.clicked() {
+                                            ui.ctx().send_viewport_cmd(
+                                                egui::ViewportCommand::RequestPaste,
+                                            );
+                                            self.clipboard_requested =
+                                                Some(ui.ctx().cumulative_pass_nr());
+                                            ui.ctx().request_repaint();
+                                        }
+                                    },
+                                );
+                            });
                             ui.label(
                                 egui::RichText::new("One per line. Example: 1.1.68.632.g2b11de83")
                                     .size(12.0)
                                     .color(egui::Color32::GRAY),
                             );
+                            self.favorites_row(ui);
 
                             egui::Frame::group(ui.style())
                                 .fill(egui::Color32::from_gray(20))
                                 .show(ui, |ui| {
-                                    let desired = egui::Vec2::new(348.0, 106.0);
+                                    let desired = egui::Vec2::new(versions_width - 12.0, 106.0);
                                     let (rect, _resp) =
                                         ui.allocate_exact_size(desired, egui::Sense::click());
                                     ui.painter().rect_filled(
@@ -728,39 +3238,71 @@ impl eframe::App for SpotifyFinderApp {
 
                             ui.add_space(5.0);
 
-                            ui.label(egui::RichText::new("Build Number Range:").strong());
                             ui.horizontal(|ui| {
-                                ui.label("From:");
-                                let desired = egui::Vec2::new(120.0, ui.spacing().interact_size.y);
-                                let (rect, _resp) =
-                                    ui.allocate_exact_size(desired, egui::Sense::click());
-                                ui.painter()
-                                    .rect_filled(rect, 4.0, egui::Color32::from_gray(20));
-                                ui.painter().rect_stroke(
-                                    rect,
-                                    4.0,
-                                    egui::Stroke::new(1.0, egui::Color32::from_gray(80)),
-                                );
-                                ui.put(
-                                    rect,
-                                    egui::TextEdit::singleline(&mut self.range_from).frame(false),
-                                );
-
-                                ui.label("To:");
-                                let desired = egui::Vec2::new(120.0, ui.spacing().interact_size.y);
-                                let (rect, _resp) =
-                                    ui.allocate_exact_size(desired, egui::Sense::click());
-                                ui.painter()
-                                    .rect_filled(rect, 4.0, egui::Color32::from_gray(20));
-                                ui.painter().rect_stroke(
-                                    rect,
-                                    4.0,
-                                    egui::Stroke::new(1.0, egui::Color32::from_gray(80)),
-                                );
-                                ui.put(
-                                    rect,
-                                    egui::TextEdit::singleline(&mut self.range_to).frame(false),
-                                );
+                                ui.label(egui::RichText::new("Build Number Range:").strong());
+                                ui.checkbox(&mut self.smart_search, "Smart search")
+                                    .on_hover_text(
+                                        "Find the newest builds: scan 0-1000, then 1000 more \
+                                         at a time until every platform has a hit",
+                                    );
+                            });
+                            let range_enabled = !self.smart_search;
+                            ui.add_enabled_ui(range_enabled, |ui| {
+                                ui.horizontal(|ui| {
+                                    ui.label("From:");
+                                    range_field(ui, &mut self.range_from);
+                                    ui.label("To:");
+                                    range_field(ui, &mut self.range_to);
+                                    if ui
+                                        .small_button("➕")
+                                        .on_hover_text("Also scan another range")
+                                        .clicked()
+                                    {
+                                        self.extra_ranges
+                                            .push((String::new(), String::new()));
+                                    }
+                                });
+                                let mut removed = None;
+                                for (i, (from, to)) in self.extra_ranges.iter_mut().enumerate() {
+                                    ui.horizontal(|ui| {
+                                        ui.label("From:");
+                                        range_field(ui, from);
+                                        ui.label("To:");
+                                        range_field(ui, to);
+                                        if ui.small_button("➖").clicked() {
+                                            removed = Some(i);
+                                        }
+                                    });
+                                }
+                                if let Some(i) = removed {
+                                    self.extra_ranges.remove(i);
+                                }
+                                ui.checkbox(&mut self.build_hints, "Known builds first")
+                                    .on_hover_text(
+                                        "Scan near the builds of known neighbouring versions \
+                                         (from versions.json) first, then the rest of the range",
+                                    );
+                                ui.horizontal(|ui| {
+                                    ui.checkbox(&mut self.max_gap_enabled, "Stop after")
+                                        .on_hover_text(
+                                            "Stop a platform once this many builds past its last \
+                                             hit were all missing. Never stops before the first hit",
+                                        );
+                                    ui.add_enabled(
+                                        self.max_gap_enabled,
+                                        egui::DragValue::new(&mut self.max_gap).range(1..=5000),
+                                    );
+                                    ui.label("misses");
+                                });
+                                ui.horizontal(|ui| {
+                                    ui.label("Check every").on_hover_text(
+                                        "Check only every Nth build number, a quick sweep \
+                                         of a wide range. Search a narrower range around \
+                                         the hits afterwards for the builds in between",
+                                    );
+                                    ui.add(egui::DragValue::new(&mut self.step).range(1..=1000));
+                                    ui.label(if self.step == 1 { "build" } else { "builds" });
+                                });
                             });
                         });
                     });
@@ -771,7 +3313,7 @@ impl eframe::App for SpotifyFinderApp {
                     egui::Frame::group(ui.style())
                         .fill(egui::Color32::from_gray(30))
                         .show(ui, |ui| {
-                            ui.set_width(340.0);
+                            ui.set_width(settings_width);
                             ui.label(egui::RichText::new("Target Platforms:").strong());
 
                             ui.horizontal(|ui| {
@@ -828,6 +3370,26 @@ impl eframe::App for SpotifyFinderApp {
 
                                 ui.add_space(4.0);
                                 ui.label(egui::RichText::new("All Platforms").size(12.0));
+
+                                ui.with_layout(
+                                    egui::Layout::right_to_left(egui::Align::Center),
+                                    |ui| {
+                                        for (label, windows, macos) in
+                                            [("macOS", false, true), ("Windows", true, false)]
+                                        {
+                                            if ui
+                                                .selectable_label(
+                                                    self.os_selected(windows, macos),
+                                                    label,
+                                                )
+                                                .on_hover_text(format!("Only the {} platforms", label))
+                                                .clicked()
+                                            {
+                                                self.select_os(windows, macos);
+                                            }
+                                        }
+                                    },
+                                );
                             });
 
                             ui.add_space(5.0);
@@ -853,8 +3415,24 @@ impl eframe::App for SpotifyFinderApp {
                     egui::Frame::group(ui.style())
                         .fill(egui::Color32::from_gray(30))
                         .show(ui, |ui| {
-                            ui.set_width(340.0);
-                            ui.label(egui::RichText::new("Advanced:").strong());
+                            ui.set_width(settings_width);
+                            ui.horizontal(|ui| {
+                                ui.label(egui::RichText::new("Connections:").strong());
+                                ui.with_layout(
+                                    egui::Layout::right_to_left(egui::Align::Center),
+                                    |ui| {
+                                        if ui
+                                            .button("⚙ Settings")
+                                            .on_hover_text(
+                                                "Network, output and behaviour options",
+                                            )
+                                            .clicked()
+                                        {
+                                            self.settings_open = !self.settings_open;
+                                        }
+                                    },
+                                );
+                            });
 
                             ui.add_space(5.0);
 
@@ -954,6 +3532,7 @@ impl eframe::App for SpotifyFinderApp {
                                             (MAX_CONNECTION_OPTIONS.len() - 1) as f32;
                                         let idx_f = (new_frac * max_idx_usize).round();
                                         self.max_connections_index = idx_f as usize;
+                                        self.custom_connections.clear();
                                     }
                                 }
 
@@ -969,44 +3548,69 @@ impl eframe::App for SpotifyFinderApp {
                                 );
                             });
 
-                            ui.add_space(5.0);
-
-                            ui.checkbox(&mut self.report_unknown, "Report unknown versions");
+                            ui.horizontal(|ui| {
+                                ui.label("Custom:");
+                                ui.add(
+                                    egui::TextEdit::singleline(&mut self.custom_connections)
+                                        .hint_text("overrides slider")
+                                        .desired_width(100.0),
+                                );
+                            });
                         });
                 });
             });
 
             ui.add_space(8.0);
 
+            if let Some(checkpoint) = self.resumable.as_ref().filter(|_| !self.is_searching) {
+                let done = checkpoint.completed.len();
+                let total = checkpoint.params.versions.len();
+                let age = format_age(checkpoint.age());
+                ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "⏯ A search was interrupted {}: {} of {} version(s) done",
+                            age, done, total
+                        ))
+                        .color(egui::Color32::from_rgb(255, 200, 0)),
+                    );
+                    if ui
+                        .button("Resume")
+                        .on_hover_text("Fill in the form and continue where it stopped")
+                        .clicked()
+                    {
+                        self.resume_search();
+                    }
+                    if ui.button("Discard").clicked() {
+                        self.discard_checkpoint();
+                    }
+                });
+                ui.add_space(4.0);
+            }
+
             ui.horizontal(|ui| {
                 let btn_size = egui::Vec2::new(120.0, 28.0);
 
                 if self.is_searching {
-                    if self.is_paused {
-                        if ui
-                            .add_sized(btn_size, egui::Button::new("▶ Resume"))
-                            .clicked()
-                        {
-                            self.is_paused = false;
-                            self.pause_flag.store(false, Ordering::Relaxed);
-                        }
-                    } else if ui
-                        .add_sized(btn_size, egui::Button::new("⏸ Pause"))
+                    let label = if self.is_paused { "▶ Resume" } else { "⏸ Pause" };
+                    if ui
+                        .add_sized(btn_size, egui::Button::new(label))
+                        .on_hover_text("Space")
                         .clicked()
                     {
-                        self.is_paused = true;
-                        self.pause_flag.store(true, Ordering::Relaxed);
-                        self.progress_text = "Paused".to_string();
+                        self.toggle_pause();
                     }
 
                     if ui
                         .add_sized(btn_size, egui::Button::new("⏹ Stop"))
+                        .on_hover_text("Esc")
                         .clicked()
                     {
                         self.stop_search();
                     }
                 } else if ui
                     .add_sized(btn_size, egui::Button::new("▶ Start Search"))
+                    .on_hover_text("Enter")
                     .clicked()
                 {
                     self.start_search();
@@ -1019,6 +3623,51 @@ impl eframe::App for SpotifyFinderApp {
                     {
                         self.clear_results();
                     }
+
+                    if ui
+                        .add_sized(btn_size, egui::Button::new("📂 Load last results"))
+                        .clicked()
+                    {
+                        self.load_last_results();
+                    }
+
+                    if ui
+                        .add_sized(btn_size, egui::Button::new("🔍 Preview URLs"))
+                        .clicked()
+                    {
+                        self.preview_urls();
+                    }
+
+                    // Hidden while searching, when the row has no room for it.
+                    if !self.is_searching
+                        && self.run_report.is_some()
+                        && ui
+                            .add_sized(btn_size, egui::Button::new("💾 Save report"))
+                            .on_hover_text(
+                                "Save a Markdown summary of the last search: settings, \
+                                 request counts, time taken and the installers found",
+                            )
+                            .clicked()
+                    {
+                        self.save_run_report();
+                    }
+
+                    let failures = self.failed_checks.len();
+                    if !self.is_searching
+                        && failures > 0
+                        && ui
+                            .add_sized(
+                                btn_size,
+                                egui::Button::new(format!("🔁 Rescan failures ({})", failures)),
+                            )
+                            .on_hover_text(
+                                "Check again only the builds that got no response or \
+                                 stayed rate-limited in the last search",
+                            )
+                            .clicked()
+                    {
+                        self.rescan_failures();
+                    }
                 });
 
                 if self.is_searching {
@@ -1040,11 +3689,250 @@ impl eframe::App for SpotifyFinderApp {
             if self.is_searching || self.progress > 0.0 {
                 ui.add_space(5.0);
                 ui.add(egui::ProgressBar::new(self.progress).text(&self.progress_text));
+
+                if self.platform_progress.len() > 1 {
+                    let platforms: Vec<Platform> = Platform::all()
+                        .into_iter()
+                        .filter(|p| self.platform_progress.contains_key(p))
+                        .collect();
+                    let spacing = ui.spacing().item_spacing.x;
+                    let bar_width = (ui.available_width() - spacing * (platforms.len() - 1) as f32)
+                        / platforms.len() as f32;
+
+                    ui.horizontal(|ui| {
+                        for platform in platforms {
+                            let (processed, total) = self.platform_progress[&platform];
+                            let fraction = if total == 0 {
+                                0.0
+                            } else {
+                                processed as f32 / total as f32
+                            };
+                            ui.add(
+                                egui::ProgressBar::new(fraction)
+                                    .desired_width(bar_width)
+                                    .desired_height(14.0)
+                                    .text(
+                                        egui::RichText::new(format!(
+                                            "{} {}/{}",
+                                            platform.name(),
+                                            processed,
+                                            total
+                                        ))
+                                        .size(10.0),
+                                    ),
+                            );
+                        }
+                    });
+                }
             }
 
             ui.add_space(8.0);
 
-            ui.label(egui::RichText::new("Search Results").strong());
+            egui::CollapsingHeader::new("Check URLs")
+                .id_salt("check_urls")
+                .show(ui, |ui| {
+                    ui.label("Installer URLs to check as they are, one per line:");
+                    ui.add(
+                        egui::TextEdit::multiline(&mut self.url_list)
+                            .font(egui::TextStyle::Monospace)
+                            .hint_text("https://upgrade.scdn.co/upgrade/client/...")
+                            .desired_rows(3)
+                            .desired_width(f32::INFINITY),
+                    );
+                    if ui
+                        .add_enabled(!self.is_searching, egui::Button::new("🔗 Check URLs"))
+                        .on_hover_text(
+                            "Reports which URLs are live, with their size. With \
+                             diagnostics on, the misses are listed with their status",
+                        )
+                        .clicked()
+                    {
+                        self.check_url_list();
+                    }
+                });
+
+            if !self.found_urls.is_empty() {
+                let mut requested = Vec::new();
+
+                egui::CollapsingHeader::new("Downloads").show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        egui::ComboBox::from_id_salt("downloader")
+                            .selected_text(self.downloader.to_string())
+                            .width(70.0)
+                            .show_ui(ui, |ui| {
+                                for tool in [Downloader::Curl, Downloader::Wget] {
+                                    ui.selectable_value(
+                                        &mut self.downloader,
+                                        tool,
+                                        tool.to_string(),
+                                    );
+                                }
+                            });
+                        if ui
+                            .button("📋 Copy as commands")
+                            .on_hover_text("One command per installer, saving into \"Download to\"")
+                            .clicked()
+                        {
+                            ui.ctx().copy_text(self.download_commands());
+                        }
+                        if ui
+                            .button("💾 Export URLs (.txt)")
+                            .on_hover_text(
+                                "Save the listed URLs, one per line, into \"Download to\" \
+                                 for a batch downloader. Honours \"Latest only\"",
+                            )
+                            .clicked()
+                        {
+                            self.export_urls();
+                        }
+                        if ui
+                            .button("💾 Save as JSON")
+                            .on_hover_text(
+                                "Save every installer found, grouped by version and \
+                                 platform like the CLI's --output-shape grouped",
+                            )
+                            .clicked()
+                        {
+                            self.save_results_json();
+                        }
+                    });
+
+                    egui::ScrollArea::vertical()
+                        .id_salt("downloads")
+                        .max_height(120.0)
+                        .show(ui, |ui| {
+                            for platform in Platform::all() {
+                                let Some(urls) = self.found_urls.get(&platform) else {
+                                    continue;
+                                };
+                                for url in urls {
+                                    ui.horizontal(|ui| {
+                                        let running = matches!(
+                                            self.downloads.get(url),
+                                            Some(DownloadState::Running(..))
+                                        );
+                                        if ui
+                                            .add_enabled(!running, egui::Button::new("⬇ Download"))
+                                            .clicked()
+                                        {
+                                            requested.push(url.clone());
+                                        }
+                                        if ui
+                                            .button("🌐 Open")
+                                            .on_hover_text(
+                                                "Open in the default browser, which \
+                                                 usually starts downloading it",
+                                            )
+                                            .clicked()
+                                        {
+                                            ui.ctx().open_url(egui::OpenUrl::new_tab(url));
+                                        }
+                                        if ui.button("📋").on_hover_text("Copy the URL").clicked()
+                                        {
+                                            ui.ctx().copy_text(url.clone());
+                                        }
+                                        ui.label(file_name_from_url(url).unwrap_or(url));
+
+                                        match self.downloads.get(url) {
+                                            Some(DownloadState::Running(downloaded, total)) => {
+                                                let text = match total {
+                                                    Some(total) if *total > 0 => {
+                                                        format!("{}%", downloaded * 100 / total)
+                                                    }
+                                                    _ => format_size(Some(*downloaded)),
+                                                };
+                                                ui.label(text);
+                                            }
+                                            Some(DownloadState::Done(path)) => {
+                                                ui.label(
+                                                    egui::RichText::new(format!(
+                                                        "Saved to {}",
+                                                        path.display()
+                                                    ))
+                                                    .color(egui::Color32::from_rgb(0, 200, 0)),
+                                                );
+                                            }
+                                            Some(DownloadState::Failed(e)) => {
+                                                ui.label(
+                                                    egui::RichText::new(e).color(
+                                                        egui::Color32::from_rgb(255, 80, 80),
+                                                    ),
+                                                );
+                                            }
+                                            None => {}
+                                        }
+                                    });
+                                }
+                            }
+                        });
+                });
+
+                for url in requested {
+                    self.start_download(url);
+                }
+
+                ui.add_space(5.0);
+            }
+
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new("Search Results").strong());
+                let found = self.found_count();
+                if found > 0 || self.is_searching {
+                    ui.label(
+                        egui::RichText::new(format!("Found: {}", found))
+                            .color(egui::Color32::LIGHT_GREEN),
+                    );
+                }
+                if ui
+                    .checkbox(&mut self.latest_only, "Latest only")
+                    .on_hover_text("Show only the highest build per platform")
+                    .changed()
+                {
+                    self.rerender_found();
+                }
+                if ui
+                    .checkbox(&mut self.counts_only, "Counts only")
+                    .on_hover_text("Show how many builds each platform has instead of the URLs")
+                    .changed()
+                {
+                    self.unique_builds &= !self.counts_only;
+                    self.rerender_found();
+                }
+                if ui
+                    .checkbox(&mut self.unique_builds, "Unique builds")
+                    .on_hover_text(
+                        "Show the distinct build numbers each platform has across all \
+                         searched versions instead of the URLs",
+                    )
+                    .changed()
+                {
+                    self.counts_only &= !self.unique_builds;
+                    self.rerender_found();
+                }
+                if self.reports_pending.load(Ordering::Relaxed) > 0 {
+                    ui.label(
+                        egui::RichText::new("reporting…")
+                            .small()
+                            .color(egui::Color32::GRAY),
+                    )
+                    .on_hover_text("Reporting a version missing from versions.json");
+                    ui.ctx().request_repaint_after(Duration::from_millis(250));
+                }
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    for (label, color) in [
+                        ("Error", ERROR_COLOR),
+                        ("Warning", WARNING_COLOR),
+                        ("URL", URL_COLOR),
+                        ("Platform", HEADER_COLOR),
+                    ] {
+                        ui.label(egui::RichText::new(label).small().color(color));
+                    }
+                });
+            });
+
+            if !self.rejected_versions.is_empty() {
+                self.rejected_versions_summary(ui);
+            }
 
             let available_height = ui.available_height() - 10.0;
 
@@ -1055,8 +3943,16 @@ impl eframe::App for SpotifyFinderApp {
                         .max_height(available_height)
                         .show(ui, |ui| {
                             let mut read_only: &str = &self.displayed_results;
+                            let mut layouter = |ui: &egui::Ui, text: &str, wrap_width: f32| {
+                                let font_id = egui::TextStyle::Monospace.resolve(ui.style());
+                                let mut job =
+                                    results_layout_job(text, font_id, ui.visuals().text_color());
+                                job.wrap.max_width = wrap_width;
+                                ui.fonts(|fonts| fonts.layout_job(job))
+                            };
                             ui.add(
                                 egui::TextEdit::multiline(&mut read_only)
+                                    .layouter(&mut layouter)
                                     .desired_width(f32::INFINITY)
                                     .desired_rows(20)
                                     .font(egui::TextStyle::Monospace)
@@ -1069,20 +3965,95 @@ impl eframe::App for SpotifyFinderApp {
 }
 
 fn main() -> Result<(), eframe::Error> {
+    // Quiet unless RUST_LOG asks for more, e.g. RUST_LOG=loaderspot_core=debug.
+    env_logger::Builder::new()
+        .filter_level(log::LevelFilter::Warn)
+        .parse_default_env()
+        .init();
+
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([750.0, 650.0])
             .with_min_inner_size([750.0, 650.0])
-            .with_resizable(false)
-            .with_maximize_button(false)
-            .with_title("LoaderSpot"),
-        centered: true,
+            .with_title("LoaderSpot")
+            .with_drag_and_drop(true),
+        // eframe restores the last size and position from its settings file;
+        // centering would override that position, so only center the first run.
+        persist_window: true,
+        centered: !has_saved_settings(),
         ..Default::default()
     };
 
     eframe::run_native(
-        "LoaderSpot",
+        APP_ID,
         options,
-        Box::new(|_cc| Ok(Box::new(SpotifyFinderApp::default()))),
+        Box::new(|cc| Ok(Box::new(SpotifyFinderApp::new(cc)))),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(ranges: &[(i32, i32)], step: u32) -> SearchParams {
+        SearchParams {
+            versions: Vec::new(),
+            platforms: vec![Platform::WinX86, Platform::WinX64],
+            ranges: ranges.to_vec(),
+            ladder: None,
+            build_hints: false,
+            max_gap: None,
+            try_dmg: false,
+            step,
+        }
+    }
+
+    #[test]
+    fn search_work_counts_every_build_of_every_platform() {
+        let versions = [
+            "1.2.53.1.gaaaaaaaa".to_string(),
+            "1.2.54.1.gbbbbbbbb".to_string(),
+        ];
+        let work = SearchWork::new(&params(&[(0, 9), (20, 29)], 2), &versions, false, false);
+
+        // x86 stops after 1.2.53.
+        assert_eq!(work.per_platform[&Platform::WinX86], 10);
+        assert_eq!(work.per_platform[&Platform::WinX64], 20);
+        assert_eq!(work.total(), 30);
+        assert!(work.exact);
+        assert!(processed_all(work.total(), work.exact, 30));
+        assert!(!processed_all(work.total(), work.exact, 29));
+        assert!(!processed_all(work.total(), work.exact, 31));
+    }
+
+    #[test]
+    fn search_work_is_an_upper_bound_when_builds_may_be_skipped() {
+        let versions = ["1.2.54.1.gbbbbbbbb".to_string()];
+        let ladder = SearchParams {
+            ladder: Some(LadderOptions::default()),
+            ..params(&[(0, 9)], 1)
+        };
+        let ladder_work = SearchWork::new(&ladder, &versions, false, false);
+        assert_eq!(ladder_work.total(), LadderOptions::default().max_builds());
+
+        let gap = SearchParams {
+            max_gap: Some(50),
+            ..params(&[(0, 9)], 1)
+        };
+        let hints = SearchParams {
+            build_hints: true,
+            ..params(&[(0, 9)], 1)
+        };
+        for work in [
+            ladder_work,
+            SearchWork::new(&gap, &versions, false, false),
+            SearchWork::new(&hints, &versions, false, false),
+            SearchWork::new(&params(&[(0, 9)], 1), &versions, true, false),
+            SearchWork::new(&params(&[(0, 9)], 1), &versions, false, true),
+        ] {
+            assert!(!work.exact);
+            assert!(processed_all(work.total(), work.exact, 0));
+            assert!(!processed_all(work.total(), work.exact, work.total() + 1));
+        }
+    }
+}
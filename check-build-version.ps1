@@ -1,9 +1,91 @@
 param (
     [string]$versions,
     [string]$source,
-    [string]$googleAppsUrl
+    [string]$googleAppsUrl,
+    # Используется, когда -source не передан
+    [string]$sourceDefault,
+    # Секунд на ответ GAS; Apps Script бывает медленным
+    [int]$gasTimeout = 60,
+    # en или ru; без него язык берётся из LANG, по умолчанию en
+    [string]$lang,
+    # Скрывает сообщения о ходе работы, ошибки выводятся всегда
+    [switch]$quiet
 )
 
+# Попыток отправки в GAS; пауза между ними удваивается, начиная с 2 секунд
+$gasAttempts = 3
+
+# Русские строки - прежние сообщения скрипта
+$messages = @{
+    en = @{
+        BuildType        = "build type: {0}"
+        BuildNotFound    = "Build not found"
+        ReadFailed       = "Failed to read the file: {0}"
+        Downloading      = "Downloading {0}..."
+        Saved            = "File saved to {0}"
+        Unpacking        = "Unpacking to {0}"
+        NoSpotifyFiles   = "Neither Spotify.dll nor Spotify.exe was found in {0}"
+        DownloadFailed   = "Failed to download or unpack the installer: {0}"
+        MissingParams    = "One or more required parameters (versions, googleAppsUrl) were not provided."
+        Source           = "Source: {0} (from {1})"
+        HostName         = "host name"
+        Sending          = "Sending data to GAS..."
+        GasResponse      = "GAS response: {0}"
+        GasBadStatus     = "Failed to send to GAS. Status: {0}. Response: {1}"
+        GasCritical      = "Critical error while sending to GAS: {0}"
+        GasRetry         = "{0} (attempt {1} of {2}, retrying in {3} s)"
+        GasGaveUp        = "{0} (attempts: {1})"
+    }
+    ru = @{
+        BuildType        = "build type: {0}"
+        BuildNotFound    = "Билд не найден"
+        ReadFailed       = "Ошибка при чтении файла: {0}"
+        Downloading      = "Скачивание файла из {0}..."
+        Saved            = "Файл сохранен в {0}"
+        Unpacking        = "Распаковка файла в {0}"
+        NoSpotifyFiles   = "Файлы Spotify.dll и Spotify.exe не найдены в {0}"
+        DownloadFailed   = "Произошла ошибка при скачивании или распаковке: {0}"
+        MissingParams    = "Один или несколько обязательных параметров (versions, googleAppsUrl) не предоставлены."
+        Source           = "Источник: {0} (из {1})"
+        HostName         = "имя хоста"
+        Sending          = "Отправка данных на GAS..."
+        GasResponse      = "Ответ от GAS: {0}"
+        GasBadStatus     = "Ошибка при отправке в GAS. Статус: {0}. Ответ: {1}"
+        GasCritical      = "Критическая ошибка при отправке в GAS: {0}"
+        GasRetry         = "{0} (попытка {1} из {2}, повтор через {3} с)"
+        GasGaveUp        = "{0} (попыток: {1})"
+    }
+}
+
+# -lang важнее LANG; из LANG вроде ru_RU.UTF-8 берутся первые две буквы
+if ([string]::IsNullOrEmpty($lang) -and -not [string]::IsNullOrEmpty($env:LANG)) {
+    $lang = $env:LANG.Substring(0, [Math]::Min(2, $env:LANG.Length))
+}
+$lang = "$lang".ToLowerInvariant()
+if (-not $messages.ContainsKey($lang)) {
+    $lang = "en"
+}
+
+function Get-Message {
+    param (
+        [string]$Key,
+        [object[]]$Arguments = @()
+    )
+
+    return $messages[$lang][$Key] -f $Arguments
+}
+
+# Сообщения о ходе работы; -quiet их скрывает
+function Write-Status {
+    param (
+        [string]$Message
+    )
+
+    if (-not $quiet) {
+        Write-Host $Message
+    }
+}
+
 function Find-BuildInfo {
     [CmdletBinding()]
     param (
@@ -22,18 +104,18 @@ function Find-BuildInfo {
                 $match = [regex]::Match($line, $regex)
                 if ($match.Success) {
                     $buildType = $match.Groups[1].Value
-                    Write-Host "build type: $buildType"
+                    Write-Status (Get-Message BuildType $buildType)
                     return $buildType
                 }
             }
 
             if (-not $found) {
-                Write-Host "Билд не найден"
+                Write-Status (Get-Message BuildNotFound)
                 return $false
             }
         }
         catch {
-            Write-Error "Ошибка при чтении файла: $_"
+            Write-Error (Get-Message ReadFailed $_)
             return $false
         }
     }
@@ -57,11 +139,11 @@ function Get-BuildTypeFromUrl {
     }
 
     try {
-        Write-Host "Скачивание файла из $Url..."
+        Write-Status (Get-Message Downloading $Url)
         Invoke-WebRequest -Uri $Url -OutFile $exePath
-        Write-Host "Файл сохранен в $exePath"
+        Write-Status (Get-Message Saved $exePath)
 
-        Write-Host "Распаковка файла в $destinationPath"
+        Write-Status (Get-Message Unpacking $destinationPath)
         Start-Process -Wait -FilePath $exePath -ArgumentList "/extract `"$destinationPath`""
 
         $dllPath = Join-Path $destinationPath "Spotify.dll"
@@ -73,22 +155,44 @@ function Get-BuildTypeFromUrl {
             return Find-BuildInfo -Path $exePathForAnalysis
         }
         else {
-            Write-Error "Файлы Spotify.dll и Spotify.exe не найдены в $destinationPath"
+            Write-Error (Get-Message NoSpotifyFiles $destinationPath)
             return $false
         }
     }
     catch {
-        Write-Error "Произошла ошибка при скачивании или распаковке: $_"
+        Write-Error (Get-Message DownloadFailed $_)
         return $false
     }
 }
 
-if ([string]::IsNullOrEmpty($versions) -or [string]::IsNullOrEmpty($source) -or [string]::IsNullOrEmpty($googleAppsUrl)) {
-    Write-Error "Один или несколько обязательных параметров (versions, source, googleAppsUrl) не предоставлены."
+if ([string]::IsNullOrEmpty($versions) -or [string]::IsNullOrEmpty($googleAppsUrl)) {
+    Write-Error (Get-Message MissingParams)
     exit 1
 }
 
+# -source важнее всего; без него берётся -sourceDefault, затем LOADERSPOT_SOURCE_DEFAULT, затем имя хоста
+if (-not [string]::IsNullOrEmpty($source)) {
+    $sourceOrigin = "-source"
+} elseif (-not [string]::IsNullOrEmpty($sourceDefault)) {
+    $source = $sourceDefault
+    $sourceOrigin = "-sourceDefault"
+} elseif (-not [string]::IsNullOrEmpty($env:LOADERSPOT_SOURCE_DEFAULT)) {
+    $source = $env:LOADERSPOT_SOURCE_DEFAULT
+    $sourceOrigin = "LOADERSPOT_SOURCE_DEFAULT"
+} else {
+    $source = [System.Net.Dns]::GetHostName()
+    $sourceOrigin = Get-Message HostName
+}
+Write-Status (Get-Message Source $source, $sourceOrigin)
+
+# loaderspot_cli оборачивает результаты в {"schema_version", "versions", "results": [...]};
+# в GAS уходит объект одной версии, как и раньше
 $versionsObj = $versions | ConvertFrom-Json
+if ($null -ne $versionsObj.schema_version) {
+    $versionsObj = @($versionsObj.results)[0]
+}
+# details (размер и Last-Modified) в GAS не передаём: там каждый ключ, кроме version, считается URL платформы
+$versionsObj.PSObject.Properties.Remove("details")
 $win64Url = $versionsObj.WIN64
 $buildType = $false
 
@@ -106,20 +210,35 @@ $versionsObj | Add-Member -NotePropertyName "source" -NotePropertyValue $source
 
 $finalJson = $versionsObj | ConvertTo-Json -Compress
 
-Write-Host "Отправка данных на GAS..."
+Write-Status (Get-Message Sending)
 
-try {
-    $response = Invoke-WebRequest -Uri $googleAppsUrl `
-        -Method POST `
-        -ContentType "application/json" `
-        -Body $finalJson `
-        -UseBasicParsing -ErrorAction Stop
-    
-    if ($response.StatusCode -eq 200) {
-        Write-Host "Ответ от GAS: $($response.Content)"
+$delay = 2
+for ($attempt = 1; $attempt -le $gasAttempts; $attempt++) {
+    try {
+        $response = Invoke-WebRequest -Uri $googleAppsUrl `
+            -Method POST `
+            -ContentType "application/json" `
+            -Body $finalJson `
+            -TimeoutSec $gasTimeout `
+            -UseBasicParsing -ErrorAction Stop
+
+        if ($response.StatusCode -eq 200) {
+            Write-Status (Get-Message GasResponse $response.Content)
+            exit 0
+        }
+        $problem = Get-Message GasBadStatus $response.StatusCode, $response.Content
+    } catch {
+        $problem = Get-Message GasCritical $_
+    }
+
+    if ($attempt -lt $gasAttempts) {
+        if (-not $quiet) {
+            Write-Warning (Get-Message GasRetry $problem, $attempt, $gasAttempts, $delay)
+        }
+        Start-Sleep -Seconds $delay
+        $delay *= 2
     } else {
-        Write-Error "Ошибка при отправке в GAS. Статус: $($response.StatusCode). Ответ: $($response.Content)"
+        Write-Error (Get-Message GasGaveUp $problem, $gasAttempts)
     }
-} catch {
-    Write-Error "Критическая ошибка при отправке в GAS: $_"
-}
\ No newline at end of file
+}
+exit 1